@@ -1,24 +1,30 @@
 use std::{
-    net::{IpAddr, Ipv4Addr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
     path::PathBuf,
     sync::Arc,
 };
 
 use clap::{Parser, Subcommand};
+use libp2p::PeerId;
 use ream_manager::config::ManagerConfig;
 use ream_network_spec::{cli::network_parser, networks::NetworkSpec};
 use ream_node::version::FULL_VERSION;
-use ream_p2p::bootnodes::Bootnodes;
+use ream_p2p::{bootnodes::Bootnodes, peer_manager::static_peer::StaticPeer};
+use ream_storage::db::DEFAULT_SLOTS_PER_STATE_SNAPSHOT;
+use ream_syncer::backfill::BackfillTarget;
 use url::Url;
 
+const DEFAULT_BACKFILL_TARGET: BackfillTarget = BackfillTarget::Genesis;
 const DEFAULT_DISABLE_DISCOVERY: bool = false;
 const DEFAULT_DISCOVERY_PORT: u16 = 9000;
 const DEFAULT_HTTP_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 const DEFAULT_HTTP_ALLOW_ORIGIN: bool = false;
 const DEFAULT_HTTP_PORT: u16 = 5052;
+const DEFAULT_MAX_PEERS: usize = 55;
 const DEFAULT_NETWORK: &str = "mainnet";
 const DEFAULT_SOCKET_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
 const DEFAULT_SOCKET_PORT: u16 = 9000;
+const DEFAULT_TARGET_PEERS: usize = 50;
 
 #[derive(Debug, Parser)]
 #[command(author, version = FULL_VERSION, about, long_about = None)]
@@ -66,6 +72,30 @@ pub struct NodeConfig {
     #[arg(long, help = "Discovery 5 listening port (UDP)", default_value_t = DEFAULT_DISCOVERY_PORT)]
     pub discovery_port: u16,
 
+    #[arg(long, help = "Set P2P QUIC port (UDP), QUIC is disabled if not set")]
+    pub quic_port: Option<u16>,
+
+    #[arg(
+        long,
+        help = "Set P2P IPv6 socket address to listen on in addition to the IPv4 socket address"
+    )]
+    pub socket_address6: Option<Ipv6Addr>,
+
+    #[arg(
+        long,
+        help = "Set P2P IPv6 socket port (TCP), defaults to --socket-port"
+    )]
+    pub socket_port6: Option<u16>,
+
+    #[arg(
+        long,
+        help = "Discovery 5 IPv6 listening port (UDP), defaults to --discovery-port"
+    )]
+    pub discovery_port6: Option<u16>,
+
+    #[arg(long, help = "Set P2P IPv6 QUIC port (UDP), defaults to --quic-port")]
+    pub quic_port6: Option<u16>,
+
     #[arg(long, help = "Disable Discv5", default_value_t = DEFAULT_DISABLE_DISCOVERY)]
     pub disable_discovery: bool,
 
@@ -108,6 +138,58 @@ pub struct NodeConfig {
         requires = "execution_endpoint"
     )]
     pub execution_jwt_secret: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "How far back to backfill blocks after checkpoint sync: genesis, weak-subjectivity or blob-retention",
+        default_value_t = DEFAULT_BACKFILL_TARGET
+    )]
+    pub backfill_target: BackfillTarget,
+
+    #[arg(
+        long,
+        help = "Path to a secp256k1 private key file (raw or hex encoded) to use as the node's network identity. Defaults to a key stored in the data directory."
+    )]
+    pub private_key_file: Option<PathBuf>,
+
+    #[arg(long, help = "Number of peers to maintain connections to", default_value_t = DEFAULT_TARGET_PEERS)]
+    pub target_peers: usize,
+
+    #[arg(
+        long,
+        help = "Maximum number of connected peers, inbound connections beyond this are refused",
+        default_value_t = DEFAULT_MAX_PEERS
+    )]
+    pub max_peers: usize,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "One or more comma-delimited ENRs or multiaddrs ending in /p2p/<peer id> of peers to always stay connected to"
+    )]
+    pub static_peers: Vec<StaticPeer>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "One or more comma-delimited peer ids of peers exempt from scoring and peer limits"
+    )]
+    pub trusted_peers: Vec<PeerId>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "One or more comma-delimited ENRs or multiaddrs ending in /p2p/<peer id> of gossipsub direct peers, which are kept connected like static peers"
+    )]
+    pub direct_peers: Vec<StaticPeer>,
+
+    #[arg(
+        long,
+        help = "Number of slots between the finalized state snapshots kept in the cold store. Smaller values use more disk space but make historical states faster to load, e.g. for archive nodes.",
+        default_value_t = DEFAULT_SLOTS_PER_STATE_SNAPSHOT,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub slots_per_state_snapshot: u64,
 }
 
 impl From<NodeConfig> for ManagerConfig {
@@ -119,6 +201,11 @@ impl From<NodeConfig> for ManagerConfig {
             socket_address: config.socket_address,
             socket_port: config.socket_port,
             discovery_port: config.discovery_port,
+            quic_port: config.quic_port,
+            socket_address6: config.socket_address6,
+            socket_port6: config.socket_port6,
+            discovery_port6: config.discovery_port6,
+            quic_port6: config.quic_port6,
             disable_discovery: config.disable_discovery,
             data_dir: config.data_dir,
             ephemeral: config.ephemeral,
@@ -127,6 +214,13 @@ impl From<NodeConfig> for ManagerConfig {
             purge_db: config.purge_db,
             execution_endpoint: config.execution_endpoint,
             execution_jwt_secret: config.execution_jwt_secret,
+            backfill_target: config.backfill_target,
+            private_key_file: config.private_key_file,
+            target_peers: config.target_peers,
+            max_peers: config.max_peers,
+            static_peers: config.static_peers,
+            trusted_peers: config.trusted_peers,
+            direct_peers: config.direct_peers,
         }
     }
 }
@@ -150,6 +244,10 @@ mod tests {
             "9001",
             "--discovery-port",
             "9002",
+            "--quic-port",
+            "9003",
+            "--socket-address6",
+            "::1",
         ]);
 
         match cli.command {
@@ -162,6 +260,13 @@ mod tests {
                 );
                 assert_eq!(config.socket_port, 9001);
                 assert_eq!(config.discovery_port, 9002);
+                assert_eq!(config.quic_port, Some(9003));
+                assert_eq!(config.socket_address6, Some(Ipv6Addr::LOCALHOST));
+                assert_eq!(config.socket_port6, None);
+                assert_eq!(
+                    config.slots_per_state_snapshot,
+                    DEFAULT_SLOTS_PER_STATE_SNAPSHOT
+                );
             }
         }
     }
@@ -47,7 +47,8 @@ async fn main() {
                 reset_db(ream_dir.clone()).expect("Unable to delete database");
             }
 
-            let ream_db = ReamDB::new(ream_dir).expect("unable to init Ream Database");
+            let ream_db = ReamDB::new(ream_dir.clone(), config.slots_per_state_snapshot)
+                .expect("unable to init Ream Database");
 
             info!("ream database initialized ");
 
@@ -74,9 +75,10 @@ async fn main() {
 
             let http_future = start_server(server_config, ream_db.clone());
 
-            let network_manager = ManagerService::new(async_executor, config.into(), ream_db)
-                .await
-                .expect("Failed to create manager service");
+            let network_manager =
+                ManagerService::new(async_executor, config.into(), ream_db, ream_dir)
+                    .await
+                    .expect("Failed to create manager service");
 
             let network_future = main_executor.spawn(async move {
                 network_manager.start().await;
@@ -1,25 +1,163 @@
-use ream_consensus::electra::beacon_block::SignedBeaconBlock;
+use anyhow::anyhow;
+use parking_lot::RwLock;
+use ream_consensus::{
+    attestation::Attestation,
+    attester_slashing::AttesterSlashing,
+    blob_sidecar::{BlobIdentifier, BlobSidecar},
+    constants::SLOTS_PER_EPOCH,
+    electra::beacon_block::SignedBeaconBlock,
+    execution_engine::rpc_types::get_blobs::BlobAndProofV1,
+};
 use ream_execution_engine::ExecutionEngine;
-use ream_fork_choice::{handlers::on_block, store::Store};
-use ream_storage::db::ReamDB;
+use ream_fork_choice::{
+    handlers::{on_attestation, on_attester_slashing, on_block, on_tick},
+    store::Store,
+};
+use ream_light_client::{
+    bootstrap::LightClientBootstrap, cache::LightClientCache, update::LightClientUpdate,
+};
+use ream_storage::{
+    db::ReamDB,
+    tables::{Field, Table},
+};
+use tokio::sync::Mutex;
+use tracing::trace;
+use tree_hash::TreeHash;
+
+/// How far behind the current slot imported blocks still produce light client updates.
+const LIGHT_CLIENT_UPDATE_SLOTS: u64 = SLOTS_PER_EPOCH;
 
 /// BeaconChain is the main struct which manages the nodes local beacon chain.
 pub struct BeaconChain {
-    pub store: Store,
+    pub store: Mutex<Store>,
     pub execution_engine: Option<ExecutionEngine>,
+    /// Light client data derived from imported blocks, served to light clients over req/resp.
+    pub light_client_cache: RwLock<LightClientCache>,
 }
 
 impl BeaconChain {
     /// Creates a new instance of `BeaconChain`.
     pub fn new(db: ReamDB, execution_engine: Option<ExecutionEngine>) -> Self {
         Self {
-            store: Store::new(db),
+            store: Mutex::new(Store::new(db)),
             execution_engine,
+            light_client_cache: RwLock::new(LightClientCache::default()),
+        }
+    }
+
+    pub async fn process_block(&self, signed_block: SignedBeaconBlock) -> anyhow::Result<()> {
+        let mut store = self.store.lock().await;
+        on_block(&mut store, &signed_block, &self.execution_engine).await?;
+        // Light clients follow the head, so the updates of blocks imported far behind it, e.g. by
+        // range sync, aren't worth loading their attested states for
+        if signed_block.message.slot + LIGHT_CLIENT_UPDATE_SLOTS >= store.get_current_slot()? {
+            match light_client_update(&store, &signed_block) {
+                Ok(update) => self.light_client_cache.write().insert(update),
+                Err(err) => trace!("No light client update for block: {err:?}"),
+            }
+        }
+        if let Err(err) = self.cache_finalized_bootstrap(&store).await {
+            trace!("No light client bootstrap for the finalized checkpoint: {err:?}");
+        }
+        Ok(())
+    }
+
+    /// Creates the light client bootstrap of the finalized checkpoint once it changes, so
+    /// bootstrap requests never have to rebuild a state.
+    async fn cache_finalized_bootstrap(&self, store: &Store) -> anyhow::Result<()> {
+        let finalized_root = store.db.finalized_checkpoint_provider().get()?.root;
+        if self
+            .light_client_cache
+            .read()
+            .bootstrap(finalized_root)
+            .is_some()
+        {
+            return Ok(());
         }
+
+        let finalized_block = store
+            .db
+            .beacon_block_provider()
+            .get(finalized_root)?
+            .ok_or_else(|| anyhow!("Missing finalized block {finalized_root}"))?;
+        let finalized_state = store
+            .db
+            .get_state(finalized_root)
+            .await?
+            .ok_or_else(|| anyhow!("Missing finalized state {finalized_root}"))?;
+        let bootstrap = LightClientBootstrap::new(&finalized_state, &finalized_block)?;
+        self.light_client_cache
+            .write()
+            .insert_bootstrap(finalized_root, bootstrap);
+        Ok(())
     }
 
-    pub async fn process_block(&mut self, signed_block: SignedBeaconBlock) -> anyhow::Result<()> {
-        on_block(&mut self.store, &signed_block, &self.execution_engine).await?;
+    /// Stores a validated blob sidecar, so the data of its block is available when the block is
+    /// imported.
+    pub async fn process_blob_sidecar(&self, blob_sidecar: BlobSidecar) -> anyhow::Result<()> {
+        let store = self.store.lock().await;
+        store.db.blobs_and_proofs_provider().insert(
+            BlobIdentifier::new(
+                blob_sidecar.signed_block_header.message.tree_hash_root(),
+                blob_sidecar.index,
+            ),
+            BlobAndProofV1 {
+                blob: blob_sidecar.blob,
+                proof: blob_sidecar.kzg_proof,
+            },
+        )?;
         Ok(())
     }
+
+    pub async fn process_attestation(&self, attestation: Attestation) -> anyhow::Result<()> {
+        let mut store = self.store.lock().await;
+        on_attestation(&mut store, attestation, false)?;
+        Ok(())
+    }
+
+    pub async fn process_attester_slashing(
+        &self,
+        attester_slashing: AttesterSlashing,
+    ) -> anyhow::Result<()> {
+        let mut store = self.store.lock().await;
+        on_attester_slashing(&mut store, attester_slashing)?;
+        Ok(())
+    }
+
+    pub async fn process_tick(&self, time: u64) -> anyhow::Result<()> {
+        let mut store = self.store.lock().await;
+        on_tick(&mut store, time)?;
+        Ok(())
+    }
+}
+
+/// Creates the light client update proven by the sync aggregate of an imported block.
+fn light_client_update(
+    store: &Store,
+    signed_block: &SignedBeaconBlock,
+) -> anyhow::Result<LightClientUpdate> {
+    let attested_root = signed_block.message.parent_root;
+    let attested_block = store
+        .db
+        .beacon_block_provider()
+        .get(attested_root)?
+        .ok_or_else(|| anyhow!("Missing attested block {attested_root}"))?;
+    let attested_state = store
+        .db
+        .beacon_state_provider()
+        .get(attested_root)?
+        .ok_or_else(|| anyhow!("Missing attested state {attested_root}"))?;
+    let finalized_root = attested_state.finalized_checkpoint.root;
+    let finalized_block = store
+        .db
+        .beacon_block_provider()
+        .get(finalized_root)?
+        .ok_or_else(|| anyhow!("Missing finalized block {finalized_root}"))?;
+
+    LightClientUpdate::new(
+        signed_block,
+        &attested_state,
+        &attested_block,
+        &finalized_block,
+    )
 }
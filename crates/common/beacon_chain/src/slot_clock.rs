@@ -0,0 +1,394 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+use ream_consensus::constants::{INTERVALS_PER_SLOT, SECONDS_PER_SLOT};
+use tokio::sync::broadcast;
+use tracing::{error, trace};
+
+use crate::beacon_chain::BeaconChain;
+
+/// Number of slot events buffered for slow subscribers before they start lagging.
+const SLOT_EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Source of the current wall clock time, abstracted so the slot clock can be driven manually in
+/// tests.
+pub trait Clock: Send + Sync + 'static {
+    /// Returns the time elapsed since the unix epoch.
+    fn now(&self) -> Duration;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+/// A clock which only moves when told to.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new(now: Duration) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    pub fn set(&self, now: Duration) {
+        *self.now.lock() = now;
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock() += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotEvent {
+    pub slot: u64,
+    pub interval: u64,
+}
+
+/// Maps wall clock time onto slots and intervals relative to genesis.
+#[derive(Debug, Clone)]
+pub struct SlotClock<C> {
+    genesis_time: u64,
+    clock: C,
+}
+
+impl<C: Clock> SlotClock<C> {
+    pub fn new(genesis_time: u64, clock: C) -> Self {
+        Self {
+            genesis_time,
+            clock,
+        }
+    }
+
+    pub fn genesis_time(&self) -> u64 {
+        self.genesis_time
+    }
+
+    /// Returns the current time in seconds, as used by the fork choice store.
+    pub fn now_seconds(&self) -> u64 {
+        self.clock.now().as_secs()
+    }
+
+    /// Returns the current slot and interval, or `None` before genesis.
+    pub fn now(&self) -> Option<SlotEvent> {
+        let since_genesis = self.clock.now().checked_sub(self.genesis())?;
+        let seconds_per_interval = SECONDS_PER_SLOT / INTERVALS_PER_SLOT;
+
+        Some(SlotEvent {
+            slot: since_genesis.as_secs() / SECONDS_PER_SLOT,
+            interval: (since_genesis.as_secs() % SECONDS_PER_SLOT) / seconds_per_interval,
+        })
+    }
+
+    /// Returns the earliest and latest slot the current time can be in if clocks are off by up to
+    /// `disparity`.
+    pub fn slot_range(&self, disparity: Duration) -> (u64, u64) {
+        let now = self.clock.now();
+        let slot_at =
+            |time: Duration| time.saturating_sub(self.genesis()).as_secs() / SECONDS_PER_SLOT;
+
+        (
+            slot_at(now.saturating_sub(disparity)),
+            slot_at(now + disparity),
+        )
+    }
+
+    /// Returns how long until the start of the next interval, or until genesis if it hasn't
+    /// happened yet.
+    pub fn duration_to_next_interval(&self) -> Duration {
+        let now = self.clock.now();
+        let genesis = self.genesis();
+        if now < genesis {
+            return genesis - now;
+        }
+
+        let interval_duration = Duration::from_secs(SECONDS_PER_SLOT / INTERVALS_PER_SLOT);
+        let since_genesis = now - genesis;
+        let elapsed_in_interval =
+            Duration::from_nanos((since_genesis.as_nanos() % interval_duration.as_nanos()) as u64);
+
+        interval_duration - elapsed_in_interval
+    }
+
+    fn genesis(&self) -> Duration {
+        Duration::from_secs(self.genesis_time)
+    }
+}
+
+/// Ticks the fork choice store at every interval and publishes a [`SlotEvent`] for each one.
+pub struct SlotClockService<C> {
+    beacon_chain: Arc<BeaconChain>,
+    slot_clock: SlotClock<C>,
+    sender: broadcast::Sender<SlotEvent>,
+}
+
+impl<C: Clock> SlotClockService<C> {
+    pub fn new(beacon_chain: Arc<BeaconChain>, slot_clock: SlotClock<C>) -> Self {
+        let (sender, _) = broadcast::channel(SLOT_EVENT_CHANNEL_CAPACITY);
+        Self {
+            beacon_chain,
+            slot_clock,
+            sender,
+        }
+    }
+
+    /// Subscribes to the slot/interval events published on every tick.
+    pub fn subscribe(&self) -> broadcast::Receiver<SlotEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn sender(&self) -> broadcast::Sender<SlotEvent> {
+        self.sender.clone()
+    }
+
+    /// Ticks the store to the current time and publishes the current slot and interval. Does
+    /// nothing before genesis.
+    pub async fn tick(&self) -> anyhow::Result<Option<SlotEvent>> {
+        let Some(slot_event) = self.slot_clock.now() else {
+            return Ok(None);
+        };
+
+        self.beacon_chain
+            .process_tick(self.slot_clock.now_seconds())
+            .await?;
+
+        trace!(
+            "Slot clock tick: slot: {}, interval: {}",
+            slot_event.slot, slot_event.interval
+        );
+
+        // Sending only fails when there are no subscribers, which is fine
+        let _ = self.sender.send(slot_event);
+
+        Ok(Some(slot_event))
+    }
+
+    pub async fn start(self) {
+        loop {
+            if let Err(err) = self.tick().await {
+                error!("Failed to tick fork choice store: {err:?}");
+            }
+
+            tokio::time::sleep(self.slot_clock.duration_to_next_interval()).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_fork_choice::store::get_forkchoice_store;
+    use ream_storage::{
+        db::ReamDB,
+        tables::{Field, Table},
+        test_utils::import_valid_test_chain,
+    };
+    use tokio::{sync::broadcast::error::RecvError, time::timeout};
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    const GENESIS_TIME: u64 = 1_606_824_023;
+
+    /// A slot clock service over a store anchored at a genesis at `GENESIS_TIME`, with the clock
+    /// at `now`.
+    async fn test_service(now: Duration) -> (SlotClockService<ManualClock>, ManualClock, ReamDB) {
+        let chain_db = ReamDB::in_memory();
+        let genesis_root = import_valid_test_chain(&chain_db, 0).await[0];
+        let mut genesis_block = chain_db
+            .beacon_block_provider()
+            .get(genesis_root)
+            .unwrap()
+            .unwrap();
+        let mut genesis_state = chain_db
+            .beacon_state_provider()
+            .get(genesis_root)
+            .unwrap()
+            .unwrap();
+        genesis_state.genesis_time = GENESIS_TIME;
+        genesis_block.message.state_root = genesis_state.tree_hash_root();
+
+        let db = ReamDB::in_memory();
+        get_forkchoice_store(genesis_state, genesis_block.message, db.clone()).unwrap();
+        let clock = ManualClock::new(now);
+        let service = SlotClockService::new(
+            Arc::new(BeaconChain::new(db.clone(), None)),
+            SlotClock::new(GENESIS_TIME, clock.clone()),
+        );
+        (service, clock, db)
+    }
+
+    /// Receives the first event which isn't `previous`, as ticks within the same interval repeat
+    /// it.
+    async fn next_event(
+        events: &mut broadcast::Receiver<SlotEvent>,
+        previous: Option<SlotEvent>,
+    ) -> SlotEvent {
+        timeout(Duration::from_secs(5), async {
+            loop {
+                match events.recv().await {
+                    Ok(event) if Some(event) == previous => {}
+                    Ok(event) => return event,
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => panic!("Slot event channel closed"),
+                }
+            }
+        })
+        .await
+        .expect("No slot event received")
+    }
+
+    #[tokio::test]
+    async fn test_slot_clock_service_tick() {
+        let (service, clock, db) = test_service(Duration::from_secs(GENESIS_TIME - 5)).await;
+        let mut events = service.subscribe();
+
+        // Nothing happens before genesis
+        assert_eq!(service.tick().await.unwrap(), None);
+        assert!(events.try_recv().is_err());
+        assert_eq!(db.time_provider().get().unwrap(), GENESIS_TIME);
+
+        clock.advance(Duration::from_secs(5 + SECONDS_PER_SLOT + 4));
+        let slot_event = SlotEvent {
+            slot: 1,
+            interval: 1,
+        };
+        assert_eq!(service.tick().await.unwrap(), Some(slot_event));
+        assert_eq!(events.try_recv().unwrap(), slot_event);
+        assert_eq!(
+            db.time_provider().get().unwrap(),
+            GENESIS_TIME + SECONDS_PER_SLOT + 4
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slot_clock_service_start() {
+        // Just before the second interval of slot 1, so the service only sleeps for a millisecond
+        // before ticking again
+        let (service, clock, db) = test_service(
+            Duration::from_secs(GENESIS_TIME + SECONDS_PER_SLOT + 4) - Duration::from_millis(1),
+        )
+        .await;
+        let mut events = service.subscribe();
+        tokio::spawn(service.start());
+
+        let slot_event = next_event(&mut events, None).await;
+        assert_eq!(
+            slot_event,
+            SlotEvent {
+                slot: 1,
+                interval: 0
+            }
+        );
+        assert_eq!(
+            db.time_provider().get().unwrap(),
+            GENESIS_TIME + SECONDS_PER_SLOT + 3
+        );
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(
+            next_event(&mut events, Some(slot_event)).await,
+            SlotEvent {
+                slot: 1,
+                interval: 1
+            }
+        );
+        assert_eq!(
+            db.time_provider().get().unwrap(),
+            GENESIS_TIME + SECONDS_PER_SLOT + 4
+        );
+    }
+
+    #[test]
+    fn test_slot_clock_before_genesis() {
+        let clock = ManualClock::new(Duration::from_secs(GENESIS_TIME - 5));
+        let slot_clock = SlotClock::new(GENESIS_TIME, clock);
+
+        assert_eq!(slot_clock.now(), None);
+        assert_eq!(
+            slot_clock.duration_to_next_interval(),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn test_slot_clock_intervals() {
+        let clock = ManualClock::new(Duration::from_secs(GENESIS_TIME));
+        let slot_clock = SlotClock::new(GENESIS_TIME, clock.clone());
+
+        assert_eq!(
+            slot_clock.now(),
+            Some(SlotEvent {
+                slot: 0,
+                interval: 0
+            })
+        );
+        assert_eq!(
+            slot_clock.duration_to_next_interval(),
+            Duration::from_secs(4)
+        );
+
+        clock.advance(Duration::from_millis(5_500));
+        assert_eq!(
+            slot_clock.now(),
+            Some(SlotEvent {
+                slot: 0,
+                interval: 1
+            })
+        );
+        assert_eq!(
+            slot_clock.duration_to_next_interval(),
+            Duration::from_millis(2_500)
+        );
+
+        clock.set(Duration::from_secs(
+            GENESIS_TIME + 10 * SECONDS_PER_SLOT + 8,
+        ));
+        assert_eq!(
+            slot_clock.now(),
+            Some(SlotEvent {
+                slot: 10,
+                interval: 2
+            })
+        );
+        assert_eq!(
+            slot_clock.duration_to_next_interval(),
+            Duration::from_secs(4)
+        );
+    }
+
+    #[test]
+    fn test_slot_clock_slot_range() {
+        let disparity = Duration::from_millis(500);
+        let clock = ManualClock::new(Duration::from_secs(GENESIS_TIME));
+        let slot_clock = SlotClock::new(GENESIS_TIME, clock.clone());
+        assert_eq!(slot_clock.slot_range(disparity), (0, 0));
+
+        clock.set(Duration::from_millis(
+            (GENESIS_TIME + 3 * SECONDS_PER_SLOT) * 1000 - 200,
+        ));
+        assert_eq!(slot_clock.slot_range(disparity), (2, 3));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(slot_clock.slot_range(disparity), (3, 3));
+    }
+}
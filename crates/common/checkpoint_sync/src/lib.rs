@@ -64,8 +64,17 @@ pub async fn initialize_db_from_checkpoint(
     );
     let slot = block.message.slot;
 
+    // Nothing is written until the sync completes, so that a failed sync is retried from scratch
+    // on the next start
+    let batch = db.write_batch();
+
     info!("Fetching blobs...");
-    initialize_blobs_in_db(&checkpoint_sync_url, db.clone(), block.message.block_root()).await?;
+    initialize_blobs_in_db(
+        &checkpoint_sync_url,
+        batch.db().clone(),
+        block.message.block_root(),
+    )
+    .await?;
     info!(
         "Downloaded blobs for block: {}",
         block.message.body.execution_payload.block_number
@@ -81,10 +90,11 @@ pub async fn initialize_db_from_checkpoint(
     ensure!(block.message.slot == state.slot, "Slot mismatch");
 
     ensure!(block.message.state_root == state.state_root());
-    let mut store = get_forkchoice_store(state, block.message, db)?;
+    let mut store = get_forkchoice_store(state, block.message, batch.db().clone())?;
 
     let time = network_spec().min_genesis_time + SECONDS_PER_SLOT * (slot + 1);
     on_tick(&mut store, time)?;
+    batch.commit()?;
     info!("Initial sync complete");
 
     Ok(())
@@ -1065,6 +1065,26 @@ impl BeaconState {
     pub fn process_bls_to_execution_change(
         &mut self,
         signed_address_change: &SignedBLSToExecutionChange,
+    ) -> anyhow::Result<()> {
+        self.verify_bls_to_execution_change(signed_address_change)?;
+
+        let address_change = &signed_address_change.message;
+        let withdrawal_credentials = [
+            ETH1_ADDRESS_WITHDRAWAL_PREFIX,
+            vec![0x00; 11].as_slice(),
+            address_change.to_execution_address.as_slice(),
+        ]
+        .concat();
+        self.validators[address_change.validator_index as usize].withdrawal_credentials =
+            B256::from_slice(&withdrawal_credentials);
+
+        Ok(())
+    }
+
+    /// Checks the conditions of [`Self::process_bls_to_execution_change`] without applying it.
+    pub fn verify_bls_to_execution_change(
+        &self,
+        signed_address_change: &SignedBLSToExecutionChange,
     ) -> anyhow::Result<()> {
         let address_change = &signed_address_change.message;
 
@@ -1093,15 +1113,6 @@ impl BeaconState {
             "BLS Signature verification failed!"
         );
 
-        let withdrawal_credentials = [
-            ETH1_ADDRESS_WITHDRAWAL_PREFIX,
-            vec![0x00; 11].as_slice(),
-            address_change.to_execution_address.as_slice(),
-        ]
-        .concat();
-        self.validators[address_change.validator_index as usize].withdrawal_credentials =
-            B256::from_slice(&withdrawal_credentials);
-
         Ok(())
     }
 
@@ -1113,6 +1124,19 @@ impl BeaconState {
     pub fn process_voluntary_exit(
         &mut self,
         signed_voluntary_exit: &SignedVoluntaryExit,
+    ) -> anyhow::Result<()> {
+        self.verify_voluntary_exit(signed_voluntary_exit)?;
+
+        // Initiate exit
+        self.initiate_validator_exit(signed_voluntary_exit.message.validator_index)?;
+
+        Ok(())
+    }
+
+    /// Checks the conditions of [`Self::process_voluntary_exit`] without applying it.
+    pub fn verify_voluntary_exit(
+        &self,
+        signed_voluntary_exit: &SignedVoluntaryExit,
     ) -> anyhow::Result<()> {
         let voluntary_exit = &signed_voluntary_exit.message;
         let validator_index = voluntary_exit.validator_index as usize;
@@ -1171,9 +1195,6 @@ impl BeaconState {
             "BLS Signature verification failed!"
         );
 
-        // Initiate exit
-        self.initiate_validator_exit(validator_index as u64)?;
-
         Ok(())
     }
 
@@ -1470,6 +1491,20 @@ impl BeaconState {
     pub fn process_proposer_slashing(
         &mut self,
         proposer_slashing: &ProposerSlashing,
+    ) -> anyhow::Result<()> {
+        self.verify_proposer_slashing(proposer_slashing)?;
+
+        // Slash the validator
+        self.slash_validator(
+            proposer_slashing.signed_header_1.message.proposer_index,
+            None,
+        )
+    }
+
+    /// Checks the conditions of [`Self::process_proposer_slashing`] without applying it.
+    pub fn verify_proposer_slashing(
+        &self,
+        proposer_slashing: &ProposerSlashing,
     ) -> anyhow::Result<()> {
         let header_1 = &proposer_slashing.signed_header_1.message;
         let header_2 = &proposer_slashing.signed_header_2.message;
@@ -1518,8 +1553,7 @@ impl BeaconState {
             );
         }
 
-        // Slash the validator
-        self.slash_validator(proposer_index, None)
+        Ok(())
     }
 
     pub fn process_historical_summaries_update(&mut self) -> anyhow::Result<()> {
@@ -1542,6 +1576,19 @@ impl BeaconState {
         &mut self,
         attester_slashing: &AttesterSlashing,
     ) -> anyhow::Result<()> {
+        for index in self.verify_attester_slashing(attester_slashing)? {
+            self.slash_validator(index, None)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks the conditions of [`Self::process_attester_slashing`] without applying it. Returns
+    /// the indices of the validators it slashes.
+    pub fn verify_attester_slashing(
+        &self,
+        attester_slashing: &AttesterSlashing,
+    ) -> anyhow::Result<Vec<u64>> {
         let attestation_1 = &attester_slashing.attestation_1;
         let attestation_2 = &attester_slashing.attestation_2;
 
@@ -1565,19 +1612,17 @@ impl BeaconState {
         let indices_1: HashSet<_> = attestation_1.attesting_indices.iter().cloned().collect();
         let indices_2: HashSet<_> = attestation_2.attesting_indices.iter().cloned().collect();
 
-        let mut slashed_any = false;
-
-        // Find common attesting indices and process slashing
-        for &index in indices_1.intersection(&indices_2).sorted() {
-            if self.validators[index as usize].is_slashable_validator(current_epoch) {
-                self.slash_validator(index, None)?;
-                slashed_any = true;
-            }
-        }
+        // Find common attesting indices which can be slashed
+        let slashed_indices: Vec<u64> = indices_1
+            .intersection(&indices_2)
+            .sorted()
+            .filter(|index| self.validators[**index as usize].is_slashable_validator(current_epoch))
+            .copied()
+            .collect();
 
-        ensure!(slashed_any, "No validator was slashed");
+        ensure!(!slashed_indices.is_empty(), "No validator was slashed");
 
-        Ok(())
+        Ok(slashed_indices)
     }
 
     pub fn process_sync_aggregate(&mut self, sync_aggregate: &SyncAggregate) -> anyhow::Result<()> {
@@ -5,7 +5,7 @@ use tree_hash_derive::TreeHash;
 
 use crate::attestation_data::AttestationData;
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct SingleAttestation {
     pub committee_index: u64,
     pub attester_index: u64,
@@ -86,43 +86,55 @@ pub async fn on_block(
         .state_transition(signed_block, true, execution_engine)
         .await?;
 
+    // The writes of the import are committed together, so that a crash can't leave a partially
+    // imported block behind
+    let batch = store.db.write_batch();
+    let mut batch_store = Store {
+        db: batch.db().clone(),
+    };
+
     // Add new block to the store
-    store
+    batch_store
         .db
         .beacon_block_provider()
         .insert(block_root, signed_block.clone())?;
     // Add new state for this block to the store
-    store
+    batch_store
         .db
         .beacon_state_provider()
         .insert(block_root, state.clone())?;
 
     // Add block timeliness to the store
-    let time_into_slot = (store.db.time_provider().get()?
-        - store.db.genesis_time_provider().get()?)
+    let time_into_slot = (batch_store.db.time_provider().get()?
+        - batch_store.db.genesis_time_provider().get()?)
         % SECONDS_PER_SLOT;
     let is_before_attesting_interval = time_into_slot < SECONDS_PER_SLOT / INTERVALS_PER_SLOT;
-    let is_timely = store.get_current_slot()? == block.slot && is_before_attesting_interval;
-    store
+    let is_timely = batch_store.get_current_slot()? == block.slot && is_before_attesting_interval;
+    batch_store
         .db
         .block_timeliness_provider()
         .insert(block_root, is_timely)?;
 
     // Add proposer score boost if the block is timely and not conflicting with an existing block
-    let is_first_block = store.db.proposer_boost_root_provider().get()? == B256::ZERO;
+    let is_first_block = batch_store.db.proposer_boost_root_provider().get()? == B256::ZERO;
 
     if is_timely && is_first_block {
-        store.db.proposer_boost_root_provider().insert(block_root)?;
+        batch_store
+            .db
+            .proposer_boost_root_provider()
+            .insert(block_root)?;
     }
 
     // Update checkpoints in store if necessary
-    store.update_checkpoints(
+    batch_store.update_checkpoints(
         state.current_justified_checkpoint,
         state.finalized_checkpoint,
     )?;
 
     // Eagerly compute unrealized justification and finality.
-    store.compute_pulled_up_tip(block_root)?;
+    batch_store.compute_pulled_up_tip(block_root)?;
+
+    batch.commit()?;
 
     Ok(())
 }
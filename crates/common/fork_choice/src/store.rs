@@ -204,10 +204,14 @@ impl Store {
         }
 
         // Update finalized checkpoint
-        if finalized_checkpoint.epoch > self.db.finalized_checkpoint_provider().get()?.epoch {
+        let previous_finalized_checkpoint = self.db.finalized_checkpoint_provider().get()?;
+        if finalized_checkpoint.epoch > previous_finalized_checkpoint.epoch {
             self.db
                 .finalized_checkpoint_provider()
                 .insert(finalized_checkpoint)?;
+            self.db
+                .prune_abandoned_forks(previous_finalized_checkpoint, finalized_checkpoint)?;
+            self.db.migrate_to_cold(finalized_checkpoint.root)?;
         };
 
         Ok(())
@@ -310,18 +314,22 @@ impl Store {
 
         let mut attestation_score: u64 = 0;
         for index in unslashed_and_active_indices {
-            if self.db.latest_messages_provider().get(index)?.is_some()
-                && !self
+            let Some(latest_message) = self.db.latest_messages_provider().get(index)? else {
+                continue;
+            };
+            // Latest messages voting for blocks pruned with an abandoned fork carry no weight
+            if !self
+                .db
+                .equivocating_indices_provider()
+                .get()?
+                .contains(&index)
+                && self
                     .db
-                    .equivocating_indices_provider()
-                    .get()?
-                    .contains(&index)
+                    .beacon_block_provider()
+                    .get(latest_message.root)?
+                    .is_some()
                 && self.get_ancestor(
-                    self.db
-                        .latest_messages_provider()
-                        .get(index)?
-                        .ok_or_else(|| anyhow!("latest_messages not found"))?
-                        .root,
+                    latest_message.root,
                     self.db
                         .beacon_block_provider()
                         .get(root)?
@@ -770,33 +778,51 @@ pub fn get_forkchoice_store(
         signature,
     };
 
-    db.time_provider()
+    // The anchor is committed at once, so that the database is never partially initialized
+    let batch = db.write_batch();
+    let batch_db = batch.db();
+    batch_db
+        .time_provider()
         .insert(anchor_state.genesis_time + SECONDS_PER_SLOT * anchor_state.slot)?;
-    db.genesis_time_provider()
+    batch_db
+        .genesis_time_provider()
         .insert(anchor_state.genesis_time)?;
-    db.justified_checkpoint_provider()
+    batch_db
+        .justified_checkpoint_provider()
         .insert(justified_checkpoint)?;
-    db.finalized_checkpoint_provider()
+    batch_db
+        .finalized_checkpoint_provider()
         .insert(finalized_checkpoint)?;
-    db.unrealized_justified_checkpoint_provider()
+    batch_db
+        .unrealized_justified_checkpoint_provider()
         .insert(justified_checkpoint)?;
-    db.unrealized_finalized_checkpoint_provider()
+    batch_db
+        .unrealized_finalized_checkpoint_provider()
         .insert(finalized_checkpoint)?;
-    db.proposer_boost_root_provider()
+    batch_db
+        .proposer_boost_root_provider()
         .insert(proposer_boost_root)?;
-    db.beacon_block_provider()
+    batch_db
+        .beacon_block_provider()
         .insert(anchor_root, signed_anchor_block)?;
-    db.beacon_state_provider()
+    batch_db
+        .beacon_state_provider()
         .insert(anchor_root, anchor_state.clone())?;
-    db.state_root_index_provider()
+    batch_db
+        .state_root_index_provider()
         .insert(anchor_state.tree_hash_root(), anchor_root)?;
-    db.slot_index_provider()
+    batch_db
+        .slot_index_provider()
         .insert(anchor_state.slot, anchor_root)?;
-    db.checkpoint_states_provider()
+    batch_db
+        .checkpoint_states_provider()
         .insert(justified_checkpoint, anchor_state)?;
-    db.unrealized_justifications_provider()
+    batch_db
+        .unrealized_justifications_provider()
         .insert(anchor_root, justified_checkpoint)?;
 
+    batch.commit()?;
+
     Ok(Store { db })
 }
 
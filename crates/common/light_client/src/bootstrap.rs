@@ -4,13 +4,15 @@ use ream_consensus::{
     electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
     sync_committee::SyncCommittee,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
 use ssz_types::{FixedVector, typenum::U5};
 use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
 
 use crate::header::LightClientHeader;
 
-#[derive(Serialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct LightClientBootstrap {
     pub header: LightClientHeader,
     pub current_sync_committee: SyncCommittee,
@@ -0,0 +1,201 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use alloy_primitives::B256;
+
+use crate::{
+    bootstrap::LightClientBootstrap,
+    finality_update::LightClientFinalityUpdate,
+    optimistic_update::LightClientOptimisticUpdate,
+    update::{LightClientUpdate, has_supermajority},
+};
+
+/// How many sync committee periods of best updates are kept.
+pub const MAX_CACHED_SYNC_COMMITTEE_PERIODS: usize = 128;
+
+/// How many finalized checkpoints bootstraps are kept for.
+pub const MAX_CACHED_BOOTSTRAPS: usize = 128;
+
+/// Server side cache of the light client data we serve over req/resp.
+///
+/// https://ethereum.github.io/consensus-specs/specs/altair/light-client/full-node/
+#[derive(Debug, Default)]
+pub struct LightClientCache {
+    /// Best update of each sync committee period.
+    best_updates: BTreeMap<u64, LightClientUpdate>,
+    latest_finality_update: Option<LightClientFinalityUpdate>,
+    latest_optimistic_update: Option<LightClientOptimisticUpdate>,
+    /// Bootstraps of the latest finalized checkpoints by block root, oldest first.
+    bootstraps: VecDeque<(B256, LightClientBootstrap)>,
+}
+
+impl LightClientCache {
+    /// Keeps `update` if it is the best one of its period, and uses it as the latest optimistic
+    /// update if it attests to a newer header. It becomes the latest finality update if it
+    /// finalizes a newer header, or reaches supermajority for the same one.
+    pub fn insert(&mut self, update: LightClientUpdate) {
+        let attested_slot = update.attested_header.beacon.slot;
+        if self
+            .latest_optimistic_update
+            .as_ref()
+            .is_none_or(|latest| latest.attested_header.beacon.slot < attested_slot)
+        {
+            self.latest_optimistic_update = Some(update.to_optimistic_update());
+        }
+        // Following the gossip rules, the finality update only moves to a newer finalized header,
+        // or to one with supermajority participation for the same finalized header
+        // https://ethereum.github.io/consensus-specs/specs/altair/light-client/p2p-interface/#light_client_finality_update
+        let finalized_slot = update.finalized_header.beacon.slot;
+        if self.latest_finality_update.as_ref().is_none_or(|latest| {
+            latest.finalized_header.beacon.slot < finalized_slot
+                || (latest.finalized_header.beacon.slot == finalized_slot
+                    && has_supermajority(&update.sync_aggregate)
+                    && !has_supermajority(&latest.sync_aggregate))
+        }) {
+            self.latest_finality_update = Some(update.to_finality_update());
+        }
+
+        let period = update.sync_committee_period();
+        match self.best_updates.get(&period) {
+            Some(best_update) if !update.is_better_than(best_update) => {}
+            _ => {
+                self.best_updates.insert(period, update);
+            }
+        }
+        while self.best_updates.len() > MAX_CACHED_SYNC_COMMITTEE_PERIODS {
+            self.best_updates.pop_first();
+        }
+    }
+
+    /// Returns the best updates of up to `count` consecutive periods starting at `start_period`,
+    /// stopping at the first period we have no update for.
+    pub fn updates_by_range(&self, start_period: u64, count: u64) -> Vec<LightClientUpdate> {
+        (start_period..start_period.saturating_add(count))
+            .map_while(|period| self.best_updates.get(&period).cloned())
+            .collect()
+    }
+
+    /// Keeps the bootstrap of a newly finalized checkpoint, dropping the oldest ones.
+    pub fn insert_bootstrap(&mut self, block_root: B256, bootstrap: LightClientBootstrap) {
+        if self.bootstrap(block_root).is_some() {
+            return;
+        }
+        self.bootstraps.push_back((block_root, bootstrap));
+        if self.bootstraps.len() > MAX_CACHED_BOOTSTRAPS {
+            self.bootstraps.pop_front();
+        }
+    }
+
+    pub fn bootstrap(&self, block_root: B256) -> Option<&LightClientBootstrap> {
+        self.bootstraps
+            .iter()
+            .find(|(root, _)| *root == block_root)
+            .map(|(_, bootstrap)| bootstrap)
+    }
+
+    pub fn latest_finality_update(&self) -> Option<&LightClientFinalityUpdate> {
+        self.latest_finality_update.as_ref()
+    }
+
+    pub fn latest_optimistic_update(&self) -> Option<&LightClientOptimisticUpdate> {
+        self.latest_optimistic_update.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus::constants::{
+        EPOCHS_PER_SYNC_COMMITTEE_PERIOD, SLOTS_PER_EPOCH, SYNC_COMMITTEE_SIZE,
+    };
+
+    use super::*;
+    use crate::update::tests::test_update;
+
+    const SUPERMAJORITY: usize = SYNC_COMMITTEE_SIZE as usize * 2 / 3 + 1;
+
+    #[test]
+    fn test_latest_finality_update() {
+        let mut cache = LightClientCache::default();
+        cache.insert(test_update(100, 64, 10));
+        assert_eq!(
+            cache
+                .latest_finality_update()
+                .unwrap()
+                .attested_header
+                .beacon
+                .slot,
+            100
+        );
+
+        // A newer attested header with the same finalized header and no supermajority is not
+        // forwarded, unlike for optimistic updates
+        cache.insert(test_update(101, 64, 5));
+        assert_eq!(
+            cache
+                .latest_finality_update()
+                .unwrap()
+                .attested_header
+                .beacon
+                .slot,
+            100
+        );
+        assert_eq!(
+            cache
+                .latest_optimistic_update()
+                .unwrap()
+                .attested_header
+                .beacon
+                .slot,
+            101
+        );
+
+        // Reaching supermajority for the same finalized header replaces it once
+        cache.insert(test_update(102, 64, SUPERMAJORITY));
+        assert_eq!(
+            cache
+                .latest_finality_update()
+                .unwrap()
+                .attested_header
+                .beacon
+                .slot,
+            102
+        );
+        cache.insert(test_update(103, 64, SYNC_COMMITTEE_SIZE as usize));
+        assert_eq!(
+            cache
+                .latest_finality_update()
+                .unwrap()
+                .attested_header
+                .beacon
+                .slot,
+            102
+        );
+
+        // A newer finalized header always replaces it
+        cache.insert(test_update(104, 96, 1));
+        assert_eq!(
+            cache
+                .latest_finality_update()
+                .unwrap()
+                .finalized_header
+                .beacon
+                .slot,
+            96
+        );
+    }
+
+    #[test]
+    fn test_best_updates() {
+        let period_slots = EPOCHS_PER_SYNC_COMMITTEE_PERIOD * SLOTS_PER_EPOCH;
+        let mut cache = LightClientCache::default();
+        cache.insert(test_update(10, 0, 10));
+        cache.insert(test_update(20, 0, 20));
+        cache.insert(test_update(30, 0, 15));
+        cache.insert(test_update(2 * period_slots + 10, 0, 10));
+
+        let updates = cache.updates_by_range(0, 3);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].attested_header.beacon.slot, 20);
+        assert!(cache.updates_by_range(1, 2).is_empty());
+        assert_eq!(cache.updates_by_range(2, 1).len(), 1);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod bootstrap;
+pub mod cache;
 pub mod finality_update;
 pub mod header;
 pub mod optimistic_update;
+pub mod update;
@@ -0,0 +1,343 @@
+use alloy_primitives::B256;
+use anyhow::ensure;
+use ream_consensus::{
+    constants::{EPOCHS_PER_SYNC_COMMITTEE_PERIOD, SYNC_COMMITTEE_SIZE},
+    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+    misc::compute_epoch_at_slot,
+    sync_aggregate::SyncAggregate,
+    sync_committee::SyncCommittee,
+};
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::{
+    FixedVector,
+    typenum::{U5, U6},
+};
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
+
+use crate::{
+    finality_update::LightClientFinalityUpdate, header::LightClientHeader,
+    optimistic_update::LightClientOptimisticUpdate,
+};
+
+/// https://ethereum.github.io/consensus-specs/specs/altair/light-client/sync-protocol/#lightclientupdate
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
+pub struct LightClientUpdate {
+    /// Header attested to by the sync committee
+    pub attested_header: LightClientHeader,
+    /// Next sync committee corresponding to `attested_header.beacon.state_root`
+    pub next_sync_committee: SyncCommittee,
+    pub next_sync_committee_branch: FixedVector<B256, U5>,
+    /// Finalized header corresponding to `attested_header.beacon.state_root`
+    pub finalized_header: LightClientHeader,
+    pub finality_branch: FixedVector<B256, U6>,
+    /// Sync committee aggregate signature
+    pub sync_aggregate: SyncAggregate,
+    /// Slot at which the aggregate signature was created (untrusted)
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub signature_slot: u64,
+}
+
+impl LightClientUpdate {
+    /// Creates the update proven by the sync aggregate of `signed_block`, which attests to its
+    /// parent `attested_block`.
+    ///
+    /// Unlike the spec we only create updates which carry both the next sync committee and a
+    /// finality proof, as those are the only ones worth serving.
+    pub fn new(
+        signed_block: &SignedBeaconBlock,
+        attested_state: &BeaconState,
+        attested_block: &SignedBeaconBlock,
+        finalized_block: &SignedBeaconBlock,
+    ) -> anyhow::Result<Self> {
+        let sync_aggregate = signed_block.message.body.sync_aggregate.clone();
+        ensure!(
+            sync_aggregate.sync_committee_bits.num_set_bits() > 0,
+            "Sync aggregate has no participants"
+        );
+        ensure!(
+            signed_block.message.parent_root == attested_block.message.tree_hash_root(),
+            "Attested block must be the parent of the signature block"
+        );
+        ensure!(
+            attested_state.slot == attested_block.message.slot,
+            "Attested state slot must be equal to attested block slot"
+        );
+        ensure!(
+            compute_sync_committee_period_at_slot(attested_block.message.slot)
+                == compute_sync_committee_period_at_slot(signed_block.message.slot),
+            "Attested block and signature block must be in the same sync committee period"
+        );
+        ensure!(
+            attested_state.finalized_checkpoint.root == finalized_block.message.tree_hash_root(),
+            "Finalized block must be the finalized checkpoint of the attested state"
+        );
+
+        Ok(Self {
+            attested_header: LightClientHeader::new(attested_block)?,
+            next_sync_committee: (*attested_state.next_sync_committee).clone(),
+            next_sync_committee_branch: attested_state
+                .next_sync_committee_inclusion_proof()?
+                .into(),
+            finalized_header: LightClientHeader::new(finalized_block)?,
+            finality_branch: attested_state.finalized_root_inclusion_proof()?.into(),
+            sync_aggregate,
+            signature_slot: signed_block.message.slot,
+        })
+    }
+
+    /// The sync committee period of the attested header.
+    pub fn sync_committee_period(&self) -> u64 {
+        compute_sync_committee_period_at_slot(self.attested_header.beacon.slot)
+    }
+
+    /// https://ethereum.github.io/consensus-specs/specs/altair/light-client/sync-protocol/#is_sync_committee_update
+    pub fn is_sync_committee_update(&self) -> bool {
+        self.next_sync_committee_branch
+            .iter()
+            .any(|node| *node != B256::ZERO)
+    }
+
+    /// https://ethereum.github.io/consensus-specs/specs/altair/light-client/sync-protocol/#is_finality_update
+    pub fn is_finality_update(&self) -> bool {
+        self.finality_branch.iter().any(|node| *node != B256::ZERO)
+    }
+
+    /// https://ethereum.github.io/consensus-specs/specs/altair/light-client/sync-protocol/#is_better_update
+    pub fn is_better_than(&self, other: &Self) -> bool {
+        let participants = self.sync_aggregate.sync_committee_bits.num_set_bits();
+        let other_participants = other.sync_aggregate.sync_committee_bits.num_set_bits();
+
+        // Compare supermajority (> 2/3) sync committee participation
+        let supermajority = has_supermajority(&self.sync_aggregate);
+        let other_supermajority = has_supermajority(&other.sync_aggregate);
+        if supermajority != other_supermajority {
+            return supermajority;
+        }
+        if !supermajority && participants != other_participants {
+            return participants > other_participants;
+        }
+
+        // Compare presence of relevant sync committee
+        let relevant_sync_committee = self.has_relevant_sync_committee();
+        let other_relevant_sync_committee = other.has_relevant_sync_committee();
+        if relevant_sync_committee != other_relevant_sync_committee {
+            return relevant_sync_committee;
+        }
+
+        // Compare indication of any finality
+        let finality = self.is_finality_update();
+        if finality != other.is_finality_update() {
+            return finality;
+        }
+
+        // Compare sync committee finality
+        if finality {
+            let sync_committee_finality = self.has_sync_committee_finality();
+            if sync_committee_finality != other.has_sync_committee_finality() {
+                return sync_committee_finality;
+            }
+        }
+
+        // Tiebreaker 1: Sync committee participation beyond supermajority
+        if participants != other_participants {
+            return participants > other_participants;
+        }
+
+        // Tiebreaker 2: Prefer older data (fewer changes to best)
+        if self.attested_header.beacon.slot != other.attested_header.beacon.slot {
+            return self.attested_header.beacon.slot < other.attested_header.beacon.slot;
+        }
+
+        // Tiebreaker 3: Prefer updates with earlier signature slots
+        self.signature_slot < other.signature_slot
+    }
+
+    /// Whether the update carries the next sync committee of the period it was signed in.
+    fn has_relevant_sync_committee(&self) -> bool {
+        self.is_sync_committee_update()
+            && self.sync_committee_period()
+                == compute_sync_committee_period_at_slot(self.signature_slot)
+    }
+
+    /// Whether the finalized header is in the same sync committee period as the attested header.
+    fn has_sync_committee_finality(&self) -> bool {
+        compute_sync_committee_period_at_slot(self.finalized_header.beacon.slot)
+            == self.sync_committee_period()
+    }
+
+    pub fn to_finality_update(&self) -> LightClientFinalityUpdate {
+        LightClientFinalityUpdate {
+            attested_header: self.attested_header.clone(),
+            finalized_header: self.finalized_header.clone(),
+            finality_branch: self.finality_branch.clone(),
+            sync_aggregate: self.sync_aggregate.clone(),
+            signature_slot: self.signature_slot,
+        }
+    }
+
+    pub fn to_optimistic_update(&self) -> LightClientOptimisticUpdate {
+        LightClientOptimisticUpdate {
+            attested_header: self.attested_header.clone(),
+            sync_aggregate: self.sync_aggregate.clone(),
+            signature_slot: self.signature_slot,
+        }
+    }
+}
+
+/// Whether more than 2/3 of the sync committee took part in `sync_aggregate`.
+pub fn has_supermajority(sync_aggregate: &SyncAggregate) -> bool {
+    sync_aggregate.sync_committee_bits.num_set_bits() * 3 >= SYNC_COMMITTEE_SIZE as usize * 2
+}
+
+pub fn compute_sync_committee_period_at_slot(slot: u64) -> u64 {
+    compute_epoch_at_slot(slot) / EPOCHS_PER_SYNC_COMMITTEE_PERIOD
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use alloy_primitives::{Address, U256};
+    use ream_consensus::{
+        beacon_block_header::BeaconBlockHeader, constants::SLOTS_PER_EPOCH,
+        electra::execution_payload_header::ExecutionPayloadHeader,
+    };
+    use ssz_types::{BitVector, VariableList};
+
+    use super::*;
+
+    /// An update attesting to `attested_slot` and finalizing `finalized_slot`, signed by the
+    /// first `participants` members of the sync committee at the next slot.
+    pub(crate) fn test_update(
+        attested_slot: u64,
+        finalized_slot: u64,
+        participants: usize,
+    ) -> LightClientUpdate {
+        let header = |slot| LightClientHeader {
+            beacon: BeaconBlockHeader {
+                slot,
+                proposer_index: 0,
+                parent_root: B256::ZERO,
+                state_root: B256::ZERO,
+                body_root: B256::ZERO,
+            },
+            execution: ExecutionPayloadHeader {
+                parent_hash: B256::ZERO,
+                fee_recipient: Address::ZERO,
+                state_root: B256::ZERO,
+                receipts_root: B256::ZERO,
+                logs_bloom: FixedVector::default(),
+                prev_randao: B256::ZERO,
+                block_number: 0,
+                gas_limit: 0,
+                gas_used: 0,
+                timestamp: 0,
+                extra_data: VariableList::default(),
+                base_fee_per_gas: U256::ZERO,
+                block_hash: B256::ZERO,
+                transactions_root: B256::ZERO,
+                withdrawals_root: B256::ZERO,
+                blob_gas_used: 0,
+                excess_blob_gas: 0,
+            },
+            execution_branch: FixedVector::default(),
+        };
+        let mut sync_committee_bits = BitVector::new();
+        for index in 0..participants {
+            sync_committee_bits.set(index, true).unwrap();
+        }
+
+        LightClientUpdate {
+            attested_header: header(attested_slot),
+            next_sync_committee: SyncCommittee {
+                pubkeys: FixedVector::default(),
+                aggregate_pubkey: Default::default(),
+            },
+            next_sync_committee_branch: FixedVector::default(),
+            finalized_header: header(finalized_slot),
+            finality_branch: FixedVector::default(),
+            sync_aggregate: SyncAggregate {
+                sync_committee_bits,
+                sync_committee_signature: Default::default(),
+            },
+            signature_slot: attested_slot + 1,
+        }
+    }
+
+    #[test]
+    fn test_is_better_than() {
+        let supermajority = SYNC_COMMITTEE_SIZE as usize * 2 / 3 + 1;
+
+        // Supermajority participation wins over anything else
+        let update = test_update(100, 64, supermajority);
+        let other = test_update(90, 64, supermajority - 2);
+        assert!(update.is_better_than(&other));
+        assert!(!other.is_better_than(&update));
+
+        // Then higher participation
+        let update = test_update(100, 64, 20);
+        let other = test_update(90, 64, 10);
+        assert!(update.is_better_than(&other));
+        assert!(!other.is_better_than(&update));
+
+        // Then older attested headers
+        let update = test_update(90, 64, 20);
+        let other = test_update(100, 64, 20);
+        assert!(update.is_better_than(&other));
+        assert!(!other.is_better_than(&update));
+
+        // Then older signatures
+        let mut other = test_update(90, 64, 20);
+        other.signature_slot += 1;
+        assert!(update.is_better_than(&other));
+        assert!(!update.is_better_than(&update));
+    }
+
+    #[test]
+    fn test_is_better_than_sync_committee_and_finality() {
+        let period_slots = EPOCHS_PER_SYNC_COMMITTEE_PERIOD * SLOTS_PER_EPOCH;
+        let supermajority = SYNC_COMMITTEE_SIZE as usize * 2 / 3 + 1;
+        let with_proofs = |mut update: LightClientUpdate| {
+            update.next_sync_committee_branch = FixedVector::from_elem(B256::repeat_byte(1));
+            update.finality_branch = FixedVector::from_elem(B256::repeat_byte(1));
+            update
+        };
+
+        // Below supermajority, participation comes before any proof
+        let update = test_update(100, 64, 20);
+        let other = with_proofs(test_update(100, 64, 10));
+        assert!(update.is_better_than(&other));
+
+        // With supermajority, a relevant sync committee wins over higher participation
+        let update = with_proofs(test_update(100, 64, supermajority));
+        let other = test_update(100, 64, SYNC_COMMITTEE_SIZE as usize);
+        assert!(update.is_better_than(&other));
+        assert!(!other.is_better_than(&update));
+
+        // The sync committee is only relevant if signed in the attested period
+        let mut other = with_proofs(test_update(
+            period_slots - 2,
+            64,
+            SYNC_COMMITTEE_SIZE as usize,
+        ));
+        assert!(other.is_better_than(&update));
+        other.signature_slot = period_slots;
+        assert!(update.is_better_than(&other));
+
+        // Then any finality
+        let mut other = with_proofs(test_update(100, 64, SYNC_COMMITTEE_SIZE as usize));
+        other.finality_branch = FixedVector::default();
+        assert!(update.is_better_than(&other));
+        assert!(!other.is_better_than(&update));
+
+        // Then finality within the attested period
+        let update = with_proofs(test_update(period_slots + 100, period_slots, supermajority));
+        let other = with_proofs(test_update(
+            period_slots + 100,
+            64,
+            SYNC_COMMITTEE_SIZE as usize,
+        ));
+        assert!(update.is_better_than(&other));
+        assert!(!other.is_better_than(&update));
+    }
+}
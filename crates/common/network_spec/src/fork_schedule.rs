@@ -21,4 +21,65 @@ impl ForkSchedule {
         self.iter()
             .filter(|fork| fork.epoch != Fork::UNSCHEDULED_EPOCH)
     }
+
+    /// Returns the fork that is active at `epoch`.
+    pub fn fork_at_epoch(&self, epoch: u64) -> &Fork {
+        self.scheduled()
+            .filter(|fork| fork.epoch <= epoch)
+            .last()
+            .unwrap_or(&self.0[0])
+    }
+
+    /// Returns the first fork scheduled after `epoch`, if any.
+    pub fn next_fork(&self, epoch: u64) -> Option<&Fork> {
+        self.scheduled().find(|fork| fork.epoch > epoch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::aliases::B32;
+
+    use super::*;
+
+    fn fork(version: u8, epoch: u64) -> Fork {
+        Fork {
+            previous_version: B32::ZERO,
+            current_version: B32::from([version, 0, 0, 0]),
+            epoch,
+        }
+    }
+
+    #[test]
+    fn test_fork_at_epoch() {
+        let schedule = ForkSchedule::new([
+            fork(0, 0),
+            fork(1, 0),
+            fork(2, 10),
+            fork(3, 20),
+            fork(4, Fork::UNSCHEDULED_EPOCH),
+            fork(5, Fork::UNSCHEDULED_EPOCH),
+        ]);
+
+        assert_eq!(
+            schedule.fork_at_epoch(0).current_version,
+            B32::from([1, 0, 0, 0])
+        );
+        assert_eq!(
+            schedule.fork_at_epoch(15).current_version,
+            B32::from([2, 0, 0, 0])
+        );
+        assert_eq!(
+            schedule.fork_at_epoch(20).current_version,
+            B32::from([3, 0, 0, 0])
+        );
+        assert_eq!(
+            schedule.fork_at_epoch(u64::MAX - 1).current_version,
+            B32::from([3, 0, 0, 0])
+        );
+
+        assert_eq!(schedule.next_fork(0).map(|fork| fork.epoch), Some(10));
+        assert_eq!(schedule.next_fork(10).map(|fork| fork.epoch), Some(20));
+        assert_eq!(schedule.next_fork(20), None);
+    }
 }
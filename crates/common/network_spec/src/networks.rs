@@ -50,6 +50,12 @@ pub fn set_network_spec(network_spec: Arc<NetworkSpec>) {
         .expect("NetworkSpec should be set only once at the start of the application");
 }
 
+/// Initializes the static [NetworkSpec] to [DEV] unless it is already set, for tests sharing it
+/// within a test binary.
+pub fn initialize_test_network_spec() {
+    NETWORK_SPEC.get_or_init(|| DEV.clone());
+}
+
 /// Returns the static [NetworkSpec] initialized by [set_network_spec].
 ///
 /// # Panics
@@ -164,6 +170,27 @@ impl NetworkSpec {
         .compute_fork_digest()
     }
 
+    /// Returns the digest of the fork active at `epoch`.
+    pub fn fork_digest_at_epoch(&self, epoch: u64, genesis_validators_root: B256) -> B32 {
+        ForkData {
+            current_version: self.fork_schedule().fork_at_epoch(epoch).current_version,
+            genesis_validators_root,
+        }
+        .compute_fork_digest()
+    }
+
+    /// Returns whether `fork_digest` belongs to one of the scheduled forks.
+    pub fn is_known_fork_digest(&self, fork_digest: B32, genesis_validators_root: B256) -> bool {
+        self.fork_schedule().scheduled().any(|fork| {
+            ForkData {
+                current_version: fork.current_version,
+                genesis_validators_root,
+            }
+            .compute_fork_digest()
+                == fork_digest
+        })
+    }
+
     pub fn fork_schedule(&self) -> ForkSchedule {
         ForkSchedule([
             Fork {
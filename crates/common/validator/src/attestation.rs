@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
+use alloy_primitives::{B256, U256};
 use anyhow::{anyhow, ensure};
+use ethereum_hashing::hash;
 use ream_bls::{
     PrivateKey,
     signature::BLSSignature,
@@ -14,7 +16,10 @@ use ream_consensus::{
         SLOTS_PER_EPOCH,
     },
     electra::beacon_state::BeaconState,
-    misc::{compute_epoch_at_slot, compute_signing_root, get_committee_indices},
+    misc::{
+        bytes_to_int64, compute_epoch_at_slot, compute_shuffled_index, compute_signing_root,
+        get_committee_indices,
+    },
 };
 use ream_network_spec::networks::network_spec;
 use ssz_types::{
@@ -22,7 +27,33 @@ use ssz_types::{
     typenum::{U64, U131072},
 };
 
-use crate::constants::DOMAIN_SELECTION_PROOF;
+use crate::constants::{
+    ATTESTATION_SUBNET_PREFIX_BITS, DOMAIN_SELECTION_PROOF, TARGET_AGGREGATORS_PER_COMMITTEE,
+};
+
+/// https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#attestation-subnet-subscription
+pub fn compute_subscribed_subnet(node_id: B256, epoch: u64, index: u64) -> anyhow::Result<u64> {
+    let node_id = U256::from_be_bytes(node_id.0);
+    let node_id_prefix = node_id >> (256 - ATTESTATION_SUBNET_PREFIX_BITS as usize);
+    let node_offset = node_id % U256::from(network_spec().epochs_per_subnet_subscription);
+    let permutation_seed = hash(
+        &((epoch + node_offset.to::<u64>()) / network_spec().epochs_per_subnet_subscription)
+            .to_le_bytes(),
+    );
+    let permutated_prefix = compute_shuffled_index(
+        node_id_prefix.to::<usize>(),
+        1 << ATTESTATION_SUBNET_PREFIX_BITS,
+        B256::from_slice(&permutation_seed),
+    )?;
+    Ok((permutated_prefix as u64 + index) % network_spec().attestation_subnet_count)
+}
+
+/// The long-lived attestation subnets a node has to subscribe to in `epoch`.
+pub fn compute_subscribed_subnets(node_id: B256, epoch: u64) -> anyhow::Result<Vec<u64>> {
+    (0..network_spec().subnets_per_node)
+        .map(|index| compute_subscribed_subnet(node_id, epoch, index))
+        .collect()
+}
 
 /// Compute the correct subnet for an attestation for Phase 0.
 /// Note, this mimics expected future behavior where attestations will be mapped to their shard
@@ -99,3 +130,14 @@ pub fn get_slot_signature(
     let signing_root = compute_signing_root(slot, domain);
     Ok(private_key.sign(signing_root.as_ref())?)
 }
+
+pub fn is_aggregator(
+    state: &BeaconState,
+    slot: u64,
+    committee_index: u64,
+    slot_signature: &BLSSignature,
+) -> anyhow::Result<bool> {
+    let committee = state.get_beacon_committee(slot, committee_index)?;
+    let modulo = (committee.len() as u64 / TARGET_AGGREGATORS_PER_COMMITTEE).max(1);
+    Ok(bytes_to_int64(&hash(slot_signature.to_bytes())[0..8]) % modulo == 0)
+}
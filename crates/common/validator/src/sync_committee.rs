@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
+use alloy_primitives::B256;
 use anyhow::{anyhow, bail, ensure};
+use ethereum_hashing::hash;
 use ream_bls::{
     BLSSignature, PrivateKey,
     traits::{Aggregatable, Signable},
@@ -8,22 +10,36 @@ use ream_bls::{
 use ream_consensus::{
     constants::{EPOCHS_PER_SYNC_COMMITTEE_PERIOD, SYNC_COMMITTEE_SIZE},
     electra::{beacon_block::BeaconBlock, beacon_state::BeaconState},
-    misc::{compute_epoch_at_slot, compute_signing_root},
+    misc::{bytes_to_int64, compute_epoch_at_slot, compute_signing_root},
     sync_aggregate::SyncAggregate,
 };
 use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
 use ssz_types::{BitVector, typenum::U512};
 use tree_hash_derive::TreeHash;
 
 use crate::{
-    constants::{DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF, SYNC_COMMITTEE_SUBNET_COUNT},
+    constants::{
+        DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF, SYNC_COMMITTEE_SUBNET_COUNT,
+        TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE,
+    },
     contribution_and_proof::SyncCommitteeContribution,
 };
 
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
+pub struct SyncCommitteeMessage {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    pub beacon_block_root: B256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    pub signature: BLSSignature,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TreeHash)]
 pub struct SyncAggregatorSelectionData {
-    slot: u64,
-    subcommittee_index: u64,
+    pub slot: u64,
+    pub subcommittee_index: u64,
 }
 
 pub fn compute_sync_committee_period(epoch: u64) -> u64 {
@@ -142,3 +158,11 @@ pub fn get_sync_committee_selection_proof(
     );
     Ok(private_key.sign(signing_root.as_ref())?)
 }
+
+pub fn is_sync_committee_aggregator(signature: &BLSSignature) -> bool {
+    let modulo = (SYNC_COMMITTEE_SIZE
+        / SYNC_COMMITTEE_SUBNET_COUNT
+        / TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE)
+        .max(1);
+    bytes_to_int64(&hash(signature.to_bytes())[0..8]) % modulo == 0
+}
@@ -1,18 +1,25 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
 
-use discv5::{ConfigBuilder, Enr, ListenConfig};
+use discv5::{ConfigBuilder, Enr};
 
-use crate::subnet::{AttestationSubnets, SyncCommitteeSubnets};
+use crate::{
+    eth2::EnrForkId,
+    listen_address::ListenAddress,
+    subnet::{AttestationSubnets, SyncCommitteeSubnets},
+};
 
 pub struct DiscoveryConfig {
     pub discv5_config: discv5::Config,
     pub bootnodes: Vec<Enr>,
-    pub socket_address: IpAddr,
-    pub socket_port: u16,
-    pub discovery_port: u16,
+    /// Addresses and ports advertised in the ENR, for each IP family we listen on
+    pub listen_address: ListenAddress,
     pub disable_discovery: bool,
     pub attestation_subnets: AttestationSubnets,
     pub sync_committee_subnets: SyncCommitteeSubnets,
+    /// The `eth2` field advertised in the initial ENR
+    pub enr_fork_id: EnrForkId,
+    /// Directory the local ENR is persisted to, so its sequence number survives restarts
+    pub data_dir: Option<PathBuf>,
 }
 
 impl Default for DiscoveryConfig {
@@ -28,22 +35,18 @@ impl Default for DiscoveryConfig {
             .enable_attestation_subnet(1)
             .expect("Failed to enable attestation subnet 1");
 
-        let socket_address = Ipv4Addr::UNSPECIFIED;
-        let socket_port = 9000;
-        let discovery_port = 9000;
-        let listen_config = ListenConfig::from_ip(socket_address.into(), discovery_port);
-
-        let discv5_config = ConfigBuilder::new(listen_config).build();
+        let listen_address = ListenAddress::default();
+        let discv5_config = ConfigBuilder::new(listen_address.discv5_listen_config()).build();
 
         Self {
             discv5_config,
             bootnodes: Vec::new(),
-            socket_address: socket_address.into(),
-            socket_port,
-            discovery_port,
+            listen_address,
             disable_discovery: false,
             attestation_subnets,
             sync_committee_subnets,
+            enr_fork_id: EnrForkId::default(),
+            data_dir: None,
         }
     }
 }
@@ -1,11 +1,13 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     future::Future,
+    path::PathBuf,
     pin::Pin,
     task::{Context, Poll},
     time::Instant,
 };
 
+use alloy_rlp::{Decodable, Encodable};
 use anyhow::anyhow;
 use discv5::{
     Discv5, Enr,
@@ -21,21 +23,23 @@ use libp2p::{
         THandlerOutEvent, ToSwarm, dummy::ConnectionHandler,
     },
 };
-use ream_consensus::constants::genesis_validators_root;
 use tokio::sync::mpsc;
-use tracing::{error, info, warn};
+use tracing::{error, info, trace, warn};
 
 use crate::{
     config::DiscoveryConfig,
+    enr_ext::{QUIC_ENR_KEY, QUIC6_ENR_KEY},
     eth2::{ENR_ETH2_KEY, EnrForkId},
+    persistence::{load_known_enrs, restore_enr_seq, save_enr, save_known_enrs},
     subnet::{
-        ATTESTATION_BITFIELD_ENR_KEY, SYNC_COMMITTEE_BITFIELD_ENR_KEY,
-        attestation_subnet_predicate, sync_committee_subnet_predicate,
+        ATTESTATION_BITFIELD_ENR_KEY, AttestationSubnets, SYNC_COMMITTEE_BITFIELD_ENR_KEY,
+        SyncCommitteeSubnets, attestation_subnet_predicate, sync_committee_subnet_predicate,
     },
 };
 
 #[derive(Debug)]
 pub struct DiscoveredPeers {
+    pub query_type: QueryType,
     pub peers: HashMap<Enr, Option<Instant>>,
 }
 
@@ -47,7 +51,7 @@ enum EventStream {
     Present(mpsc::Receiver<discv5::Event>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum QueryType {
     Peers,
     AttestationSubnetPeers(Vec<u8>),
@@ -65,6 +69,11 @@ pub struct Discovery {
     event_stream: EventStream,
     discovery_queries: FuturesUnordered<Pin<Box<dyn Future<Output = QueryResult> + Send>>>,
     find_peer_active: bool,
+    /// Subnet queries in flight, which run independently of the general peer query.
+    active_subnet_queries: HashSet<QueryType>,
+    /// ENRs persisted by a previous run, until they are taken to be dialed.
+    known_enrs: Vec<Enr>,
+    data_dir: Option<PathBuf>,
     pub started: bool,
 }
 
@@ -74,12 +83,25 @@ impl Discovery {
             convert_to_enr(local_key).map_err(|err| anyhow!("Failed to convert key: {err:?}"))?;
 
         let mut enr_builder = Enr::builder();
-        enr_builder.ip(config.socket_address);
-        enr_builder.tcp4(config.socket_port);
-        enr_builder.udp4(config.discovery_port);
+        if let Some(v4) = config.listen_address.v4() {
+            enr_builder.ip4(v4.address);
+            enr_builder.tcp4(v4.tcp_port);
+            enr_builder.udp4(v4.discovery_port);
+            if let Some(quic_port) = v4.quic_port {
+                enr_builder.add_value(QUIC_ENR_KEY, &quic_port);
+            }
+        }
+        if let Some(v6) = config.listen_address.v6() {
+            enr_builder.ip6(v6.address);
+            enr_builder.tcp6(v6.tcp_port);
+            enr_builder.udp6(v6.discovery_port);
+            if let Some(quic_port) = v6.quic_port {
+                enr_builder.add_value(QUIC6_ENR_KEY, &quic_port);
+            }
+        }
 
-        let enr = enr_builder
-            .add_value(ENR_ETH2_KEY, &EnrForkId::electra(genesis_validators_root()))
+        let mut enr = enr_builder
+            .add_value(ENR_ETH2_KEY, &config.enr_fork_id)
             .add_value(ATTESTATION_BITFIELD_ENR_KEY, &config.attestation_subnets)
             .add_value(
                 SYNC_COMMITTEE_BITFIELD_ENR_KEY,
@@ -88,6 +110,10 @@ impl Discovery {
             .build(&enr_local)
             .map_err(|err| anyhow!("Failed to build ENR: {err}"))?;
 
+        if let Some(data_dir) = &config.data_dir {
+            restore_enr_seq(data_dir, &mut enr, &enr_local)?;
+        }
+
         let node_local_id = enr.node_id();
 
         let mut discv5 = Discv5::new(enr.clone(), enr_local, config.discv5_config.clone())
@@ -104,6 +130,18 @@ impl Discovery {
             };
         }
 
+        // Peers known from a previous run fill the routing table until bootnodes respond
+        let known_enrs = match &config.data_dir {
+            Some(data_dir) => load_known_enrs(data_dir),
+            None => vec![],
+        };
+        for enr in &known_enrs {
+            if let Err(err) = discv5.add_enr(enr.clone()) {
+                trace!("Failed to add known ENR to Discv5 {err:?}");
+            }
+        }
+        info!("Loaded {} known ENRs", known_enrs.len());
+
         let event_stream = if !config.disable_discovery {
             discv5
                 .start()
@@ -121,6 +159,9 @@ impl Discovery {
             event_stream,
             discovery_queries: FuturesUnordered::new(),
             find_peer_active: false,
+            active_subnet_queries: HashSet::new(),
+            known_enrs,
+            data_dir: config.data_dir.clone(),
             started: !config.disable_discovery,
         })
     }
@@ -129,6 +170,74 @@ impl Discovery {
         &self.local_enr
     }
 
+    /// Takes the ENRs persisted by a previous run, to dial them first.
+    pub fn take_known_enrs(&mut self) -> Vec<Enr> {
+        std::mem::take(&mut self.known_enrs)
+    }
+
+    /// Persists `connected_enrs` followed by the ENRs of the routing table, so the next run can
+    /// start from them.
+    pub fn persist_known_enrs(
+        &self,
+        connected_enrs: impl IntoIterator<Item = Enr>,
+    ) -> anyhow::Result<()> {
+        let Some(data_dir) = &self.data_dir else {
+            return Ok(());
+        };
+
+        let mut seen = HashSet::new();
+        let enrs: Vec<Enr> = connected_enrs
+            .into_iter()
+            .chain(self.discv5.table_entries_enr())
+            .filter(|enr| seen.insert(enr.node_id()))
+            .collect();
+        save_known_enrs(data_dir, &enrs)?;
+        trace!("Persisted {} known ENRs", enrs.len());
+        Ok(())
+    }
+
+    /// Advertises the subnets we are subscribed to in our ENR. Every changed field bumps the ENR
+    /// sequence number.
+    pub fn update_subnets(
+        &mut self,
+        attestation_subnets: &AttestationSubnets,
+        sync_committee_subnets: &SyncCommitteeSubnets,
+    ) -> anyhow::Result<()> {
+        self.update_enr_field(ATTESTATION_BITFIELD_ENR_KEY, attestation_subnets)?;
+        self.update_enr_field(SYNC_COMMITTEE_BITFIELD_ENR_KEY, sync_committee_subnets)
+    }
+
+    /// Advertises the current fork digest and the next scheduled fork in our ENR.
+    pub fn update_enr_fork_id(&mut self, enr_fork_id: &EnrForkId) -> anyhow::Result<()> {
+        self.update_enr_field(ENR_ETH2_KEY, enr_fork_id)
+    }
+
+    /// Sets an ENR field if its value changed, which bumps the ENR sequence number, and persists
+    /// the new ENR.
+    fn update_enr_field<T: Encodable + Decodable + PartialEq>(
+        &mut self,
+        key: &str,
+        value: &T,
+    ) -> anyhow::Result<()> {
+        let current_value = self.local_enr.get_decodable::<T>(key).and_then(Result::ok);
+        if current_value.as_ref() == Some(value) {
+            return Ok(());
+        }
+
+        self.discv5
+            .enr_insert(key, value)
+            .map_err(|err| anyhow!("Failed to update {key} in ENR: {err:?}"))?;
+        self.local_enr = self.discv5.local_enr();
+        info!(
+            "Updated {key} in local ENR, new sequence number {}",
+            self.local_enr.seq()
+        );
+        if let Some(data_dir) = &self.data_dir {
+            save_enr(data_dir, &self.local_enr)?;
+        }
+        Ok(())
+    }
+
     pub fn discover_peers(&mut self, query: QueryType, target_peers: usize) {
         // If the discv5 service isn't running or we are in the process of a query, don't bother
         // queuing a new one.
@@ -140,6 +249,19 @@ impl Discovery {
         self.start_query(query, target_peers);
     }
 
+    /// Looks for peers advertising the given subnets in their ENR. Unlike
+    /// [`Discovery::discover_peers`] this doesn't wait for the general peer query, only for an
+    /// identical subnet query.
+    pub fn discover_subnet_peers(&mut self, query: QueryType, target_peers: usize) {
+        if !self.started || query == QueryType::Peers || self.active_subnet_queries.contains(&query)
+        {
+            return;
+        }
+        self.active_subnet_queries.insert(query.clone());
+
+        self.start_query(query, target_peers);
+    }
+
     fn start_query(&mut self, query: QueryType, target_peers: usize) {
         let query_future = self
             .discv5
@@ -164,8 +286,9 @@ impl Discovery {
         self.discovery_queries.push(Box::pin(query_future));
     }
 
-    fn process_queries(&mut self, cx: &mut Context) -> Option<HashMap<Enr, Option<Instant>>> {
+    fn process_queries(&mut self, cx: &mut Context) -> Option<DiscoveredPeers> {
         while let Poll::Ready(Some(query)) = self.discovery_queries.poll_next_unpin(cx) {
+            let query_type = query.query_type.clone();
             let result = match query.query_type {
                 QueryType::Peers => {
                     self.find_peer_active = false;
@@ -185,7 +308,7 @@ impl Discovery {
                     }
                 }
                 QueryType::AttestationSubnetPeers(subnet_ids) => {
-                    self.find_peer_active = false;
+                    self.active_subnet_queries.remove(&query_type);
                     match query.result {
                         Ok(peers) => {
                             let predicate = attestation_subnet_predicate(subnet_ids);
@@ -207,7 +330,7 @@ impl Discovery {
                     }
                 }
                 QueryType::SyncCommitteeSubnetPeers(subnet_ids) => {
-                    self.find_peer_active = false;
+                    self.active_subnet_queries.remove(&query_type);
                     match query.result {
                         Ok(peers) => {
                             let predicate = sync_committee_subnet_predicate(subnet_ids);
@@ -232,8 +355,8 @@ impl Discovery {
                     }
                 }
             };
-            if result.is_some() {
-                return result;
+            if let Some(peers) = result {
+                return Some(DiscoveredPeers { query_type, peers });
             }
         }
         None
@@ -294,8 +417,8 @@ impl NetworkBehaviour for Discovery {
             return Poll::Pending;
         }
 
-        if let Some(peers) = self.process_queries(cx) {
-            return Poll::Ready(ToSwarm::GenerateEvent(DiscoveredPeers { peers }));
+        if let Some(discovered_peers) = self.process_queries(cx) {
+            return Poll::Ready(ToSwarm::GenerateEvent(discovered_peers));
         }
 
         match &mut self.event_stream {
@@ -340,18 +463,20 @@ mod tests {
     use alloy_primitives::B256;
     use libp2p::identity::Keypair;
     use ream_consensus::constants::GENESIS_VALIDATORS_ROOT;
-    use ream_network_spec::networks::{DEV, set_network_spec};
+    use ream_network_spec::networks::initialize_test_network_spec;
 
     use super::*;
     use crate::{
         config::DiscoveryConfig,
+        enr_ext::EnrExt,
+        listen_address::ListenAddress,
         subnet::{AttestationSubnets, SyncCommitteeSubnets},
     };
 
     #[tokio::test]
     async fn test_initial_subnet_setup() -> anyhow::Result<()> {
         let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
-        set_network_spec(DEV.clone());
+        initialize_test_network_spec();
         let key = Keypair::generate_secp256k1();
         let mut config = DiscoveryConfig::default();
         config.attestation_subnets.enable_attestation_subnet(0)?; // Set subnet 0
@@ -370,6 +495,55 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_update_subnets_bumps_enr_seq() -> anyhow::Result<()> {
+        let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
+        initialize_test_network_spec();
+        let key = Keypair::generate_secp256k1();
+        let config = DiscoveryConfig {
+            disable_discovery: true,
+            ..DiscoveryConfig::default()
+        };
+
+        let mut discovery = Discovery::new(key, &config).await?;
+        let initial_seq = discovery.local_enr().seq();
+
+        let mut attestation_subnets = AttestationSubnets::new();
+        attestation_subnets.enable_attestation_subnet(7)?;
+        discovery.update_subnets(&attestation_subnets, &SyncCommitteeSubnets::new())?;
+        assert_eq!(discovery.local_enr().seq(), initial_seq + 1);
+        assert!(attestation_subnet_predicate(vec![7])(discovery.local_enr()));
+
+        // Nothing changed, so the sequence number stays the same
+        discovery.update_subnets(&attestation_subnets, &SyncCommitteeSubnets::new())?;
+        assert_eq!(discovery.local_enr().seq(), initial_seq + 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_enr_advertises_quic_port() -> anyhow::Result<()> {
+        let key = Keypair::generate_secp256k1();
+        let config = DiscoveryConfig {
+            listen_address: ListenAddress::new(
+                Ipv4Addr::new(192, 168, 1, 100).into(),
+                9000,
+                9000,
+                Some(9001),
+            ),
+            disable_discovery: true,
+            ..DiscoveryConfig::default()
+        };
+
+        let discovery = Discovery::new(key, &config).await?;
+        let local_enr = discovery.local_enr();
+        assert_eq!(local_enr.quic4(), Some(9001));
+        assert_eq!(
+            local_enr.quic_multiaddrs(),
+            vec!["/ip4/192.168.1.100/udp/9001/quic-v1".parse::<Multiaddr>()?]
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_attestation_subnet_predicate() -> anyhow::Result<()> {
         let key = Keypair::generate_secp256k1();
@@ -422,8 +596,9 @@ mod tests {
         peer_config
             .attestation_subnets
             .enable_attestation_subnet(0)?;
-        peer_config.socket_address = Ipv4Addr::new(192, 168, 1, 100).into(); // Non-localhost IP
-        peer_config.socket_port = 9001; // Different port
+        // Non-localhost IP and a different port
+        peer_config.listen_address =
+            ListenAddress::new(Ipv4Addr::new(192, 168, 1, 100).into(), 9001, 9000, None);
         peer_config.disable_discovery = true;
 
         let peer_discovery = Discovery::new(peer_key, &peer_config).await.unwrap();
@@ -444,9 +619,10 @@ mod tests {
 
         // Poll the discovery to process the query
         let mut cx = Context::from_waker(futures::task::noop_waker_ref());
-        if let Poll::Ready(ToSwarm::GenerateEvent(DiscoveredPeers { peers })) =
+        if let Poll::Ready(ToSwarm::GenerateEvent(DiscoveredPeers { query_type, peers })) =
             discovery.poll(&mut cx)
         {
+            assert_eq!(query_type, QueryType::AttestationSubnetPeers(vec![0]));
             assert_eq!(peers.len(), 1);
             assert!(peers.contains_key(&peer_enr));
         } else {
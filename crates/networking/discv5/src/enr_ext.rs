@@ -0,0 +1,89 @@
+use anyhow::anyhow;
+use discv5::{
+    Enr,
+    enr::{CombinedPublicKey, EnrPublicKey},
+};
+use libp2p::{
+    Multiaddr, PeerId,
+    identity::{PublicKey, secp256k1},
+    multiaddr::Protocol,
+};
+
+/// ENR key of the QUIC port of the IPv4 address
+pub const QUIC_ENR_KEY: &str = "quic";
+/// ENR key of the QUIC port of the IPv6 address
+pub const QUIC6_ENR_KEY: &str = "quic6";
+
+/// Helpers to get the libp2p view of a peer out of its ENR.
+pub trait EnrExt {
+    /// Returns the libp2p peer id derived from the ENR's public key.
+    fn peer_id(&self) -> anyhow::Result<PeerId>;
+
+    /// Returns the TCP multiaddrs advertised by the ENR.
+    fn tcp_multiaddrs(&self) -> Vec<Multiaddr>;
+
+    /// Returns the QUIC port of the IPv4 address, if advertised.
+    fn quic4(&self) -> Option<u16>;
+
+    /// Returns the QUIC port of the IPv6 address, if advertised.
+    fn quic6(&self) -> Option<u16>;
+
+    /// Returns the QUIC multiaddrs advertised by the ENR.
+    fn quic_multiaddrs(&self) -> Vec<Multiaddr>;
+}
+
+impl EnrExt for Enr {
+    fn peer_id(&self) -> anyhow::Result<PeerId> {
+        let public_key = match self.public_key() {
+            CombinedPublicKey::Secp256k1(public_key) => PublicKey::from(
+                secp256k1::PublicKey::try_from_bytes(&public_key.encode())
+                    .map_err(|err| anyhow!("Invalid secp256k1 public key: {err:?}"))?,
+            ),
+            CombinedPublicKey::Ed25519(_) => {
+                return Err(anyhow!("Only secp256k1 ENRs are supported"));
+            }
+        };
+
+        Ok(PeerId::from_public_key(&public_key))
+    }
+
+    fn tcp_multiaddrs(&self) -> Vec<Multiaddr> {
+        let mut multiaddrs = vec![];
+        if let (Some(ip), Some(tcp)) = (self.ip4(), self.tcp4()) {
+            let mut multiaddr: Multiaddr = ip.into();
+            multiaddr.push(Protocol::Tcp(tcp));
+            multiaddrs.push(multiaddr);
+        }
+        if let (Some(ip6), Some(tcp6)) = (self.ip6(), self.tcp6()) {
+            let mut multiaddr: Multiaddr = ip6.into();
+            multiaddr.push(Protocol::Tcp(tcp6));
+            multiaddrs.push(multiaddr);
+        }
+        multiaddrs
+    }
+
+    fn quic4(&self) -> Option<u16> {
+        self.get_decodable(QUIC_ENR_KEY).and_then(Result::ok)
+    }
+
+    fn quic6(&self) -> Option<u16> {
+        self.get_decodable(QUIC6_ENR_KEY).and_then(Result::ok)
+    }
+
+    fn quic_multiaddrs(&self) -> Vec<Multiaddr> {
+        let mut multiaddrs = vec![];
+        if let (Some(ip), Some(quic)) = (self.ip4(), self.quic4()) {
+            let mut multiaddr: Multiaddr = ip.into();
+            multiaddr.push(Protocol::Udp(quic));
+            multiaddr.push(Protocol::QuicV1);
+            multiaddrs.push(multiaddr);
+        }
+        if let (Some(ip6), Some(quic6)) = (self.ip6(), self.quic6()) {
+            let mut multiaddr: Multiaddr = ip6.into();
+            multiaddr.push(Protocol::Udp(quic6));
+            multiaddr.push(Protocol::QuicV1);
+            multiaddrs.push(multiaddr);
+        }
+        multiaddrs
+    }
+}
@@ -7,7 +7,7 @@ use ssz_derive::{Decode, Encode};
 
 pub const ENR_ETH2_KEY: &str = "eth2";
 
-#[derive(Default, Debug, Encode, Decode)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct EnrForkId {
     pub fork_digest: B32,
     pub next_fork_version: B32,
@@ -15,17 +15,22 @@ pub struct EnrForkId {
 }
 
 impl EnrForkId {
-    pub fn electra(genesis_validators_root: B256) -> Self {
-        let current_fork_version = network_spec().electra_fork_version;
-        let next_fork_version = current_fork_version;
-        let next_fork_epoch = FAR_FUTURE_EPOCH;
-
+    /// https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#eth2-field
+    pub fn at_epoch(epoch: u64, genesis_validators_root: B256) -> Self {
+        let fork_schedule = network_spec().fork_schedule();
+        let current_fork_version = fork_schedule.fork_at_epoch(epoch).current_version;
         let fork_digest = ForkData {
             current_version: current_fork_version,
             genesis_validators_root,
         }
         .compute_fork_digest();
 
+        // Without a planned fork, the next fork version is the current one
+        let (next_fork_version, next_fork_epoch) = match fork_schedule.next_fork(epoch) {
+            Some(next_fork) => (next_fork.current_version, next_fork.epoch),
+            None => (current_fork_version, FAR_FUTURE_EPOCH),
+        };
+
         Self {
             fork_digest,
             next_fork_version,
@@ -1,4 +1,7 @@
 pub mod config;
 pub mod discovery;
+pub mod enr_ext;
 pub mod eth2;
+pub mod listen_address;
+pub mod persistence;
 pub mod subnet;
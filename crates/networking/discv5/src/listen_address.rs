@@ -0,0 +1,160 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
+use discv5::ListenConfig;
+use libp2p::{Multiaddr, multiaddr::Protocol};
+
+/// Address and ports we listen on for one IP family.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListenAddr<Ip> {
+    pub address: Ip,
+    pub tcp_port: u16,
+    pub discovery_port: u16,
+    /// QUIC is disabled for this family if not set
+    pub quic_port: Option<u16>,
+}
+
+impl<Ip: Into<IpAddr> + Copy> ListenAddr<Ip> {
+    fn tcp_multiaddr(&self) -> Multiaddr {
+        let address: IpAddr = self.address.into();
+        let mut multiaddr = Multiaddr::from(address);
+        multiaddr.push(Protocol::Tcp(self.tcp_port));
+        multiaddr
+    }
+
+    fn quic_multiaddr(&self) -> Option<Multiaddr> {
+        let address: IpAddr = self.address.into();
+        let mut multiaddr = Multiaddr::from(address);
+        multiaddr.push(Protocol::Udp(self.quic_port?));
+        multiaddr.push(Protocol::QuicV1);
+        Some(multiaddr)
+    }
+}
+
+/// The IP families we listen on, and the addresses and ports of each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddress {
+    V4(ListenAddr<Ipv4Addr>),
+    V6(ListenAddr<Ipv6Addr>),
+    DualStack(ListenAddr<Ipv4Addr>, ListenAddr<Ipv6Addr>),
+}
+
+impl Default for ListenAddress {
+    fn default() -> Self {
+        ListenAddress::new(Ipv4Addr::UNSPECIFIED.into(), 9000, 9000, None)
+    }
+}
+
+impl ListenAddress {
+    /// Listens on a single IP family, the one of `address`.
+    pub fn new(
+        address: IpAddr,
+        tcp_port: u16,
+        discovery_port: u16,
+        quic_port: Option<u16>,
+    ) -> Self {
+        match address {
+            IpAddr::V4(address) => ListenAddress::V4(ListenAddr {
+                address,
+                tcp_port,
+                discovery_port,
+                quic_port,
+            }),
+            IpAddr::V6(address) => ListenAddress::V6(ListenAddr {
+                address,
+                tcp_port,
+                discovery_port,
+                quic_port,
+            }),
+        }
+    }
+
+    pub fn v4(&self) -> Option<&ListenAddr<Ipv4Addr>> {
+        match self {
+            ListenAddress::V4(v4) | ListenAddress::DualStack(v4, _) => Some(v4),
+            ListenAddress::V6(_) => None,
+        }
+    }
+
+    pub fn v6(&self) -> Option<&ListenAddr<Ipv6Addr>> {
+        match self {
+            ListenAddress::V6(v6) | ListenAddress::DualStack(_, v6) => Some(v6),
+            ListenAddress::V4(_) => None,
+        }
+    }
+
+    /// The discv5 sockets, one per IP family.
+    pub fn discv5_listen_config(&self) -> ListenConfig {
+        ListenConfig::from_two_sockets(
+            self.v4()
+                .map(|v4| SocketAddrV4::new(v4.address, v4.discovery_port)),
+            self.v6()
+                .map(|v6| SocketAddrV6::new(v6.address, v6.discovery_port, 0, 0)),
+        )
+    }
+
+    pub fn tcp_multiaddrs(&self) -> Vec<Multiaddr> {
+        self.v4()
+            .map(ListenAddr::tcp_multiaddr)
+            .into_iter()
+            .chain(self.v6().map(ListenAddr::tcp_multiaddr))
+            .collect()
+    }
+
+    pub fn quic_multiaddrs(&self) -> Vec<Multiaddr> {
+        self.v4()
+            .and_then(ListenAddr::quic_multiaddr)
+            .into_iter()
+            .chain(self.v6().and_then(ListenAddr::quic_multiaddr))
+            .collect()
+    }
+
+    /// Whether QUIC is enabled for any of the IP families.
+    pub fn quic_enabled(&self) -> bool {
+        self.v4().is_some_and(|v4| v4.quic_port.is_some())
+            || self.v6().is_some_and(|v6| v6.quic_port.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dual_stack_multiaddrs() {
+        let listen_address = ListenAddress::DualStack(
+            ListenAddr {
+                address: Ipv4Addr::UNSPECIFIED,
+                tcp_port: 9000,
+                discovery_port: 9000,
+                quic_port: Some(9001),
+            },
+            ListenAddr {
+                address: Ipv6Addr::UNSPECIFIED,
+                tcp_port: 9090,
+                discovery_port: 9090,
+                quic_port: None,
+            },
+        );
+
+        assert_eq!(
+            listen_address.tcp_multiaddrs(),
+            vec![
+                "/ip4/0.0.0.0/tcp/9000".parse::<Multiaddr>().unwrap(),
+                "/ip6/::/tcp/9090".parse::<Multiaddr>().unwrap(),
+            ]
+        );
+        assert_eq!(
+            listen_address.quic_multiaddrs(),
+            vec![
+                "/ip4/0.0.0.0/udp/9001/quic-v1"
+                    .parse::<Multiaddr>()
+                    .unwrap()
+            ]
+        );
+        assert!(listen_address.quic_enabled());
+        assert!(matches!(
+            listen_address.discv5_listen_config(),
+            ListenConfig::DualStack { .. }
+        ));
+    }
+}
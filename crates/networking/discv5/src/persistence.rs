@@ -0,0 +1,107 @@
+use std::{fs, path::Path, str::FromStr};
+
+use anyhow::anyhow;
+use discv5::{Enr, enr::CombinedKey};
+use tracing::{info, warn};
+
+/// File the local ENR is stored in, inside the network directory.
+pub const ENR_FILENAME: &str = "enr.dat";
+
+/// File the ENRs of known peers are stored in, inside the network directory.
+pub const KNOWN_ENRS_FILENAME: &str = "known_enrs.dat";
+
+/// Upper bound of persisted peer ENRs, enough to fill the routing table's closest buckets.
+pub const MAX_KNOWN_ENRS: usize = 256;
+
+/// Reads the ENR persisted by a previous run, if any.
+pub fn load_enr(dir: &Path) -> Option<Enr> {
+    let path = dir.join(ENR_FILENAME);
+    let enr = fs::read_to_string(&path).ok()?;
+    match Enr::from_str(enr.trim()) {
+        Ok(enr) => Some(enr),
+        Err(err) => {
+            warn!("Ignoring invalid ENR in {path:?}: {err}");
+            None
+        }
+    }
+}
+
+pub fn save_enr(dir: &Path, enr: &Enr) -> anyhow::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(ENR_FILENAME), enr.to_base64())?;
+    Ok(())
+}
+
+/// Carries the sequence number over from the ENR persisted by a previous run, so it keeps
+/// increasing across restarts. The sequence number is kept if nothing changed and bumped
+/// otherwise. The resulting ENR is persisted again.
+pub fn restore_enr_seq(dir: &Path, enr: &mut Enr, key: &CombinedKey) -> anyhow::Result<()> {
+    if let Some(previous_enr) = load_enr(dir) {
+        let seq = if previous_enr.node_id() == enr.node_id() && previous_enr.iter().eq(enr.iter()) {
+            previous_enr.seq()
+        } else {
+            previous_enr.seq() + 1
+        };
+
+        if seq != enr.seq() {
+            enr.set_seq(seq, key)
+                .map_err(|err| anyhow!("Failed to set ENR sequence number: {err:?}"))?;
+        }
+        info!("Restored ENR sequence number {seq}");
+    }
+
+    save_enr(dir, enr)
+}
+
+/// Reads the peer ENRs persisted by a previous run, one per line, skipping invalid ones.
+pub fn load_known_enrs(dir: &Path) -> Vec<Enr> {
+    let Ok(enrs) = fs::read_to_string(dir.join(KNOWN_ENRS_FILENAME)) else {
+        return vec![];
+    };
+    enrs.lines()
+        .filter_map(|enr| match Enr::from_str(enr.trim()) {
+            Ok(enr) => Some(enr),
+            Err(err) => {
+                warn!("Ignoring invalid known ENR: {err}");
+                None
+            }
+        })
+        .take(MAX_KNOWN_ENRS)
+        .collect()
+}
+
+pub fn save_known_enrs(dir: &Path, enrs: &[Enr]) -> anyhow::Result<()> {
+    fs::create_dir_all(dir)?;
+    let enrs = enrs
+        .iter()
+        .take(MAX_KNOWN_ENRS)
+        .map(Enr::to_base64)
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(dir.join(KNOWN_ENRS_FILENAME), enrs)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use discv5::enr::CombinedKey;
+
+    use super::*;
+
+    #[test]
+    fn test_known_enrs_round_trip() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert!(load_known_enrs(dir.path()).is_empty());
+
+        let enrs = (0..3)
+            .map(|port| {
+                Enr::builder()
+                    .udp4(port)
+                    .build(&CombinedKey::generate_secp256k1())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        save_known_enrs(dir.path(), &enrs)?;
+        assert_eq!(load_known_enrs(dir.path()), enrs);
+        Ok(())
+    }
+}
@@ -1,6 +1,13 @@
-use std::{net::IpAddr, path::PathBuf};
+use std::{
+    net::{IpAddr, Ipv6Addr},
+    path::PathBuf,
+};
 
-use ream_p2p::bootnodes::Bootnodes;
+use anyhow::bail;
+use libp2p::PeerId;
+use ream_discv5::listen_address::{ListenAddr, ListenAddress};
+use ream_p2p::{bootnodes::Bootnodes, peer_manager::static_peer::StaticPeer};
+use ream_syncer::backfill::BackfillTarget;
 use url::Url;
 
 pub struct ManagerConfig {
@@ -10,6 +17,11 @@ pub struct ManagerConfig {
     pub socket_address: IpAddr,
     pub socket_port: u16,
     pub discovery_port: u16,
+    pub quic_port: Option<u16>,
+    pub socket_address6: Option<Ipv6Addr>,
+    pub socket_port6: Option<u16>,
+    pub discovery_port6: Option<u16>,
+    pub quic_port6: Option<u16>,
     pub disable_discovery: bool,
     pub data_dir: Option<PathBuf>,
     pub ephemeral: bool,
@@ -18,4 +30,40 @@ pub struct ManagerConfig {
     pub purge_db: bool,
     pub execution_endpoint: Option<Url>,
     pub execution_jwt_secret: Option<PathBuf>,
+    pub backfill_target: BackfillTarget,
+    pub private_key_file: Option<PathBuf>,
+    pub target_peers: usize,
+    pub max_peers: usize,
+    pub static_peers: Vec<StaticPeer>,
+    pub trusted_peers: Vec<PeerId>,
+    pub direct_peers: Vec<StaticPeer>,
+}
+
+impl ManagerConfig {
+    /// Listens on `socket_address`, and additionally on `socket_address6` for dual-stack. The
+    /// IPv6 ports default to the ones of `socket_address`.
+    pub fn listen_address(&self) -> anyhow::Result<ListenAddress> {
+        let listen_address = ListenAddress::new(
+            self.socket_address,
+            self.socket_port,
+            self.discovery_port,
+            self.quic_port,
+        );
+        let Some(address6) = self.socket_address6 else {
+            return Ok(listen_address);
+        };
+
+        let ListenAddress::V4(v4) = listen_address else {
+            bail!("Dual-stack requires the socket address to be an IPv4 address");
+        };
+        Ok(ListenAddress::DualStack(
+            v4,
+            ListenAddr {
+                address: address6,
+                tcp_port: self.socket_port6.unwrap_or(self.socket_port),
+                discovery_port: self.discovery_port6.unwrap_or(self.discovery_port),
+                quic_port: self.quic_port6.or(self.quic_port),
+            },
+        ))
+    }
 }
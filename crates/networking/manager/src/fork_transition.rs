@@ -0,0 +1,89 @@
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::{B256, aliases::B32};
+use ream_discv5::eth2::EnrForkId;
+use ream_network_spec::networks::network_spec;
+use tracing::info;
+
+use crate::p2p_sender::P2PSender;
+
+/// How many epochs before a fork we start listening on the topics of the new fork.
+pub const SUBSCRIBE_EPOCHS_BEFORE_FORK: u64 = 2;
+
+/// How many epochs after a fork we keep listening on the topics of the old fork.
+pub const UNSUBSCRIBE_EPOCHS_AFTER_FORK: u64 = 2;
+
+/// Moves our gossip topics and ENR from one fork to the next as the fork schedule dictates.
+///
+/// https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#transitioning-the-gossip
+pub struct ForkTransitionService {
+    genesis_validators_root: B256,
+    p2p_sender: P2PSender,
+    current_fork_digest: Option<B32>,
+    /// Fork digests we asked the network to subscribe to.
+    subscribed_fork_digests: HashSet<B32>,
+    /// Digests of past forks, with the epoch from which we no longer listen on their topics.
+    previous_fork_digests: HashMap<B32, u64>,
+    enr_fork_id: Option<EnrForkId>,
+}
+
+impl ForkTransitionService {
+    pub fn new(genesis_validators_root: B256, p2p_sender: P2PSender) -> Self {
+        Self {
+            genesis_validators_root,
+            p2p_sender,
+            current_fork_digest: None,
+            subscribed_fork_digests: HashSet::new(),
+            previous_fork_digests: HashMap::new(),
+            enr_fork_id: None,
+        }
+    }
+
+    /// Subscribes to the topics of an upcoming fork, switches over at the fork boundary and drops
+    /// the topics of the previous fork once the overlap period is over.
+    pub fn on_epoch(&mut self, epoch: u64) {
+        let fork_digest = network_spec().fork_digest_at_epoch(epoch, self.genesis_validators_root);
+        if self.current_fork_digest != Some(fork_digest) {
+            if let Some(previous_fork_digest) = self.current_fork_digest {
+                info!("Fork boundary reached at epoch {epoch}, new fork digest {fork_digest}");
+                self.previous_fork_digests
+                    .insert(previous_fork_digest, epoch + UNSUBSCRIBE_EPOCHS_AFTER_FORK);
+            }
+            self.current_fork_digest = Some(fork_digest);
+            self.subscribe_to_fork(fork_digest);
+        }
+
+        if let Some(next_fork) = network_spec().fork_schedule().next_fork(epoch) {
+            if epoch + SUBSCRIBE_EPOCHS_BEFORE_FORK >= next_fork.epoch {
+                let next_fork_digest = network_spec()
+                    .fork_digest_at_epoch(next_fork.epoch, self.genesis_validators_root);
+                self.subscribe_to_fork(next_fork_digest);
+            }
+        }
+
+        let expired_fork_digests: Vec<B32> = self
+            .previous_fork_digests
+            .iter()
+            .filter(|(_, until_epoch)| epoch >= **until_epoch)
+            .map(|(fork_digest, _)| *fork_digest)
+            .collect();
+        for fork_digest in expired_fork_digests {
+            info!("Unsubscribing from topics of previous fork digest {fork_digest}");
+            self.previous_fork_digests.remove(&fork_digest);
+            self.subscribed_fork_digests.remove(&fork_digest);
+            self.p2p_sender.unsubscribe_from_fork(fork_digest);
+        }
+
+        let enr_fork_id = EnrForkId::at_epoch(epoch, self.genesis_validators_root);
+        if self.enr_fork_id.as_ref() != Some(&enr_fork_id) {
+            self.p2p_sender.update_enr_fork_id(enr_fork_id.clone());
+            self.enr_fork_id = Some(enr_fork_id);
+        }
+    }
+
+    fn subscribe_to_fork(&mut self, fork_digest: B32) {
+        if self.subscribed_fork_digests.insert(fork_digest) {
+            self.p2p_sender.subscribe_to_fork(fork_digest);
+        }
+    }
+}
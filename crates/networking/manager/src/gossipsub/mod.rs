@@ -0,0 +1,127 @@
+pub mod validate;
+
+use anyhow::anyhow;
+use libp2p::{PeerId, gossipsub::MessageId};
+use ream_beacon_chain::beacon_chain::BeaconChain;
+use ream_consensus::misc::compute_epoch_at_slot;
+use ream_fork_choice::store::Store;
+use ream_p2p::gossipsub::{message::GossipsubMessage, topics::GossipTopic};
+use tracing::{trace, warn};
+use validate::{
+    GossipValidationError, GossipValidator, attestation::single_attestation_to_attestation,
+    message_acceptance,
+};
+
+use crate::p2p_sender::P2PSender;
+
+/// Validates a decoded gossip message, reports the result back to gossipsub and feeds accepted
+/// messages into fork choice.
+pub async fn handle_gossipsub_message(
+    beacon_chain: &BeaconChain,
+    gossip_validator: &mut GossipValidator,
+    p2p_sender: &P2PSender,
+    propagation_source: PeerId,
+    message_id: MessageId,
+    topic: GossipTopic,
+    message: GossipsubMessage,
+) {
+    let validation_result = {
+        let store = beacon_chain.store.lock().await;
+        gossip_validator.validate(&store, &topic, &message).await
+    };
+    p2p_sender.send_validation_result(
+        message_id,
+        propagation_source,
+        message_acceptance(&validation_result),
+    );
+
+    match validation_result {
+        Ok(()) => {}
+        Err(GossipValidationError::Internal(err)) => {
+            warn!(
+                "Failed to validate gossip message from {propagation_source} on {topic}: {err:?}"
+            );
+            return;
+        }
+        Err(err) => {
+            trace!("Gossip message from {propagation_source} on {topic} not accepted: {err}");
+            return;
+        }
+    }
+
+    let result = match message {
+        GossipsubMessage::BeaconBlock(signed_block) => {
+            beacon_chain.process_block(*signed_block).await
+        }
+        GossipsubMessage::BlobSidecar(blob_sidecar) => {
+            beacon_chain.process_blob_sidecar(*blob_sidecar).await
+        }
+        GossipsubMessage::BeaconAttestation(single_attestation) => {
+            let attestation = gossip_validator
+                .target_state(single_attestation.data.target)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Missing state for target {:?}",
+                        single_attestation.data.target
+                    )
+                })
+                .and_then(|target_state| {
+                    single_attestation_to_attestation(&target_state, &single_attestation)
+                });
+            match attestation {
+                Ok(attestation) => beacon_chain.process_attestation(attestation).await,
+                Err(err) => Err(err),
+            }
+        }
+        GossipsubMessage::AggregateAndProof(signed_aggregate_and_proof) => {
+            beacon_chain
+                .process_attestation(signed_aggregate_and_proof.message.aggregate)
+                .await
+        }
+        GossipsubMessage::AttesterSlashing(attester_slashing) => {
+            beacon_chain
+                .process_attester_slashing(*attester_slashing)
+                .await
+        }
+        message => {
+            trace!(
+                "No further processing for gossip message from {propagation_source}: {message:?}"
+            );
+            return;
+        }
+    };
+
+    if let Err(err) = result {
+        warn!("Failed to process gossip message from {propagation_source}: {err:?}");
+    }
+}
+
+/// Tells the network how many validators are active, so it can scale the gossipsub topic score
+/// parameters to the expected message rates.
+pub async fn update_gossipsub_score_params(beacon_chain: &BeaconChain, p2p_sender: &P2PSender) {
+    let result = {
+        let store = beacon_chain.store.lock().await;
+        active_validator_count(&store).await
+    };
+    match result {
+        Ok((active_validators, current_slot)) => {
+            p2p_sender.update_gossipsub_score_params(active_validators, current_slot)
+        }
+        Err(err) => warn!("Failed to update gossipsub score parameters: {err:?}"),
+    }
+}
+
+/// Returns the number of active validators in the head state and the current slot.
+async fn active_validator_count(store: &Store) -> anyhow::Result<(u64, u64)> {
+    let current_slot = store.get_current_slot()?;
+    let head_root = store.get_head()?;
+    let head_state = store
+        .db
+        .get_state(head_root)
+        .await?
+        .ok_or_else(|| anyhow!("Missing head state {head_root}"))?;
+    let active_validators = head_state
+        .get_active_validator_indices(compute_epoch_at_slot(current_slot))
+        .len() as u64;
+    Ok((active_validators, current_slot))
+}
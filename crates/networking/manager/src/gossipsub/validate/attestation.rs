@@ -0,0 +1,412 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use ream_bls::traits::Verifiable;
+use ream_consensus::{
+    attestation::Attestation,
+    attestation_data::AttestationData,
+    checkpoint::Checkpoint,
+    constants::{DOMAIN_AGGREGATE_AND_PROOF, DOMAIN_BEACON_ATTESTER},
+    electra::beacon_state::BeaconState,
+    misc::{compute_epoch_at_slot, compute_signing_root, get_committee_indices},
+    single_attestation::SingleAttestation,
+};
+use ream_fork_choice::store::Store;
+use ream_validator::{
+    aggregate_and_proof::SignedAggregateAndProof,
+    attestation::{compute_subnet_for_attestation, is_aggregator},
+    constants::DOMAIN_SELECTION_PROOF,
+};
+use ssz_types::{BitList, BitVector};
+
+use super::{
+    GossipValidationError, ValidationResult, ensure_known_and_finalized_descendant,
+    ensure_within_propagation_range, ignore_if, reject_if, target_state,
+};
+
+/// https://ethereum.github.io/consensus-specs/specs/electra/p2p-interface/#beacon_attestation_subnet_id
+pub async fn validate_beacon_attestation(
+    store: &Store,
+    seen_attesters: &mut HashSet<(u64, u64)>,
+    target_states: &mut HashMap<Checkpoint, Arc<BeaconState>>,
+    subnet_id: u64,
+    attestation: &SingleAttestation,
+) -> ValidationResult {
+    let data = &attestation.data;
+    validate_attestation_data(store, data)?;
+    ignore_if!(
+        seen_attesters.contains(&(data.target.epoch, attestation.attester_index)),
+        "Already seen an attestation from {} for epoch {}",
+        attestation.attester_index,
+        data.target.epoch
+    );
+
+    let state = target_state(store, target_states, data.target).await?;
+    let committees_per_slot = state.get_committee_count_per_slot(data.target.epoch);
+    reject_if!(
+        attestation.committee_index >= committees_per_slot,
+        "Committee index {} is out of range",
+        attestation.committee_index
+    );
+    reject_if!(
+        compute_subnet_for_attestation(committees_per_slot, data.slot, attestation.committee_index)
+            != subnet_id,
+        "Attestation for committee {} does not belong to subnet {subnet_id}",
+        attestation.committee_index
+    );
+
+    let committee = state.get_beacon_committee(data.slot, attestation.committee_index)?;
+    reject_if!(
+        !committee.contains(&attestation.attester_index),
+        "Attester {} is not part of committee {}",
+        attestation.attester_index,
+        attestation.committee_index
+    );
+
+    let attester = &state.validators[attestation.attester_index as usize];
+    let signing_root = compute_signing_root(
+        data,
+        state.get_domain(DOMAIN_BEACON_ATTESTER, Some(data.target.epoch)),
+    );
+    reject_if!(
+        !matches!(
+            attestation
+                .signature
+                .verify(&attester.pubkey, signing_root.as_ref()),
+            Ok(true)
+        ),
+        "Invalid attestation signature"
+    );
+
+    seen_attesters.insert((data.target.epoch, attestation.attester_index));
+    Ok(())
+}
+
+/// https://ethereum.github.io/consensus-specs/specs/electra/p2p-interface/#beacon_aggregate_and_proof
+pub async fn validate_aggregate_and_proof(
+    store: &Store,
+    seen_aggregators: &mut HashSet<(u64, u64)>,
+    target_states: &mut HashMap<Checkpoint, Arc<BeaconState>>,
+    signed_aggregate_and_proof: &SignedAggregateAndProof,
+) -> ValidationResult {
+    let aggregate_and_proof = &signed_aggregate_and_proof.message;
+    let aggregate = &aggregate_and_proof.aggregate;
+    let data = &aggregate.data;
+
+    let committee_indices = get_committee_indices(&aggregate.committee_bits);
+    let [committee_index] = committee_indices[..] else {
+        return Err(GossipValidationError::Reject(format!(
+            "Aggregate must have exactly one committee bit set, got {}",
+            committee_indices.len()
+        )));
+    };
+    validate_attestation_data(store, data)?;
+    ignore_if!(
+        seen_aggregators.contains(&(data.target.epoch, aggregate_and_proof.aggregator_index)),
+        "Already seen an aggregate from {} for epoch {}",
+        aggregate_and_proof.aggregator_index,
+        data.target.epoch
+    );
+
+    let state = target_state(store, target_states, data.target).await?;
+    reject_if!(
+        committee_index >= state.get_committee_count_per_slot(data.target.epoch),
+        "Committee index {committee_index} is out of range"
+    );
+    let committee = state.get_beacon_committee(data.slot, committee_index)?;
+    reject_if!(
+        aggregate.aggregation_bits.len() != committee.len(),
+        "Aggregation bits length {} does not match committee size {}",
+        aggregate.aggregation_bits.len(),
+        committee.len()
+    );
+    reject_if!(
+        aggregate.aggregation_bits.is_zero(),
+        "Aggregate has no participants"
+    );
+    reject_if!(
+        !committee.contains(&aggregate_and_proof.aggregator_index),
+        "Aggregator {} is not part of committee {committee_index}",
+        aggregate_and_proof.aggregator_index
+    );
+    reject_if!(
+        !is_aggregator(
+            &state,
+            data.slot,
+            committee_index,
+            &aggregate_and_proof.selection_proof
+        )?,
+        "Validator {} is not an aggregator",
+        aggregate_and_proof.aggregator_index
+    );
+
+    let aggregator = &state.validators[aggregate_and_proof.aggregator_index as usize];
+    let selection_proof_root = compute_signing_root(
+        data.slot,
+        state.get_domain(
+            DOMAIN_SELECTION_PROOF,
+            Some(compute_epoch_at_slot(data.slot)),
+        ),
+    );
+    reject_if!(
+        !matches!(
+            aggregate_and_proof
+                .selection_proof
+                .verify(&aggregator.pubkey, selection_proof_root.as_ref()),
+            Ok(true)
+        ),
+        "Invalid selection proof"
+    );
+
+    let aggregate_and_proof_root = compute_signing_root(
+        aggregate_and_proof,
+        state.get_domain(
+            DOMAIN_AGGREGATE_AND_PROOF,
+            Some(compute_epoch_at_slot(data.slot)),
+        ),
+    );
+    reject_if!(
+        !matches!(
+            signed_aggregate_and_proof
+                .signature
+                .verify(&aggregator.pubkey, aggregate_and_proof_root.as_ref()),
+            Ok(true)
+        ),
+        "Invalid aggregator signature"
+    );
+
+    let indexed_attestation = state.get_indexed_attestation(aggregate)?;
+    reject_if!(
+        !matches!(
+            state.is_valid_indexed_attestation(&indexed_attestation),
+            Ok(true)
+        ),
+        "Invalid aggregate signature"
+    );
+
+    seen_aggregators.insert((data.target.epoch, aggregate_and_proof.aggregator_index));
+    Ok(())
+}
+
+/// Checks shared by unaggregated and aggregated attestations.
+fn validate_attestation_data(store: &Store, data: &AttestationData) -> ValidationResult {
+    reject_if!(data.index != 0, "Attestation data index must be zero");
+    ensure_within_propagation_range(store, data.slot)?;
+    reject_if!(
+        data.target.epoch != compute_epoch_at_slot(data.slot),
+        "Target epoch {} does not match slot {}",
+        data.target.epoch,
+        data.slot
+    );
+
+    ensure_known_and_finalized_descendant(store, data.beacon_block_root)?;
+    reject_if!(
+        store.get_checkpoint_block(data.beacon_block_root, data.target.epoch)? != data.target.root,
+        "Target root {} is not an ancestor of block {}",
+        data.target.root,
+        data.beacon_block_root
+    );
+    Ok(())
+}
+
+/// Converts a validated `SingleAttestation` into the `Attestation` fork choice expects, using the
+/// `target_state` it was validated against.
+pub fn single_attestation_to_attestation(
+    target_state: &BeaconState,
+    attestation: &SingleAttestation,
+) -> anyhow::Result<Attestation> {
+    let committee =
+        target_state.get_beacon_committee(attestation.data.slot, attestation.committee_index)?;
+    let position = committee
+        .iter()
+        .position(|index| *index == attestation.attester_index)
+        .ok_or_else(|| anyhow!("Attester is not part of the committee"))?;
+
+    let mut aggregation_bits = BitList::with_capacity(committee.len())
+        .map_err(|err| anyhow!("Failed to create aggregation bits: {err:?}"))?;
+    aggregation_bits
+        .set(position, true)
+        .map_err(|err| anyhow!("Failed to set aggregation bit: {err:?}"))?;
+    let mut committee_bits = BitVector::new();
+    committee_bits
+        .set(attestation.committee_index as usize, true)
+        .map_err(|err| anyhow!("Failed to set committee bit: {err:?}"))?;
+
+    Ok(Attestation {
+        aggregation_bits,
+        data: attestation.data.clone(),
+        signature: attestation.signature.clone(),
+        committee_bits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::{BLSSignature, traits::Signable};
+    use ream_consensus::constants::SLOTS_PER_EPOCH;
+    use ream_storage::{tables::Table, test_utils::test_private_key};
+
+    use super::*;
+    use crate::gossipsub::validate::test_store;
+
+    /// Returns a signed attestation of the single validator for its slot in the first epoch, and
+    /// the subnet it belongs to.
+    fn test_attestation(state: &BeaconState, target: Checkpoint) -> (SingleAttestation, u64) {
+        let slot = (0..SLOTS_PER_EPOCH)
+            .find(|slot| state.get_beacon_committee(*slot, 0).unwrap().contains(&0))
+            .unwrap();
+        let data = AttestationData {
+            slot,
+            index: 0,
+            beacon_block_root: target.root,
+            source: target,
+            target,
+        };
+        let signature = test_private_key()
+            .sign(
+                compute_signing_root(
+                    data.clone(),
+                    state.get_domain(DOMAIN_BEACON_ATTESTER, Some(0)),
+                )
+                .as_slice(),
+            )
+            .unwrap();
+        let attestation = SingleAttestation {
+            committee_index: 0,
+            attester_index: 0,
+            data,
+            signature,
+        };
+        (attestation, compute_subnet_for_attestation(1, slot, 0))
+    }
+
+    fn modified(
+        attestation: &SingleAttestation,
+        modify: impl FnOnce(&mut SingleAttestation),
+    ) -> SingleAttestation {
+        let mut attestation = attestation.clone();
+        modify(&mut attestation);
+        attestation
+    }
+
+    #[tokio::test]
+    async fn test_validate_beacon_attestation() {
+        let (store, roots) = test_store(2, SLOTS_PER_EPOCH - 1).await;
+        let target = Checkpoint {
+            epoch: 0,
+            root: roots[0],
+        };
+        let state = store
+            .db
+            .beacon_state_provider()
+            .get(roots[0])
+            .unwrap()
+            .unwrap();
+        let (attestation, subnet_id) = test_attestation(&state, target);
+        let mut seen_attesters = HashSet::new();
+        let mut target_states = HashMap::new();
+
+        for (invalid_attestation, invalid_subnet_id, expect_reject) in [
+            (
+                modified(&attestation, |attestation| {
+                    attestation.data.beacon_block_root = B256::repeat_byte(1)
+                }),
+                subnet_id,
+                false,
+            ),
+            (
+                modified(&attestation, |attestation| {
+                    attestation.data.slot = SLOTS_PER_EPOCH
+                }),
+                subnet_id,
+                false,
+            ),
+            (attestation.clone(), subnet_id + 1, true),
+            (
+                modified(&attestation, |attestation| attestation.committee_index = 1),
+                subnet_id,
+                true,
+            ),
+            (
+                modified(&attestation, |attestation| attestation.attester_index = 1),
+                subnet_id,
+                true,
+            ),
+            (
+                modified(&attestation, |attestation| {
+                    attestation.data.target.epoch = 1
+                }),
+                subnet_id,
+                true,
+            ),
+            (
+                modified(&attestation, |attestation| {
+                    attestation.data.target.root = roots[1]
+                }),
+                subnet_id,
+                true,
+            ),
+            (
+                modified(&attestation, |attestation| {
+                    attestation.signature = BLSSignature::infinity()
+                }),
+                subnet_id,
+                true,
+            ),
+        ] {
+            let result = validate_beacon_attestation(
+                &store,
+                &mut seen_attesters,
+                &mut target_states,
+                invalid_subnet_id,
+                &invalid_attestation,
+            )
+            .await;
+            if expect_reject {
+                assert!(matches!(result, Err(GossipValidationError::Reject(_))));
+            } else {
+                assert!(matches!(result, Err(GossipValidationError::Ignore(_))));
+            }
+        }
+        assert!(seen_attesters.is_empty());
+
+        validate_beacon_attestation(
+            &store,
+            &mut seen_attesters,
+            &mut target_states,
+            subnet_id,
+            &attestation,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            validate_beacon_attestation(
+                &store,
+                &mut seen_attesters,
+                &mut target_states,
+                subnet_id,
+                &attestation,
+            )
+            .await,
+            Err(GossipValidationError::Ignore(_))
+        ));
+
+        // The target state is cached for the conversion, without being stored
+        assert!(
+            store
+                .db
+                .checkpoint_states_provider()
+                .get(target)
+                .unwrap()
+                .is_none()
+        );
+        let converted =
+            single_attestation_to_attestation(&target_states[&target], &attestation).unwrap();
+        assert_eq!(converted.aggregation_bits.num_set_bits(), 1);
+        assert_eq!(get_committee_indices(&converted.committee_bits), vec![0]);
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use ream_consensus::{electra::beacon_block::SignedBeaconBlock, misc::compute_start_slot_at_epoch};
+use ream_fork_choice::store::Store;
+use ream_storage::tables::{Field, Table};
+
+use super::{
+    GossipValidationError, ValidationResult, ensure_not_from_future_slot, ignore_if,
+    parent_state_at_slot, reject_if,
+};
+
+/// https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#beacon_block
+pub async fn validate_beacon_block(
+    store: &Store,
+    seen_block_proposers: &mut HashSet<(u64, u64)>,
+    signed_block: &SignedBeaconBlock,
+) -> ValidationResult {
+    let block = &signed_block.message;
+    ensure_not_from_future_slot(store, block.slot)?;
+
+    let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
+    ignore_if!(
+        block.slot <= compute_start_slot_at_epoch(finalized_checkpoint.epoch),
+        "Block at slot {} is not later than the finalized slot",
+        block.slot
+    );
+    ignore_if!(
+        seen_block_proposers.contains(&(block.slot, block.proposer_index)),
+        "Already seen a block from proposer {} at slot {}",
+        block.proposer_index,
+        block.slot
+    );
+
+    let Some(parent_block) = store.db.beacon_block_provider().get(block.parent_root)? else {
+        return Err(GossipValidationError::Ignore(format!(
+            "Unknown parent {}",
+            block.parent_root
+        )));
+    };
+    reject_if!(
+        parent_block.message.slot >= block.slot,
+        "Block slot {} is not later than its parent's slot {}",
+        block.slot,
+        parent_block.message.slot
+    );
+    reject_if!(
+        store.get_checkpoint_block(block.parent_root, finalized_checkpoint.epoch)?
+            != finalized_checkpoint.root,
+        "Block does not descend from the finalized checkpoint"
+    );
+
+    let state = parent_state_at_slot(store, block.parent_root, block.slot).await?;
+    let expected_proposer_index = state.get_beacon_proposer_index()?;
+    reject_if!(
+        block.proposer_index != expected_proposer_index,
+        "Unexpected proposer {}, expected {expected_proposer_index}",
+        block.proposer_index
+    );
+    reject_if!(
+        !matches!(state.verify_block_signature(signed_block), Ok(true)),
+        "Invalid proposer signature"
+    );
+
+    seen_block_proposers.insert((block.slot, block.proposer_index));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::BLSSignature;
+
+    use super::*;
+    use crate::gossipsub::validate::test_store;
+
+    fn modified(
+        block: &SignedBeaconBlock,
+        modify: impl FnOnce(&mut SignedBeaconBlock),
+    ) -> SignedBeaconBlock {
+        let mut block = block.clone();
+        modify(&mut block);
+        block
+    }
+
+    #[tokio::test]
+    async fn test_validate_beacon_block() {
+        let (store, roots) = test_store(3, 3).await;
+        let block = store
+            .db
+            .beacon_block_provider()
+            .get(roots[3])
+            .unwrap()
+            .unwrap();
+        let mut seen_block_proposers = HashSet::new();
+
+        for (invalid_block, expect_reject) in [
+            (modified(&block, |block| block.message.slot = 4), false),
+            (
+                modified(&block, |block| {
+                    block.message.parent_root = B256::repeat_byte(1)
+                }),
+                false,
+            ),
+            (modified(&block, |block| block.message.slot = 2), true),
+            (
+                modified(&block, |block| block.message.proposer_index = 1),
+                true,
+            ),
+            (
+                modified(&block, |block| block.signature = BLSSignature::infinity()),
+                true,
+            ),
+        ] {
+            let result =
+                validate_beacon_block(&store, &mut seen_block_proposers, &invalid_block).await;
+            if expect_reject {
+                assert!(matches!(result, Err(GossipValidationError::Reject(_))));
+            } else {
+                assert!(matches!(result, Err(GossipValidationError::Ignore(_))));
+            }
+        }
+        assert!(seen_block_proposers.is_empty());
+
+        validate_beacon_block(&store, &mut seen_block_proposers, &block)
+            .await
+            .unwrap();
+        assert!(matches!(
+            validate_beacon_block(&store, &mut seen_block_proposers, &block).await,
+            Err(GossipValidationError::Ignore(_))
+        ));
+    }
+}
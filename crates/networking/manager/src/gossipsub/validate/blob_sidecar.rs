@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+
+use ream_bls::traits::Verifiable;
+use ream_consensus::{
+    blob_sidecar::BlobSidecar,
+    constants::{BLOB_KZG_COMMITMENTS_INDEX, DOMAIN_BEACON_PROPOSER, KZG_COMMITMENTS_MERKLE_DEPTH},
+    misc::{compute_epoch_at_slot, compute_signing_root, compute_start_slot_at_epoch},
+};
+use ream_fork_choice::store::Store;
+use ream_merkle::is_valid_merkle_branch;
+use ream_network_spec::networks::network_spec;
+use ream_polynomial_commitments::handlers::verify_blob_kzg_proof_batch;
+use ream_storage::tables::{Field, Table};
+use ream_validator::blob_sidecars::compute_subnet_for_blob_sidecar;
+use tree_hash::TreeHash;
+
+use super::{
+    GossipValidationError, ValidationResult, ensure_not_from_future_slot, ignore_if,
+    parent_state_at_slot, reject_if,
+};
+
+/// Depth of the `kzg_commitment_inclusion_proof`: the commitment list, its length mix-in and the
+/// block body.
+const KZG_COMMITMENT_INCLUSION_PROOF_DEPTH: u64 = 17;
+
+/// https://ethereum.github.io/consensus-specs/specs/electra/p2p-interface/#blob_sidecar_subnet_id
+pub async fn validate_blob_sidecar(
+    store: &Store,
+    seen_blob_sidecars: &mut HashSet<(u64, u64, u64)>,
+    subnet_id: u64,
+    blob_sidecar: &BlobSidecar,
+) -> ValidationResult {
+    let header = &blob_sidecar.signed_block_header.message;
+    reject_if!(
+        blob_sidecar.index >= network_spec().max_blobs_per_block_electra,
+        "Blob index {} is out of range",
+        blob_sidecar.index
+    );
+    reject_if!(
+        compute_subnet_for_blob_sidecar(blob_sidecar.index) != subnet_id,
+        "Blob index {} does not belong to subnet {subnet_id}",
+        blob_sidecar.index
+    );
+    ensure_not_from_future_slot(store, header.slot)?;
+
+    let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
+    ignore_if!(
+        header.slot <= compute_start_slot_at_epoch(finalized_checkpoint.epoch),
+        "Blob sidecar at slot {} is not later than the finalized slot",
+        header.slot
+    );
+    ignore_if!(
+        seen_blob_sidecars.contains(&(header.slot, header.proposer_index, blob_sidecar.index)),
+        "Already seen blob sidecar {} from proposer {} at slot {}",
+        blob_sidecar.index,
+        header.proposer_index,
+        header.slot
+    );
+
+    let Some(parent_block) = store.db.beacon_block_provider().get(header.parent_root)? else {
+        return Err(GossipValidationError::Ignore(format!(
+            "Unknown parent {}",
+            header.parent_root
+        )));
+    };
+    reject_if!(
+        parent_block.message.slot >= header.slot,
+        "Blob sidecar slot {} is not later than its parent's slot {}",
+        header.slot,
+        parent_block.message.slot
+    );
+    reject_if!(
+        store.get_checkpoint_block(header.parent_root, finalized_checkpoint.epoch)?
+            != finalized_checkpoint.root,
+        "Blob sidecar does not descend from the finalized checkpoint"
+    );
+
+    reject_if!(
+        !is_valid_merkle_branch(
+            blob_sidecar.kzg_commitment.tree_hash_root(),
+            &blob_sidecar.kzg_commitment_inclusion_proof,
+            KZG_COMMITMENT_INCLUSION_PROOF_DEPTH,
+            (BLOB_KZG_COMMITMENTS_INDEX << (KZG_COMMITMENTS_MERKLE_DEPTH + 1)) + blob_sidecar.index,
+            header.body_root,
+        ),
+        "Invalid KZG commitment inclusion proof"
+    );
+    reject_if!(
+        !matches!(
+            verify_blob_kzg_proof_batch(
+                &[blob_sidecar.blob.clone()],
+                &[blob_sidecar.kzg_commitment],
+                &[blob_sidecar.kzg_proof],
+            ),
+            Ok(true)
+        ),
+        "Invalid KZG proof"
+    );
+
+    let state = parent_state_at_slot(store, header.parent_root, header.slot).await?;
+    let expected_proposer_index = state.get_beacon_proposer_index()?;
+    reject_if!(
+        header.proposer_index != expected_proposer_index,
+        "Unexpected proposer {}, expected {expected_proposer_index}",
+        header.proposer_index
+    );
+
+    let proposer = &state.validators[header.proposer_index as usize];
+    let domain = state.get_domain(
+        DOMAIN_BEACON_PROPOSER,
+        Some(compute_epoch_at_slot(header.slot)),
+    );
+    let signing_root = compute_signing_root(header, domain);
+    reject_if!(
+        !matches!(
+            blob_sidecar
+                .signed_block_header
+                .signature
+                .verify(&proposer.pubkey, signing_root.as_ref()),
+            Ok(true)
+        ),
+        "Invalid proposer signature"
+    );
+
+    seen_blob_sidecars.insert((header.slot, header.proposer_index, blob_sidecar.index));
+    Ok(())
+}
@@ -0,0 +1,420 @@
+//! Gossip validation rules from
+//! https://ethereum.github.io/consensus-specs/specs/electra/p2p-interface/#global-topics
+
+pub mod attestation;
+pub mod beacon_block;
+pub mod blob_sidecar;
+pub mod operations;
+pub mod sync_committee;
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use alloy_primitives::B256;
+use libp2p::gossipsub::MessageAcceptance;
+use ream_beacon_chain::slot_clock::{SlotClock, SystemClock};
+use ream_consensus::{
+    checkpoint::Checkpoint,
+    constants::SLOTS_PER_EPOCH,
+    electra::beacon_state::BeaconState,
+    misc::{compute_epoch_at_slot, compute_start_slot_at_epoch},
+};
+use ream_fork_choice::store::Store;
+use ream_network_spec::networks::network_spec;
+use ream_p2p::gossipsub::{
+    message::GossipsubMessage,
+    topics::{GossipTopic, GossipTopicKind},
+};
+use ream_storage::tables::{Field, Table};
+use thiserror::Error;
+
+/// Why a gossip message was not accepted.
+#[derive(Error, Debug)]
+pub enum GossipValidationError {
+    /// The message is not propagated, but the sender is not penalized.
+    #[error("Ignored: {0}")]
+    Ignore(String),
+
+    /// The message is invalid, it is not propagated and the sender is penalized.
+    #[error("Rejected: {0}")]
+    Reject(String),
+
+    /// We failed to validate the message, e.g. because of a database error.
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+pub type ValidationResult = Result<(), GossipValidationError>;
+
+pub fn message_acceptance(result: &ValidationResult) -> MessageAcceptance {
+    match result {
+        Ok(()) => MessageAcceptance::Accept,
+        Err(GossipValidationError::Reject(_)) => MessageAcceptance::Reject,
+        Err(GossipValidationError::Ignore(_) | GossipValidationError::Internal(_)) => {
+            MessageAcceptance::Ignore
+        }
+    }
+}
+
+macro_rules! ignore_if {
+    ($condition:expr, $($arg:tt)+) => {
+        if $condition {
+            return Err($crate::gossipsub::validate::GossipValidationError::Ignore(format!($($arg)+)));
+        }
+    };
+}
+
+macro_rules! reject_if {
+    ($condition:expr, $($arg:tt)+) => {
+        if $condition {
+            return Err($crate::gossipsub::validate::GossipValidationError::Reject(format!($($arg)+)));
+        }
+    };
+}
+
+pub(crate) use ignore_if;
+pub(crate) use reject_if;
+
+/// Keeps track of the messages already seen on each topic, so duplicates are ignored instead of
+/// being propagated again.
+#[derive(Debug, Default)]
+pub struct GossipValidator {
+    /// `(slot, proposer_index)` of the blocks already seen.
+    seen_block_proposers: HashSet<(u64, u64)>,
+    /// `(slot, proposer_index, index)` of the blob sidecars already seen.
+    seen_blob_sidecars: HashSet<(u64, u64, u64)>,
+    /// `(target_epoch, attester_index)` of the unaggregated attestations already seen.
+    seen_attesters: HashSet<(u64, u64)>,
+    /// `(target_epoch, aggregator_index)` of the aggregates already seen.
+    seen_aggregators: HashSet<(u64, u64)>,
+    /// `(slot, validator_index, subnet_id)` of the sync committee messages already seen.
+    seen_sync_committee_messages: HashSet<(u64, u64, u64)>,
+    /// `(slot, aggregator_index, subcommittee_index)` of the contributions already seen.
+    seen_sync_contributions: HashSet<(u64, u64, u64)>,
+    seen_voluntary_exits: HashSet<u64>,
+    seen_proposer_slashings: HashSet<u64>,
+    seen_attester_slashing_indices: HashSet<u64>,
+    seen_bls_to_execution_changes: HashSet<u64>,
+    /// The head state operations are validated against, reloaded when the head changes.
+    head_state: Option<(B256, Arc<BeaconState>)>,
+    /// The states at the targets of recent attestations, which determine their committees.
+    target_states: HashMap<Checkpoint, Arc<BeaconState>>,
+}
+
+impl GossipValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates a gossip message received on `topic` against our fork choice store.
+    pub async fn validate(
+        &mut self,
+        store: &Store,
+        topic: &GossipTopic,
+        message: &GossipsubMessage,
+    ) -> ValidationResult {
+        self.prune(store.get_current_slot()?);
+
+        match (topic.kind, message) {
+            (GossipTopicKind::BeaconBlock, GossipsubMessage::BeaconBlock(signed_block)) => {
+                beacon_block::validate_beacon_block(
+                    store,
+                    &mut self.seen_block_proposers,
+                    signed_block,
+                )
+                .await
+            }
+            (
+                GossipTopicKind::BlobSidecar(subnet_id),
+                GossipsubMessage::BlobSidecar(blob_sidecar),
+            ) => {
+                blob_sidecar::validate_blob_sidecar(
+                    store,
+                    &mut self.seen_blob_sidecars,
+                    subnet_id,
+                    blob_sidecar,
+                )
+                .await
+            }
+            (
+                GossipTopicKind::BeaconAttestation(subnet_id),
+                GossipsubMessage::BeaconAttestation(attestation),
+            ) => {
+                attestation::validate_beacon_attestation(
+                    store,
+                    &mut self.seen_attesters,
+                    &mut self.target_states,
+                    subnet_id,
+                    attestation,
+                )
+                .await
+            }
+            (
+                GossipTopicKind::AggregateAndProof,
+                GossipsubMessage::AggregateAndProof(aggregate_and_proof),
+            ) => {
+                attestation::validate_aggregate_and_proof(
+                    store,
+                    &mut self.seen_aggregators,
+                    &mut self.target_states,
+                    aggregate_and_proof,
+                )
+                .await
+            }
+            (
+                GossipTopicKind::VoluntaryExit,
+                GossipsubMessage::VoluntaryExit(signed_voluntary_exit),
+            ) => operations::validate_voluntary_exit(
+                &self.head_state(store).await?,
+                &mut self.seen_voluntary_exits,
+                signed_voluntary_exit,
+            ),
+            (
+                GossipTopicKind::ProposerSlashing,
+                GossipsubMessage::ProposerSlashing(proposer_slashing),
+            ) => operations::validate_proposer_slashing(
+                &self.head_state(store).await?,
+                &mut self.seen_proposer_slashings,
+                proposer_slashing,
+            ),
+            (
+                GossipTopicKind::AttesterSlashing,
+                GossipsubMessage::AttesterSlashing(attester_slashing),
+            ) => operations::validate_attester_slashing(
+                &self.head_state(store).await?,
+                &mut self.seen_attester_slashing_indices,
+                attester_slashing,
+            ),
+            (
+                GossipTopicKind::BlsToExecutionChange,
+                GossipsubMessage::BlsToExecutionChange(signed_bls_to_execution_change),
+            ) => operations::validate_bls_to_execution_change(
+                &self.head_state(store).await?,
+                &mut self.seen_bls_to_execution_changes,
+                signed_bls_to_execution_change,
+            ),
+            (
+                GossipTopicKind::SyncCommittee(subnet_id),
+                GossipsubMessage::SyncCommittee(sync_committee_message),
+            ) => sync_committee::validate_sync_committee_message(
+                store,
+                &self.head_state(store).await?,
+                &mut self.seen_sync_committee_messages,
+                subnet_id,
+                sync_committee_message,
+            ),
+            (
+                GossipTopicKind::SyncCommitteeContributionAndProof,
+                GossipsubMessage::SyncCommitteeContributionAndProof(contribution_and_proof),
+            ) => sync_committee::validate_sync_committee_contribution_and_proof(
+                store,
+                &self.head_state(store).await?,
+                &mut self.seen_sync_contributions,
+                contribution_and_proof,
+            ),
+            (kind, _) => Err(GossipValidationError::Ignore(format!(
+                "No validation for messages on {kind} topics"
+            ))),
+        }
+    }
+
+    /// Returns the head state, which is used to validate operations.
+    async fn head_state(
+        &mut self,
+        store: &Store,
+    ) -> Result<Arc<BeaconState>, GossipValidationError> {
+        let head_root = store.get_head()?;
+        if let Some((cached_root, head_state)) = &self.head_state {
+            if *cached_root == head_root {
+                return Ok(head_state.clone());
+            }
+        }
+
+        let head_state = Arc::new(store.db.get_state(head_root).await?.ok_or_else(|| {
+            GossipValidationError::Ignore(format!("Missing head state {head_root}"))
+        })?);
+        self.head_state = Some((head_root, head_state.clone()));
+        Ok(head_state)
+    }
+
+    /// Returns the cached state at the `target` of an attestation that passed validation.
+    pub fn target_state(&self, target: Checkpoint) -> Option<Arc<BeaconState>> {
+        self.target_states.get(&target).cloned()
+    }
+
+    /// Forgets about messages that are too old to be propagated anymore.
+    fn prune(&mut self, current_slot: u64) {
+        let min_slot =
+            current_slot.saturating_sub(network_spec().attestation_propagation_slot_range);
+        let min_epoch = compute_epoch_at_slot(current_slot).saturating_sub(1);
+
+        self.seen_block_proposers
+            .retain(|(slot, _)| *slot >= min_slot);
+        self.seen_blob_sidecars
+            .retain(|(slot, _, _)| *slot >= min_slot);
+        self.seen_attesters.retain(|(epoch, _)| *epoch >= min_epoch);
+        self.seen_aggregators
+            .retain(|(epoch, _)| *epoch >= min_epoch);
+        self.target_states
+            .retain(|target, _| target.epoch >= min_epoch);
+        self.seen_sync_committee_messages
+            .retain(|(slot, _, _)| *slot >= min_slot);
+        self.seen_sync_contributions
+            .retain(|(slot, _, _)| *slot >= min_slot);
+    }
+}
+
+/// Returns the range of slots the wall clock time can be in, allowing for
+/// `MAXIMUM_GOSSIP_CLOCK_DISPARITY` in either direction.
+fn current_slot_range(store: &Store) -> anyhow::Result<(u64, u64)> {
+    let genesis_time = store.db.genesis_time_provider().get()?;
+    let disparity = Duration::from_millis(network_spec().maximum_gossip_clock_disparity);
+    Ok(SlotClock::new(genesis_time, SystemClock).slot_range(disparity))
+}
+
+fn ensure_not_from_future_slot(store: &Store, slot: u64) -> ValidationResult {
+    let (_, latest_slot) = current_slot_range(store)?;
+    ignore_if!(slot > latest_slot, "Slot {slot} is in the future");
+    Ok(())
+}
+
+fn ensure_current_slot(store: &Store, slot: u64) -> ValidationResult {
+    let (earliest_slot, latest_slot) = current_slot_range(store)?;
+    ignore_if!(
+        slot < earliest_slot || slot > latest_slot,
+        "Slot {slot} is not the current slot"
+    );
+    Ok(())
+}
+
+/// Ensures an attestation for `slot` is within `ATTESTATION_PROPAGATION_SLOT_RANGE` and from the
+/// current or previous epoch.
+fn ensure_within_propagation_range(store: &Store, slot: u64) -> ValidationResult {
+    let (earliest_slot, latest_slot) = current_slot_range(store)?;
+    ignore_if!(slot > latest_slot, "Slot {slot} is in the future");
+    ignore_if!(
+        slot + network_spec().attestation_propagation_slot_range < earliest_slot,
+        "Slot {slot} is too old to be propagated"
+    );
+
+    let current_epoch = compute_epoch_at_slot(latest_slot);
+    ignore_if!(
+        compute_epoch_at_slot(slot) + 1 < current_epoch,
+        "Slot {slot} is from before the previous epoch"
+    );
+    Ok(())
+}
+
+/// Ensures the block with `block_root` is known and descends from the finalized checkpoint.
+fn ensure_known_and_finalized_descendant(
+    store: &Store,
+    block_root: B256,
+) -> Result<Checkpoint, GossipValidationError> {
+    ignore_if!(
+        store.db.beacon_block_provider().get(block_root)?.is_none(),
+        "Unknown block {block_root}"
+    );
+
+    let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
+    reject_if!(
+        store.get_checkpoint_block(block_root, finalized_checkpoint.epoch)?
+            != finalized_checkpoint.root,
+        "Block {block_root} does not descend from the finalized checkpoint"
+    );
+    Ok(finalized_checkpoint)
+}
+
+/// Returns the state of `parent_root` advanced to `slot`, used to check the proposer of a block
+/// at `slot`.
+async fn parent_state_at_slot(
+    store: &Store,
+    parent_root: B256,
+    slot: u64,
+) -> Result<BeaconState, GossipValidationError> {
+    let Some(mut state) = store.db.get_state(parent_root).await? else {
+        return Err(GossipValidationError::Ignore(format!(
+            "Missing state for parent {parent_root}"
+        )));
+    };
+
+    // Advancing the state by more than an epoch is expensive and can be triggered for free by
+    // anyone, so messages that far ahead of their parent are dropped
+    ignore_if!(
+        slot > state.slot + SLOTS_PER_EPOCH,
+        "Slot {slot} is too far ahead of its parent at slot {}",
+        state.slot
+    );
+    if state.slot < slot {
+        state.process_slots(slot)?;
+    }
+    Ok(state)
+}
+
+/// Returns the state at the `target` checkpoint, which determines the committees of an
+/// attestation.
+///
+/// The state is computed without writing it to the database, so a flood of attestations can't
+/// make us store checkpoint states fork choice never asked for, and cached so each target is only
+/// decoded and advanced once.
+async fn target_state(
+    store: &Store,
+    target_states: &mut HashMap<Checkpoint, Arc<BeaconState>>,
+    target: Checkpoint,
+) -> Result<Arc<BeaconState>, GossipValidationError> {
+    if let Some(state) = target_states.get(&target) {
+        return Ok(state.clone());
+    }
+
+    let state = match store.db.checkpoint_states_provider().get(target)? {
+        Some(state) => state,
+        None => {
+            let Some(mut state) = store.db.get_state(target.root).await? else {
+                return Err(GossipValidationError::Ignore(format!(
+                    "Missing state for target {target:?}"
+                )));
+            };
+            let target_slot = compute_start_slot_at_epoch(target.epoch);
+            if state.slot < target_slot {
+                state.process_slots(target_slot)?;
+            }
+            state
+        }
+    };
+    let state = Arc::new(state);
+    target_states.insert(target, state.clone());
+    Ok(state)
+}
+
+/// A store over the chain of [`import_valid_test_chain`] up to `chain_slots`, with its genesis
+/// finalized and the wall clock in the middle of `current_slot`.
+#[cfg(test)]
+async fn test_store(chain_slots: u64, current_slot: u64) -> (Store, Vec<B256>) {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use ream_consensus::constants::SECONDS_PER_SLOT;
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use ream_storage::{db::ReamDB, test_utils::import_valid_test_chain};
+
+    initialize_test_network_spec();
+    let db = ReamDB::in_memory();
+    let roots = import_valid_test_chain(&db, chain_slots).await;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    db.genesis_time_provider()
+        .insert(now - current_slot * SECONDS_PER_SLOT - SECONDS_PER_SLOT / 2)
+        .unwrap();
+    db.time_provider().insert(now).unwrap();
+    db.finalized_checkpoint_provider()
+        .insert(Checkpoint {
+            epoch: 0,
+            root: roots[0],
+        })
+        .unwrap();
+    (Store::new(db), roots)
+}
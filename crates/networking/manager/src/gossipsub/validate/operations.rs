@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use ream_consensus::{
+    attester_slashing::AttesterSlashing, bls_to_execution_change::SignedBLSToExecutionChange,
+    electra::beacon_state::BeaconState, proposer_slashing::ProposerSlashing,
+    voluntary_exit::SignedVoluntaryExit,
+};
+
+use super::{GossipValidationError, ValidationResult, ignore_if};
+
+/// https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#voluntary_exit
+pub fn validate_voluntary_exit(
+    head_state: &BeaconState,
+    seen_voluntary_exits: &mut HashSet<u64>,
+    signed_voluntary_exit: &SignedVoluntaryExit,
+) -> ValidationResult {
+    let validator_index = signed_voluntary_exit.message.validator_index;
+    ignore_if!(
+        seen_voluntary_exits.contains(&validator_index),
+        "Already seen a voluntary exit for validator {validator_index}"
+    );
+
+    head_state
+        .verify_voluntary_exit(signed_voluntary_exit)
+        .map_err(|err| GossipValidationError::Reject(format!("Invalid voluntary exit: {err:?}")))?;
+
+    seen_voluntary_exits.insert(validator_index);
+    Ok(())
+}
+
+/// https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#proposer_slashing
+pub fn validate_proposer_slashing(
+    head_state: &BeaconState,
+    seen_proposer_slashings: &mut HashSet<u64>,
+    proposer_slashing: &ProposerSlashing,
+) -> ValidationResult {
+    let proposer_index = proposer_slashing.signed_header_1.message.proposer_index;
+    ignore_if!(
+        seen_proposer_slashings.contains(&proposer_index),
+        "Already seen a proposer slashing for validator {proposer_index}"
+    );
+
+    head_state
+        .verify_proposer_slashing(proposer_slashing)
+        .map_err(|err| {
+            GossipValidationError::Reject(format!("Invalid proposer slashing: {err:?}"))
+        })?;
+
+    seen_proposer_slashings.insert(proposer_index);
+    Ok(())
+}
+
+/// https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#attester_slashing
+pub fn validate_attester_slashing(
+    head_state: &BeaconState,
+    seen_attester_slashing_indices: &mut HashSet<u64>,
+    attester_slashing: &AttesterSlashing,
+) -> ValidationResult {
+    let attesting_indices_2: HashSet<u64> = attester_slashing
+        .attestation_2
+        .attesting_indices
+        .iter()
+        .copied()
+        .collect();
+    let slashable_indices: Vec<u64> = attester_slashing
+        .attestation_1
+        .attesting_indices
+        .iter()
+        .filter(|index| attesting_indices_2.contains(index))
+        .copied()
+        .collect();
+    ignore_if!(
+        slashable_indices
+            .iter()
+            .all(|index| seen_attester_slashing_indices.contains(index)),
+        "Attester slashing does not slash any new validators"
+    );
+
+    head_state
+        .verify_attester_slashing(attester_slashing)
+        .map_err(|err| {
+            GossipValidationError::Reject(format!("Invalid attester slashing: {err:?}"))
+        })?;
+
+    seen_attester_slashing_indices.extend(slashable_indices);
+    Ok(())
+}
+
+/// https://ethereum.github.io/consensus-specs/specs/capella/p2p-interface/#bls_to_execution_change
+pub fn validate_bls_to_execution_change(
+    head_state: &BeaconState,
+    seen_bls_to_execution_changes: &mut HashSet<u64>,
+    signed_bls_to_execution_change: &SignedBLSToExecutionChange,
+) -> ValidationResult {
+    let validator_index = signed_bls_to_execution_change.message.validator_index;
+    ignore_if!(
+        seen_bls_to_execution_changes.contains(&validator_index),
+        "Already seen a BLS to execution change for validator {validator_index}"
+    );
+
+    head_state
+        .verify_bls_to_execution_change(signed_bls_to_execution_change)
+        .map_err(|err| {
+            GossipValidationError::Reject(format!("Invalid BLS to execution change: {err:?}"))
+        })?;
+
+    seen_bls_to_execution_changes.insert(validator_index);
+    Ok(())
+}
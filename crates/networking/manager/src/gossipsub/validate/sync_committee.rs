@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+
+use ream_bls::{PubKey, traits::Verifiable};
+use ream_consensus::{
+    constants::{DOMAIN_SYNC_COMMITTEE, SYNC_COMMITTEE_SIZE},
+    electra::beacon_state::BeaconState,
+    misc::{compute_epoch_at_slot, compute_signing_root},
+    sync_committee::SyncCommittee,
+};
+use ream_fork_choice::store::Store;
+use ream_validator::{
+    constants::{
+        DOMAIN_CONTRIBUTION_AND_PROOF, DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF,
+        SYNC_COMMITTEE_SUBNET_COUNT,
+    },
+    contribution_and_proof::SignedContributionAndProof,
+    sync_committee::{
+        SyncAggregatorSelectionData, SyncCommitteeMessage, compute_sync_committee_period,
+        is_sync_committee_aggregator,
+    },
+};
+
+use super::{GossipValidationError, ValidationResult, ensure_current_slot, ignore_if, reject_if};
+
+/// https://ethereum.github.io/consensus-specs/specs/altair/p2p-interface/#sync_committee_subnet_id
+pub fn validate_sync_committee_message(
+    store: &Store,
+    head_state: &BeaconState,
+    seen_sync_committee_messages: &mut HashSet<(u64, u64, u64)>,
+    subnet_id: u64,
+    sync_committee_message: &SyncCommitteeMessage,
+) -> ValidationResult {
+    let slot = sync_committee_message.slot;
+    let validator_index = sync_committee_message.validator_index;
+    ensure_current_slot(store, slot)?;
+    reject_if!(
+        subnet_id >= SYNC_COMMITTEE_SUBNET_COUNT,
+        "Sync committee subnet {subnet_id} is out of range"
+    );
+
+    let Some(validator) = head_state.validators.get(validator_index as usize) else {
+        return Err(GossipValidationError::Reject(format!(
+            "Unknown validator {validator_index}"
+        )));
+    };
+    let sync_committee = sync_committee_at_slot(head_state, slot)?;
+    reject_if!(
+        !subcommittee_pubkeys(sync_committee, subnet_id).any(|pubkey| *pubkey == validator.pubkey),
+        "Validator {validator_index} is not part of sync committee subnet {subnet_id}"
+    );
+    ignore_if!(
+        seen_sync_committee_messages.contains(&(slot, validator_index, subnet_id)),
+        "Already seen a sync committee message from {validator_index} at slot {slot}"
+    );
+
+    let signing_root = compute_signing_root(
+        sync_committee_message.beacon_block_root,
+        head_state.get_domain(DOMAIN_SYNC_COMMITTEE, Some(compute_epoch_at_slot(slot))),
+    );
+    reject_if!(
+        !matches!(
+            sync_committee_message
+                .signature
+                .verify(&validator.pubkey, signing_root.as_ref()),
+            Ok(true)
+        ),
+        "Invalid sync committee message signature"
+    );
+
+    seen_sync_committee_messages.insert((slot, validator_index, subnet_id));
+    Ok(())
+}
+
+/// https://ethereum.github.io/consensus-specs/specs/altair/p2p-interface/#sync_committee_contribution_and_proof
+pub fn validate_sync_committee_contribution_and_proof(
+    store: &Store,
+    head_state: &BeaconState,
+    seen_sync_contributions: &mut HashSet<(u64, u64, u64)>,
+    signed_contribution_and_proof: &SignedContributionAndProof,
+) -> ValidationResult {
+    let contribution_and_proof = &signed_contribution_and_proof.message;
+    let contribution = &contribution_and_proof.contribution;
+    let aggregator_index = contribution_and_proof.aggregator_index;
+    ensure_current_slot(store, contribution.slot)?;
+    reject_if!(
+        contribution.subcommittee_index >= SYNC_COMMITTEE_SUBNET_COUNT,
+        "Subcommittee index {} is out of range",
+        contribution.subcommittee_index
+    );
+    reject_if!(
+        contribution.aggregation_bits.is_zero(),
+        "Contribution has no participants"
+    );
+    reject_if!(
+        !is_sync_committee_aggregator(&contribution_and_proof.selection_proof),
+        "Validator {aggregator_index} is not a sync committee aggregator"
+    );
+    ignore_if!(
+        seen_sync_contributions.contains(&(
+            contribution.slot,
+            aggregator_index,
+            contribution.subcommittee_index
+        )),
+        "Already seen a contribution from {aggregator_index} at slot {}",
+        contribution.slot
+    );
+
+    let Some(aggregator) = head_state.validators.get(aggregator_index as usize) else {
+        return Err(GossipValidationError::Reject(format!(
+            "Unknown aggregator {aggregator_index}"
+        )));
+    };
+    let sync_committee = sync_committee_at_slot(head_state, contribution.slot)?;
+    reject_if!(
+        !subcommittee_pubkeys(sync_committee, contribution.subcommittee_index)
+            .any(|pubkey| *pubkey == aggregator.pubkey),
+        "Aggregator {aggregator_index} is not part of subcommittee {}",
+        contribution.subcommittee_index
+    );
+
+    let epoch = compute_epoch_at_slot(contribution.slot);
+    let selection_proof_root = compute_signing_root(
+        SyncAggregatorSelectionData {
+            slot: contribution.slot,
+            subcommittee_index: contribution.subcommittee_index,
+        },
+        head_state.get_domain(DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF, Some(epoch)),
+    );
+    reject_if!(
+        !matches!(
+            contribution_and_proof
+                .selection_proof
+                .verify(&aggregator.pubkey, selection_proof_root.as_ref()),
+            Ok(true)
+        ),
+        "Invalid selection proof"
+    );
+
+    let contribution_and_proof_root = compute_signing_root(
+        contribution_and_proof,
+        head_state.get_domain(DOMAIN_CONTRIBUTION_AND_PROOF, Some(epoch)),
+    );
+    reject_if!(
+        !matches!(
+            signed_contribution_and_proof
+                .signature
+                .verify(&aggregator.pubkey, contribution_and_proof_root.as_ref()),
+            Ok(true)
+        ),
+        "Invalid aggregator signature"
+    );
+
+    let participant_pubkeys: Vec<&PubKey> =
+        subcommittee_pubkeys(sync_committee, contribution.subcommittee_index)
+            .enumerate()
+            .filter(|(index, _)| contribution.aggregation_bits.get(*index).unwrap_or(false))
+            .map(|(_, pubkey)| pubkey)
+            .collect();
+    let contribution_root = compute_signing_root(
+        contribution.beacon_block_root,
+        head_state.get_domain(DOMAIN_SYNC_COMMITTEE, Some(epoch)),
+    );
+    reject_if!(
+        !matches!(
+            contribution
+                .signature
+                .fast_aggregate_verify(participant_pubkeys, contribution_root.as_ref()),
+            Ok(true)
+        ),
+        "Invalid contribution signature"
+    );
+
+    seen_sync_contributions.insert((
+        contribution.slot,
+        aggregator_index,
+        contribution.subcommittee_index,
+    ));
+    Ok(())
+}
+
+/// Returns the sync committee signing messages at `slot`. They are included in the block at
+/// `slot + 1`, so at the end of a period they are signed by the next period's committee.
+fn sync_committee_at_slot(
+    head_state: &BeaconState,
+    slot: u64,
+) -> Result<&SyncCommittee, GossipValidationError> {
+    let state_period = compute_sync_committee_period(head_state.get_current_epoch());
+    let message_period = compute_sync_committee_period(compute_epoch_at_slot(slot + 1));
+    if message_period == state_period {
+        Ok(&head_state.current_sync_committee)
+    } else if message_period == state_period + 1 {
+        Ok(&head_state.next_sync_committee)
+    } else {
+        Err(GossipValidationError::Ignore(format!(
+            "No sync committee for slot {slot} in the head state of period {state_period}"
+        )))
+    }
+}
+
+/// Returns the pubkeys of the members of a sync subcommittee.
+fn subcommittee_pubkeys(
+    sync_committee: &SyncCommittee,
+    subcommittee_index: u64,
+) -> impl Iterator<Item = &PubKey> {
+    let subcommittee_size = (SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT) as usize;
+    sync_committee
+        .pubkeys
+        .iter()
+        .skip(subcommittee_index as usize * subcommittee_size)
+        .take(subcommittee_size)
+}
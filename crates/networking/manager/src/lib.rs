@@ -1,2 +1,8 @@
 pub mod config;
+pub mod fork_transition;
+pub mod gossipsub;
+pub mod p2p_sender;
+pub mod req_resp;
 pub mod service;
+pub mod status;
+pub mod subnet_service;
@@ -0,0 +1,147 @@
+use alloy_primitives::aliases::B32;
+use libp2p::{
+    PeerId,
+    gossipsub::{MessageAcceptance, MessageId},
+    swarm::ConnectionId,
+};
+use ream_discv5::{
+    eth2::EnrForkId,
+    subnet::{AttestationSubnets, SyncCommitteeSubnets},
+};
+use ream_p2p::{
+    channel::P2PMessages,
+    gossipsub::topics::GossipTopicKind,
+    req_resp::{
+        error::ReqRespError,
+        handler::RespMessage,
+        messages::{ResponseMessage, goodbye::Goodbye},
+    },
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::warn;
+
+/// Thin wrapper around the channel to the network service, used to answer inbound req/resp
+/// requests, report gossip validation results, manage peers and topic subscriptions.
+#[derive(Clone)]
+pub struct P2PSender(pub UnboundedSender<P2PMessages>);
+
+impl P2PSender {
+    pub fn send_response(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        message: ResponseMessage,
+    ) {
+        self.send(
+            peer_id,
+            connection_id,
+            stream_id,
+            RespMessage::Response(message),
+        );
+    }
+
+    pub fn send_end_of_stream(&self, peer_id: PeerId, connection_id: ConnectionId, stream_id: u64) {
+        self.send(peer_id, connection_id, stream_id, RespMessage::EndOfStream);
+    }
+
+    pub fn send_error_response(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        error: ReqRespError,
+    ) {
+        self.send(peer_id, connection_id, stream_id, RespMessage::Error(error));
+    }
+
+    pub fn send_validation_result(
+        &self,
+        message_id: MessageId,
+        propagation_source: PeerId,
+        acceptance: MessageAcceptance,
+    ) {
+        if let Err(err) = self.0.send(P2PMessages::ValidationResult {
+            message_id,
+            propagation_source,
+            acceptance,
+        }) {
+            warn!("Failed to send gossip validation result to network: {err:?}");
+        }
+    }
+
+    pub fn disconnect_peer(&self, peer_id: PeerId, reason: Goodbye) {
+        if let Err(err) = self.0.send(P2PMessages::DisconnectPeer { peer_id, reason }) {
+            warn!("Failed to send disconnect request to network: {err:?}");
+        }
+    }
+
+    pub fn update_gossipsub_score_params(&self, active_validators: u64, current_slot: u64) {
+        if let Err(err) = self.0.send(P2PMessages::UpdateGossipsubScoreParams {
+            active_validators,
+            current_slot,
+        }) {
+            warn!("Failed to send gossipsub score parameters update to network: {err:?}");
+        }
+    }
+
+    pub fn subscribe_to_topic(&self, kind: GossipTopicKind) {
+        if let Err(err) = self.0.send(P2PMessages::SubscribeToTopic(kind)) {
+            warn!("Failed to send topic subscription to network: {err:?}");
+        }
+    }
+
+    pub fn unsubscribe_from_topic(&self, kind: GossipTopicKind) {
+        if let Err(err) = self.0.send(P2PMessages::UnsubscribeFromTopic(kind)) {
+            warn!("Failed to send topic unsubscription to network: {err:?}");
+        }
+    }
+
+    pub fn subscribe_to_fork(&self, fork_digest: B32) {
+        if let Err(err) = self.0.send(P2PMessages::SubscribeToFork(fork_digest)) {
+            warn!("Failed to send fork subscription to network: {err:?}");
+        }
+    }
+
+    pub fn unsubscribe_from_fork(&self, fork_digest: B32) {
+        if let Err(err) = self.0.send(P2PMessages::UnsubscribeFromFork(fork_digest)) {
+            warn!("Failed to send fork unsubscription to network: {err:?}");
+        }
+    }
+
+    pub fn update_enr_fork_id(&self, enr_fork_id: EnrForkId) {
+        if let Err(err) = self.0.send(P2PMessages::UpdateEnrForkId(enr_fork_id)) {
+            warn!("Failed to send ENR fork id update to network: {err:?}");
+        }
+    }
+
+    pub fn update_subnets(
+        &self,
+        attestation_subnets: AttestationSubnets,
+        sync_committee_subnets: SyncCommitteeSubnets,
+    ) {
+        if let Err(err) = self.0.send(P2PMessages::UpdateSubnets {
+            attestation_subnets,
+            sync_committee_subnets,
+        }) {
+            warn!("Failed to send subnet update to network: {err:?}");
+        }
+    }
+
+    fn send(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        message: RespMessage,
+    ) {
+        if let Err(err) = self.0.send(P2PMessages::Response {
+            peer_id,
+            connection_id,
+            stream_id,
+            message,
+        }) {
+            warn!("Failed to send response to network: {err:?}");
+        }
+    }
+}
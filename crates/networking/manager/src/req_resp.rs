@@ -0,0 +1,553 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+use anyhow::anyhow;
+use libp2p::{PeerId, swarm::ConnectionId};
+use ream_beacon_chain::beacon_chain::BeaconChain;
+use ream_consensus::{
+    blob_sidecar::BlobIdentifier,
+    constants::{GENESIS_EPOCH, genesis_validators_root},
+    misc::compute_epoch_at_slot,
+};
+use ream_fork_choice::store::Store;
+use ream_light_client::cache::LightClientCache;
+use ream_network_spec::networks::network_spec;
+use ream_p2p::req_resp::{
+    error::ReqRespError,
+    messages::{
+        RequestMessage, ResponseMessage,
+        beacon_blocks::{BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request},
+        blob_sidecars::{BlobSidecarsByRangeV1Request, BlobSidecarsByRootV1Request},
+        light_client::{
+            LightClientBootstrapV1Request, LightClientUpdatesByRangeV1Request,
+            MAX_REQUEST_LIGHT_CLIENT_UPDATES,
+        },
+        status::Status,
+    },
+};
+use ream_storage::{
+    db::ReamDB,
+    tables::{Field, Table},
+};
+use tracing::{trace, warn};
+
+use crate::p2p_sender::P2PSender;
+
+/// Serves an inbound req/resp request from the local database, streaming every response chunk
+/// back to the requesting peer.
+///
+/// Only `Status` needs the fork choice store, everything else is read from `db` without holding
+/// its lock.
+pub async fn handle_req_resp_message(
+    beacon_chain: &BeaconChain,
+    db: &ReamDB,
+    p2p_sender: &P2PSender,
+    peer_id: PeerId,
+    connection_id: ConnectionId,
+    stream_id: u64,
+    message: RequestMessage,
+) {
+    let send_chunk = |message| p2p_sender.send_response(peer_id, connection_id, stream_id, message);
+
+    let result = match message {
+        RequestMessage::Status(status) => {
+            trace!("Received status from {peer_id}: {status:?}");
+            get_status(&*beacon_chain.store.lock().await)
+                .map(|status| send_chunk(ResponseMessage::Status(status)))
+                .map_err(ReqRespError::from)
+        }
+        RequestMessage::BeaconBlocksByRange(request) => {
+            handle_beacon_blocks_by_range(db, &request, &send_chunk)
+        }
+        RequestMessage::BeaconBlocksByRoot(request) => {
+            handle_beacon_blocks_by_root(db, &request, &send_chunk)
+        }
+        RequestMessage::BlobSidecarsByRange(request) => {
+            handle_blob_sidecars_by_range(db, &request, &send_chunk)
+        }
+        RequestMessage::BlobSidecarsByRoot(request) => {
+            handle_blob_sidecars_by_root(db, &request, &send_chunk)
+        }
+        RequestMessage::LightClientBootstrap(request) => handle_light_client_bootstrap(
+            &beacon_chain.light_client_cache.read(),
+            &request,
+            &send_chunk,
+        ),
+        RequestMessage::LightClientUpdatesByRange(request) => handle_light_client_updates_by_range(
+            &beacon_chain.light_client_cache.read(),
+            &request,
+            &send_chunk,
+        ),
+        RequestMessage::LightClientFinalityUpdate(_) => beacon_chain
+            .light_client_cache
+            .read()
+            .latest_finality_update()
+            .map(|update| {
+                send_chunk(ResponseMessage::LightClientFinalityUpdate(Arc::new(
+                    update.clone(),
+                )))
+            })
+            .ok_or_else(|| {
+                ReqRespError::ResourceUnavailable("No light client finality update".to_string())
+            }),
+        RequestMessage::LightClientOptimisticUpdate(_) => beacon_chain
+            .light_client_cache
+            .read()
+            .latest_optimistic_update()
+            .map(|update| {
+                send_chunk(ResponseMessage::LightClientOptimisticUpdate(Arc::new(
+                    update.clone(),
+                )))
+            })
+            .ok_or_else(|| {
+                ReqRespError::ResourceUnavailable("No light client optimistic update".to_string())
+            }),
+        RequestMessage::Ping(_) | RequestMessage::MetaData(_) | RequestMessage::Goodbye(_) => {
+            warn!("Unexpected request forwarded to the manager from {peer_id}: {message:?}");
+            return;
+        }
+    };
+
+    match result {
+        Ok(()) => p2p_sender.send_end_of_stream(peer_id, connection_id, stream_id),
+        Err(err) => {
+            warn!("Failed to serve request from {peer_id}: {err:?}");
+            p2p_sender.send_error_response(peer_id, connection_id, stream_id, err);
+        }
+    }
+}
+
+/// Builds our own `Status` from the fork choice store.
+pub fn get_status(store: &Store) -> anyhow::Result<Status> {
+    let db = &store.db;
+    let finalized_checkpoint = db.finalized_checkpoint_provider().get()?;
+    let head_root = store.get_head()?;
+    let head_block = db
+        .beacon_block_provider()
+        .get(head_root)?
+        .ok_or_else(|| anyhow!("Failed to find head block: {head_root}"))?;
+
+    Ok(Status {
+        fork_digest: network_spec().fork_digest_at_epoch(
+            compute_epoch_at_slot(store.get_current_slot()?),
+            genesis_validators_root(),
+        ),
+        // The finalized root is zero while the finalized epoch is still the genesis epoch
+        finalized_root: if finalized_checkpoint.epoch == GENESIS_EPOCH {
+            B256::ZERO
+        } else {
+            finalized_checkpoint.root
+        },
+        finalized_epoch: finalized_checkpoint.epoch,
+        head_root,
+        head_slot: head_block.message.slot,
+    })
+}
+
+/// Ensures the blocks starting at `start_slot` haven't been pruned or skipped by checkpoint sync.
+fn ensure_slot_available(db: &ReamDB, start_slot: u64) -> Result<(), ReqRespError> {
+    let oldest_slot = db
+        .slot_index_provider()
+        .get_oldest_slot()
+        .map_err(anyhow::Error::from)?
+        .ok_or_else(|| ReqRespError::ResourceUnavailable("No blocks available".to_string()))?;
+
+    if start_slot < oldest_slot {
+        return Err(ReqRespError::ResourceUnavailable(format!(
+            "Blocks before slot {oldest_slot} are not available"
+        )));
+    }
+
+    Ok(())
+}
+
+fn handle_beacon_blocks_by_range(
+    db: &ReamDB,
+    request: &BeaconBlocksByRangeV2Request,
+    send_chunk: &impl Fn(ResponseMessage),
+) -> Result<(), ReqRespError> {
+    let max_request_blocks = network_spec().max_request_blocks_deneb;
+    if request.count == 0 || request.count > max_request_blocks {
+        return Err(ReqRespError::InvalidData(format!(
+            "Invalid count {}, must be between 1 and {max_request_blocks}",
+            request.count
+        )));
+    }
+
+    ensure_slot_available(db, request.start_slot)?;
+
+    for slot in request.start_slot..request.start_slot.saturating_add(request.count) {
+        let Some(block_root) = db
+            .slot_index_provider()
+            .get(slot)
+            .map_err(anyhow::Error::from)?
+        else {
+            continue;
+        };
+
+        let Some(block) = db
+            .beacon_block_provider()
+            .get(block_root)
+            .map_err(anyhow::Error::from)?
+        else {
+            continue;
+        };
+
+        send_chunk(ResponseMessage::BeaconBlocksByRange(Arc::new(block)));
+    }
+
+    Ok(())
+}
+
+fn handle_beacon_blocks_by_root(
+    db: &ReamDB,
+    request: &BeaconBlocksByRootV2Request,
+    send_chunk: &impl Fn(ResponseMessage),
+) -> Result<(), ReqRespError> {
+    let max_request_blocks = network_spec().max_request_blocks_deneb;
+    if request.inner.len() as u64 > max_request_blocks {
+        return Err(ReqRespError::InvalidData(format!(
+            "Too many roots requested: {} > {max_request_blocks}",
+            request.inner.len()
+        )));
+    }
+
+    for block_root in request.inner.iter() {
+        if let Some(block) = db
+            .beacon_block_provider()
+            .get(*block_root)
+            .map_err(anyhow::Error::from)?
+        {
+            send_chunk(ResponseMessage::BeaconBlocksByRoot(Arc::new(block)));
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_blob_sidecars_by_range(
+    db: &ReamDB,
+    request: &BlobSidecarsByRangeV1Request,
+    send_chunk: &impl Fn(ResponseMessage),
+) -> Result<(), ReqRespError> {
+    let max_request_blob_sidecars = network_spec().max_request_blob_sidecars_electra;
+    if request.count == 0
+        || request
+            .count
+            .saturating_mul(network_spec().max_blobs_per_block_electra)
+            > max_request_blob_sidecars
+    {
+        return Err(ReqRespError::InvalidData(format!(
+            "Invalid count {}, at most {max_request_blob_sidecars} blob sidecars can be requested",
+            request.count
+        )));
+    }
+
+    ensure_slot_available(db, request.start_slot)?;
+
+    for slot in request.start_slot..request.start_slot.saturating_add(request.count) {
+        let Some(block_root) = db
+            .slot_index_provider()
+            .get(slot)
+            .map_err(anyhow::Error::from)?
+        else {
+            continue;
+        };
+
+        let Some(block) = db
+            .beacon_block_provider()
+            .get(block_root)
+            .map_err(anyhow::Error::from)?
+        else {
+            continue;
+        };
+
+        for index in 0..block.message.body.blob_kzg_commitments.len() as u64 {
+            let blob_and_proof = db
+                .blobs_and_proofs_provider()
+                .get(BlobIdentifier::new(block_root, index))
+                .map_err(anyhow::Error::from)?
+                .ok_or_else(|| {
+                    ReqRespError::ResourceUnavailable(format!(
+                        "Blob sidecar {index} for block {block_root} is not available"
+                    ))
+                })?;
+
+            send_chunk(ResponseMessage::BlobSidecarsByRange(Arc::new(
+                block.blob_sidecar(blob_and_proof, index)?,
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_blob_sidecars_by_root(
+    db: &ReamDB,
+    request: &BlobSidecarsByRootV1Request,
+    send_chunk: &impl Fn(ResponseMessage),
+) -> Result<(), ReqRespError> {
+    for blob_identifier in request.inner.iter() {
+        let Some(block) = db
+            .beacon_block_provider()
+            .get(blob_identifier.block_root)
+            .map_err(anyhow::Error::from)?
+        else {
+            continue;
+        };
+
+        let Some(blob_and_proof) = db
+            .blobs_and_proofs_provider()
+            .get(blob_identifier.clone())
+            .map_err(anyhow::Error::from)?
+        else {
+            continue;
+        };
+
+        send_chunk(ResponseMessage::BlobSidecarsByRoot(Arc::new(
+            block.blob_sidecar(blob_and_proof, blob_identifier.index)?,
+        )));
+    }
+
+    Ok(())
+}
+
+/// Bootstraps are only served for the finalized checkpoints cached as they were finalized, as
+/// building one for an arbitrary root could mean replaying a lot of blocks.
+fn handle_light_client_bootstrap(
+    light_client_cache: &LightClientCache,
+    request: &LightClientBootstrapV1Request,
+    send_chunk: &impl Fn(ResponseMessage),
+) -> Result<(), ReqRespError> {
+    let block_root = request.block_root;
+    let bootstrap = light_client_cache.bootstrap(block_root).ok_or_else(|| {
+        ReqRespError::ResourceUnavailable(format!("No bootstrap for block {block_root}"))
+    })?;
+
+    send_chunk(ResponseMessage::LightClientBootstrap(Arc::new(
+        bootstrap.clone(),
+    )));
+    Ok(())
+}
+
+fn handle_light_client_updates_by_range(
+    light_client_cache: &LightClientCache,
+    request: &LightClientUpdatesByRangeV1Request,
+    send_chunk: &impl Fn(ResponseMessage),
+) -> Result<(), ReqRespError> {
+    if request.count == 0 || request.count > MAX_REQUEST_LIGHT_CLIENT_UPDATES {
+        return Err(ReqRespError::InvalidData(format!(
+            "Invalid count {}, must be between 1 and {MAX_REQUEST_LIGHT_CLIENT_UPDATES}",
+            request.count
+        )));
+    }
+
+    for update in light_client_cache.updates_by_range(request.start_period, request.count) {
+        send_chunk(ResponseMessage::LightClientUpdatesByRange(Arc::new(update)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use ream_consensus::{
+        electra::beacon_block::SignedBeaconBlock,
+        execution_engine::rpc_types::get_blobs::{Blob, BlobAndProofV1},
+        polynomial_commitments::{kzg_commitment::KZGCommitment, kzg_proof::KZGProof},
+    };
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use ream_storage::test_utils::{import_test_chain, test_block};
+    use ssz_types::{FixedVector, VariableList};
+
+    use super::*;
+
+    fn block_slots(chunks: &[ResponseMessage]) -> Vec<u64> {
+        chunks
+            .iter()
+            .map(|chunk| match chunk {
+                ResponseMessage::BeaconBlocksByRange(block)
+                | ResponseMessage::BeaconBlocksByRoot(block) => block.message.slot,
+                chunk => panic!("Unexpected chunk {chunk:?}"),
+            })
+            .collect()
+    }
+
+    fn blob_sidecar_ids(chunks: &[ResponseMessage]) -> Vec<(u64, u64)> {
+        chunks
+            .iter()
+            .map(|chunk| match chunk {
+                ResponseMessage::BlobSidecarsByRange(blob_sidecar)
+                | ResponseMessage::BlobSidecarsByRoot(blob_sidecar) => (
+                    blob_sidecar.signed_block_header.message.slot,
+                    blob_sidecar.index,
+                ),
+                chunk => panic!("Unexpected chunk {chunk:?}"),
+            })
+            .collect()
+    }
+
+    /// Imports a block at `slot` committing to `blob_count` blobs, of which only the first
+    /// `stored_blobs` are stored.
+    fn import_block_with_blobs(
+        db: &ReamDB,
+        slot: u64,
+        parent_root: B256,
+        blob_count: usize,
+        stored_blobs: u64,
+    ) -> SignedBeaconBlock {
+        let mut block = test_block(slot, parent_root);
+        block.message.body.blob_kzg_commitments =
+            VariableList::from(vec![KZGCommitment::empty_for_testing(); blob_count]);
+        let block_root = block.message.block_root();
+        db.beacon_block_provider()
+            .insert(block_root, block.clone())
+            .unwrap();
+        for index in 0..stored_blobs {
+            db.blobs_and_proofs_provider()
+                .insert(
+                    BlobIdentifier::new(block_root, index),
+                    BlobAndProofV1 {
+                        blob: Blob {
+                            inner: FixedVector::default(),
+                        },
+                        proof: KZGProof::ZERO,
+                    },
+                )
+                .unwrap();
+        }
+        block
+    }
+
+    #[test]
+    fn test_beacon_blocks_by_range() {
+        initialize_test_network_spec();
+        let db = ReamDB::in_memory();
+        let roots = import_test_chain(&db, B256::ZERO, 4..=8);
+        let chunks = RefCell::new(vec![]);
+        let send_chunk = |chunk| chunks.borrow_mut().push(chunk);
+
+        // Blocks are served by slot from the slot index
+        handle_beacon_blocks_by_range(&db, &BeaconBlocksByRangeV2Request::new(5, 3), &send_chunk)
+            .unwrap();
+        assert_eq!(block_slots(&chunks.take()), vec![5, 6, 7]);
+
+        // Ranges reaching past the head end at it
+        handle_beacon_blocks_by_range(&db, &BeaconBlocksByRangeV2Request::new(7, 10), &send_chunk)
+            .unwrap();
+        assert_eq!(block_slots(&chunks.take()), vec![7, 8]);
+
+        for count in [0, network_spec().max_request_blocks_deneb + 1] {
+            assert!(matches!(
+                handle_beacon_blocks_by_range(
+                    &db,
+                    &BeaconBlocksByRangeV2Request::new(5, count),
+                    &send_chunk,
+                ),
+                Err(ReqRespError::InvalidData(_))
+            ));
+        }
+        assert!(matches!(
+            handle_beacon_blocks_by_range(
+                &db,
+                &BeaconBlocksByRangeV2Request::new(3, 2),
+                &send_chunk,
+            ),
+            Err(ReqRespError::ResourceUnavailable(_))
+        ));
+        assert!(chunks.take().is_empty());
+
+        handle_beacon_blocks_by_root(
+            &db,
+            &BeaconBlocksByRootV2Request {
+                inner: VariableList::from(vec![roots[3], B256::repeat_byte(1), roots[0]]),
+            },
+            &send_chunk,
+        )
+        .unwrap();
+        assert_eq!(block_slots(&chunks.take()), vec![7, 4]);
+    }
+
+    #[test]
+    fn test_blob_sidecars_by_range() {
+        initialize_test_network_spec();
+        let db = ReamDB::in_memory();
+        let parent_root = import_test_chain(&db, B256::ZERO, 4..=4)[0];
+        let block = import_block_with_blobs(&db, 5, parent_root, 2, 2);
+        let block_root = block.message.block_root();
+        import_block_with_blobs(&db, 6, block_root, 2, 1);
+        let chunks = RefCell::new(vec![]);
+        let send_chunk = |chunk| chunks.borrow_mut().push(chunk);
+
+        // Blocks without blobs have no sidecars
+        handle_blob_sidecars_by_range(
+            &db,
+            &BlobSidecarsByRangeV1Request {
+                start_slot: 4,
+                count: 2,
+            },
+            &send_chunk,
+        )
+        .unwrap();
+        assert_eq!(blob_sidecar_ids(&chunks.take()), vec![(5, 0), (5, 1)]);
+
+        // A block whose blobs weren't all stored fails the request
+        assert!(matches!(
+            handle_blob_sidecars_by_range(
+                &db,
+                &BlobSidecarsByRangeV1Request {
+                    start_slot: 5,
+                    count: 2,
+                },
+                &send_chunk,
+            ),
+            Err(ReqRespError::ResourceUnavailable(_))
+        ));
+        chunks.take();
+
+        let max_count = network_spec().max_request_blob_sidecars_electra
+            / network_spec().max_blobs_per_block_electra;
+        for count in [0, max_count + 1] {
+            assert!(matches!(
+                handle_blob_sidecars_by_range(
+                    &db,
+                    &BlobSidecarsByRangeV1Request {
+                        start_slot: 5,
+                        count,
+                    },
+                    &send_chunk,
+                ),
+                Err(ReqRespError::InvalidData(_))
+            ));
+        }
+        assert!(matches!(
+            handle_blob_sidecars_by_range(
+                &db,
+                &BlobSidecarsByRangeV1Request {
+                    start_slot: 3,
+                    count: 1,
+                },
+                &send_chunk,
+            ),
+            Err(ReqRespError::ResourceUnavailable(_))
+        ));
+        assert!(chunks.take().is_empty());
+
+        // Unknown blocks and blobs which weren't stored are skipped
+        handle_blob_sidecars_by_root(
+            &db,
+            &BlobSidecarsByRootV1Request {
+                inner: VariableList::from(vec![
+                    BlobIdentifier::new(block_root, 1),
+                    BlobIdentifier::new(B256::repeat_byte(1), 0),
+                    BlobIdentifier::new(block.message.parent_root, 0),
+                ]),
+            },
+            &send_chunk,
+        )
+        .unwrap();
+        assert_eq!(blob_sidecar_ids(&chunks.take()), vec![(5, 1)]);
+    }
+}
@@ -1,35 +1,68 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
-use ream_beacon_chain::beacon_chain::BeaconChain;
-use ream_consensus::constants::genesis_validators_root;
+use alloy_primitives::B256;
+use libp2p::{PeerId, swarm::ConnectionId};
+use ream_beacon_chain::{
+    beacon_chain::BeaconChain,
+    slot_clock::{SlotClock, SlotClockService, SlotEvent, SystemClock},
+};
+use ream_consensus::{
+    constants::{GENESIS_EPOCH, SLOTS_PER_EPOCH, genesis_validators_root},
+    misc::compute_epoch_at_slot,
+};
 use ream_discv5::{
     config::DiscoveryConfig,
+    eth2::EnrForkId,
     subnet::{AttestationSubnets, SyncCommitteeSubnets},
 };
 use ream_execution_engine::ExecutionEngine;
 use ream_executor::ReamExecutor;
 use ream_network_spec::networks::network_spec;
 use ream_p2p::{
-    channel::P2PMessages,
     config::NetworkConfig,
+    constants::NETWORK_DIR,
     gossipsub::{
         configurations::GossipsubConfig,
         topics::{GossipTopic, GossipTopicKind},
     },
     network::{Network, ReamNetworkEvent},
+    peer_manager::PeerManagerConfig,
+    req_resp::{messages::RequestMessage, rate_limiter::RateLimiterConfig},
+};
+use ream_storage::{db::ReamDB, tables::Field};
+use ream_syncer::{backfill::BackfillSyncer, block_range::BlockRangeSyncer};
+use tokio::{
+    sync::{broadcast, mpsc},
+    task::JoinHandle,
+    time::interval,
 };
-use ream_storage::db::ReamDB;
-use ream_syncer::block_range::BlockRangeSyncer;
-use tokio::{sync::mpsc, task::JoinHandle};
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::config::ManagerConfig;
+use crate::{
+    config::ManagerConfig,
+    fork_transition::ForkTransitionService,
+    gossipsub::{
+        handle_gossipsub_message, update_gossipsub_score_params, validate::GossipValidator,
+    },
+    p2p_sender::P2PSender,
+    req_resp::handle_req_resp_message,
+    status::{STATUS_INTERVAL, exchange_status, process_peer_status},
+    subnet_service::SubnetService,
+};
 
 pub struct ManagerService {
+    pub beacon_chain: Arc<BeaconChain>,
+    pub ream_db: ReamDB,
     pub manager_receiver: mpsc::UnboundedReceiver<ReamNetworkEvent>,
-    pub p2p_sender: mpsc::UnboundedSender<P2PMessages>,
+    pub p2p_sender: P2PSender,
     pub network_handle: JoinHandle<()>,
+    pub slot_clock_handle: JoinHandle<()>,
+    pub slot_event_sender: broadcast::Sender<SlotEvent>,
     pub block_range_syncer: BlockRangeSyncer,
+    pub backfill_handle: JoinHandle<()>,
+    pub gossip_validator: GossipValidator,
+    pub subnet_service: SubnetService,
+    pub fork_transition_service: ForkTransitionService,
 }
 
 impl ManagerService {
@@ -37,42 +70,77 @@ impl ManagerService {
         async_executor: ReamExecutor,
         config: ManagerConfig,
         ream_db: ReamDB,
+        ream_dir: PathBuf,
     ) -> anyhow::Result<Self> {
-        let discv5_config = discv5::ConfigBuilder::new(discv5::ListenConfig::from_ip(
-            config.socket_address,
-            config.discovery_port,
-        ))
-        .build();
+        let network_dir = ream_dir.join(NETWORK_DIR);
+
+        // Topics and the ENR start out on the fork of the current epoch
+        let genesis_time = ream_db.genesis_time_provider().get()?;
+        let current_epoch = SlotClock::new(genesis_time, SystemClock)
+            .now()
+            .map_or(GENESIS_EPOCH, |now| compute_epoch_at_slot(now.slot));
+
+        let listen_address = config.listen_address()?;
+        let discv5_config =
+            discv5::ConfigBuilder::new(listen_address.discv5_listen_config()).build();
 
         let bootnodes = config.bootnodes.to_enrs(network_spec().network.clone());
         let discv5_config = DiscoveryConfig {
             discv5_config,
             bootnodes,
-            socket_address: config.socket_address,
-            socket_port: config.socket_port,
-            discovery_port: config.discovery_port,
+            listen_address: listen_address.clone(),
             disable_discovery: config.disable_discovery,
             attestation_subnets: AttestationSubnets::new(),
             sync_committee_subnets: SyncCommitteeSubnets::new(),
+            enr_fork_id: EnrForkId::at_epoch(current_epoch, genesis_validators_root()),
+            data_dir: Some(network_dir.clone()),
         };
 
-        let mut gossipsub_config = GossipsubConfig::default();
-        gossipsub_config.set_topics(vec![GossipTopic {
-            fork: network_spec().fork_digest(genesis_validators_root()),
-            kind: GossipTopicKind::BeaconBlock,
-        }]);
+        let mut gossipsub_config = GossipsubConfig {
+            direct_peers: config.direct_peers,
+            ..Default::default()
+        };
+        let fork_digest =
+            network_spec().fork_digest_at_epoch(current_epoch, genesis_validators_root());
+        gossipsub_config.set_topics(
+            [
+                GossipTopicKind::BeaconBlock,
+                GossipTopicKind::AggregateAndProof,
+                GossipTopicKind::AttesterSlashing,
+            ]
+            .into_iter()
+            .chain(
+                (0..network_spec().blob_sidecar_subnet_count_electra)
+                    .map(GossipTopicKind::BlobSidecar),
+            )
+            .map(|kind| GossipTopic {
+                fork: fork_digest,
+                kind,
+            })
+            .collect(),
+        );
 
         let network_config = NetworkConfig {
-            socket_address: config.socket_address,
-            socket_port: config.socket_port,
+            listen_address,
             discv5_config,
             gossipsub_config,
+            peer_manager_config: PeerManagerConfig {
+                target_peers: config.target_peers,
+                max_peers: config.max_peers,
+                static_peers: config.static_peers,
+                trusted_peers: config.trusted_peers.into_iter().collect(),
+                ..Default::default()
+            },
+            rate_limiter_config: RateLimiterConfig::default(),
+            data_dir: network_dir,
+            private_key_file: config.private_key_file,
         };
 
         let (manager_sender, manager_receiver) = mpsc::unbounded_channel();
         let (p2p_sender, p2p_receiver) = mpsc::unbounded_channel();
 
         let network = Network::init(async_executor, &network_config).await?;
+        let node_id = B256::from(network.enr().node_id().raw());
         let network_handle = tokio::spawn(async move {
             network.start(manager_sender, p2p_receiver).await;
         });
@@ -84,25 +152,146 @@ impl ManagerService {
         } else {
             None
         };
-        let beacon_chain = Arc::new(BeaconChain::new(ream_db, execution_engine));
-        let block_range_syncer = BlockRangeSyncer::new(beacon_chain, p2p_sender.clone());
+        let beacon_chain = Arc::new(BeaconChain::new(ream_db.clone(), execution_engine));
+
+        let slot_clock_service = SlotClockService::new(
+            beacon_chain.clone(),
+            SlotClock::new(genesis_time, SystemClock),
+        );
+        let slot_event_sender = slot_clock_service.sender();
+        let slot_clock_handle = tokio::spawn(slot_clock_service.start());
+
+        let block_range_syncer = BlockRangeSyncer::new(beacon_chain.clone(), p2p_sender.clone());
+        let backfill_syncer = BackfillSyncer::new(
+            ream_db.clone(),
+            p2p_sender.clone(),
+            block_range_syncer.peer_statuses.clone(),
+            config.backfill_target,
+        );
+        let backfill_handle = tokio::spawn(backfill_syncer.start());
 
         Ok(Self {
+            beacon_chain,
+            ream_db,
             manager_receiver,
-            p2p_sender,
+            p2p_sender: P2PSender(p2p_sender.clone()),
             network_handle,
+            slot_clock_handle,
+            slot_event_sender,
             block_range_syncer,
+            backfill_handle,
+            gossip_validator: GossipValidator::new(),
+            subnet_service: SubnetService::new(node_id, P2PSender(p2p_sender.clone())),
+            fork_transition_service: ForkTransitionService::new(
+                genesis_validators_root(),
+                P2PSender(p2p_sender),
+            ),
         })
     }
 
-    pub async fn start(self) {
-        let mut manager_receiver = self.manager_receiver;
+    pub async fn start(mut self) {
+        tokio::spawn(self.block_range_syncer.clone().start());
+
+        let mut status_interval = interval(STATUS_INTERVAL);
+        let mut slot_events = self.slot_event_sender.subscribe();
+        update_gossipsub_score_params(&self.beacon_chain, &self.p2p_sender).await;
+        match self.beacon_chain.store.lock().await.get_current_slot() {
+            Ok(slot) => {
+                self.fork_transition_service
+                    .on_epoch(compute_epoch_at_slot(slot));
+                self.subnet_service.on_slot(slot);
+            }
+            Err(err) => warn!("Failed to get current slot: {err:?}"),
+        }
         loop {
             tokio::select! {
-                Some(event) = manager_receiver.recv() => {
-                     info!("Received event: {:?}", event);
+                Some(event) = self.manager_receiver.recv() => {
+                    match event {
+                        ReamNetworkEvent::PeerConnectedIncoming(peer_id) | ReamNetworkEvent::PeerConnectedOutgoing(peer_id) => {
+                            self.spawn_status_exchange(peer_id);
+                        }
+                        ReamNetworkEvent::RequestMessage { peer_id, stream_id, connection_id, message } => {
+                            if let RequestMessage::Status(status) = &message {
+                                process_peer_status(
+                                    &self.beacon_chain,
+                                    &self.p2p_sender,
+                                    &self.block_range_syncer,
+                                    peer_id,
+                                    status.clone(),
+                                )
+                                .await;
+                            }
+                            self.spawn_req_resp(peer_id, connection_id, stream_id, message);
+                        }
+                        ReamNetworkEvent::GossipsubMessage { propagation_source, message_id, topic, message } => {
+                            handle_gossipsub_message(
+                                &self.beacon_chain,
+                                &mut self.gossip_validator,
+                                &self.p2p_sender,
+                                propagation_source,
+                                message_id,
+                                topic,
+                                message,
+                            )
+                            .await;
+                        }
+                        ReamNetworkEvent::PeerDisconnected(peer_id) => {
+                            self.block_range_syncer.remove_peer(&peer_id);
+                        }
+                        event => info!("Received event: {event:?}"),
+                    }
+                }
+                Ok(slot_event) = slot_events.recv() => {
+                    if slot_event.interval == 0 {
+                        if slot_event.slot % SLOTS_PER_EPOCH == 0 {
+                            self.fork_transition_service.on_epoch(compute_epoch_at_slot(slot_event.slot));
+                            update_gossipsub_score_params(&self.beacon_chain, &self.p2p_sender).await;
+                        }
+                        self.subnet_service.on_slot(slot_event.slot);
+                    }
+                }
+                _ = status_interval.tick() => {
+                    let peers: Vec<PeerId> = self.block_range_syncer.peer_statuses.read().keys().copied().collect();
+                    for peer_id in peers {
+                        self.spawn_status_exchange(peer_id);
+                    }
                 }
             }
         }
     }
+
+    /// Serves a request in its own task, so that reading the database doesn't hold up the
+    /// events of the other peers.
+    fn spawn_req_resp(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        message: RequestMessage,
+    ) {
+        let beacon_chain = self.beacon_chain.clone();
+        let ream_db = self.ream_db.clone();
+        let p2p_sender = self.p2p_sender.clone();
+        tokio::spawn(async move {
+            handle_req_resp_message(
+                &beacon_chain,
+                &ream_db,
+                &p2p_sender,
+                peer_id,
+                connection_id,
+                stream_id,
+                message,
+            )
+            .await;
+        });
+    }
+
+    fn spawn_status_exchange(&self, peer_id: PeerId) {
+        let beacon_chain = self.beacon_chain.clone();
+        let p2p_sender = self.p2p_sender.clone();
+        let block_range_syncer = self.block_range_syncer.clone();
+        tokio::spawn(async move {
+            exchange_status(&beacon_chain, &p2p_sender, &block_range_syncer, peer_id).await;
+        });
+    }
 }
@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use libp2p::PeerId;
+use ream_beacon_chain::beacon_chain::BeaconChain;
+use ream_consensus::constants::GENESIS_EPOCH;
+use ream_fork_choice::store::Store;
+use ream_p2p::{
+    channel::{P2PMessages, P2PResponse},
+    req_resp::messages::{ResponseMessage, goodbye::Goodbye, status::Status},
+};
+use ream_syncer::block_range::BlockRangeSyncer;
+use tokio::{sync::mpsc, time::timeout};
+use tracing::{debug, info, warn};
+
+use crate::{p2p_sender::P2PSender, req_resp::get_status};
+
+/// How often we re-exchange `Status` with connected peers to keep their chain state fresh.
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How long a peer has to answer a `Status` request.
+pub const STATUS_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends our `Status` to `peer_id` and processes the one it answers with.
+pub async fn exchange_status(
+    beacon_chain: &BeaconChain,
+    p2p_sender: &P2PSender,
+    block_range_syncer: &BlockRangeSyncer,
+    peer_id: PeerId,
+) {
+    let local_status = {
+        let store = beacon_chain.store.lock().await;
+        match get_status(&store) {
+            Ok(status) => status,
+            Err(err) => {
+                warn!("Failed to build local status: {err:?}");
+                return;
+            }
+        }
+    };
+
+    match request_status(p2p_sender, peer_id, local_status).await {
+        Ok(status) => {
+            process_peer_status(
+                beacon_chain,
+                p2p_sender,
+                block_range_syncer,
+                peer_id,
+                status,
+            )
+            .await
+        }
+        Err(err) => debug!("Status exchange with {peer_id} failed: {err:?}"),
+    }
+}
+
+/// Checks a peer's `Status` against our own chain and records it for the syncer, disconnecting
+/// the peer if it is on another network or finalized a conflicting checkpoint.
+pub async fn process_peer_status(
+    beacon_chain: &BeaconChain,
+    p2p_sender: &P2PSender,
+    block_range_syncer: &BlockRangeSyncer,
+    peer_id: PeerId,
+    status: Status,
+) {
+    let result = {
+        let store = beacon_chain.store.lock().await;
+        get_status(&store).map(|local_status| validate_status(&store, &local_status, &status))
+    };
+
+    match result {
+        Ok(Ok(())) => block_range_syncer.update_peer_status(peer_id, status),
+        Ok(Err(reason)) => {
+            info!("Disconnecting peer {peer_id} with status {status:?}: {reason:?}");
+            block_range_syncer.remove_peer(&peer_id);
+            p2p_sender.disconnect_peer(peer_id, reason);
+        }
+        Err(err) => warn!("Failed to build local status: {err:?}"),
+    }
+}
+
+/// Returns the `Goodbye` reason to disconnect with if `remote` is incompatible with our chain.
+pub fn validate_status(store: &Store, local: &Status, remote: &Status) -> Result<(), Goodbye> {
+    if local.fork_digest != remote.fork_digest {
+        return Err(Goodbye::IrrelevantNetwork);
+    }
+
+    // A peer that finalized a checkpoint we have already passed must agree with our chain. The
+    // genesis checkpoint is always advertised with a zero root, and checkpoints from before our
+    // checkpoint sync anchor can't be verified, so those are accepted.
+    if remote.finalized_epoch == GENESIS_EPOCH || remote.finalized_epoch > local.finalized_epoch {
+        return Ok(());
+    }
+
+    match store.get_checkpoint_block(local.finalized_root, remote.finalized_epoch) {
+        Ok(finalized_root) if finalized_root != remote.finalized_root => {
+            Err(Goodbye::IrrelevantNetwork)
+        }
+        _ => Ok(()),
+    }
+}
+
+async fn request_status(
+    p2p_sender: &P2PSender,
+    peer_id: PeerId,
+    status: Status,
+) -> anyhow::Result<Status> {
+    let (callback, mut receiver) = mpsc::channel(2);
+    p2p_sender
+        .0
+        .send(P2PMessages::RequestStatus {
+            peer_id,
+            status,
+            callback,
+        })
+        .map_err(|err| anyhow!("Failed to send status request: {err:?}"))?;
+
+    timeout(STATUS_REQUEST_TIMEOUT, async {
+        let mut remote_status = None;
+        while let Some(response) = receiver.recv().await {
+            match response? {
+                P2PResponse::ResponseMessage(ResponseMessage::Status(status)) => {
+                    remote_status = Some(status)
+                }
+                P2PResponse::ResponseMessage(message) => {
+                    bail!("Unexpected response to status request: {message:?}")
+                }
+                P2PResponse::EndOfStream => {
+                    return remote_status
+                        .ok_or_else(|| anyhow!("Peer closed the stream without a status"));
+                }
+            }
+        }
+
+        bail!("Status response channel closed")
+    })
+    .await
+    .map_err(|_| anyhow!("Status request timed out"))?
+}
@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::B256;
+use ream_consensus::misc::compute_epoch_at_slot;
+use ream_discv5::subnet::{AttestationSubnets, SyncCommitteeSubnets};
+use ream_p2p::gossipsub::topics::GossipTopicKind;
+use ream_validator::attestation::compute_subscribed_subnets;
+use tracing::{info, warn};
+
+use crate::p2p_sender::P2PSender;
+
+/// Keeps our attestation and sync committee subnet subscriptions in line with the spec and with
+/// the duties of our validators.
+///
+/// Long-lived attestation subnets are derived from our node id and advertised in the ENR.
+/// Short-lived subscriptions are only needed until a validator duty is done and are not
+/// advertised.
+pub struct SubnetService {
+    node_id: B256,
+    p2p_sender: P2PSender,
+    long_lived_subnets: HashSet<u64>,
+    /// Attestation subnets needed for validator duties, with the last slot they are needed for.
+    short_lived_subnets: HashMap<u64, u64>,
+    /// Sync committee subnets of our validators, with the last epoch they are needed for.
+    sync_committee_subnets: HashMap<u64, u64>,
+    subscribed_topics: HashSet<GossipTopicKind>,
+    advertised_attestation_subnets: Option<AttestationSubnets>,
+    advertised_sync_committee_subnets: Option<SyncCommitteeSubnets>,
+}
+
+impl SubnetService {
+    pub fn new(node_id: B256, p2p_sender: P2PSender) -> Self {
+        Self {
+            node_id,
+            p2p_sender,
+            long_lived_subnets: HashSet::new(),
+            short_lived_subnets: HashMap::new(),
+            sync_committee_subnets: HashMap::new(),
+            subscribed_topics: HashSet::new(),
+            advertised_attestation_subnets: None,
+            advertised_sync_committee_subnets: None,
+        }
+    }
+
+    /// Subscribes to an attestation subnet until `until_slot`, e.g. to aggregate attestations.
+    pub fn subscribe_to_attestation_subnet(&mut self, subnet_id: u64, until_slot: u64) {
+        let expiry = self.short_lived_subnets.entry(subnet_id).or_default();
+        *expiry = (*expiry).max(until_slot);
+        self.update_subscriptions();
+    }
+
+    /// Subscribes to sync committee subnets until `until_epoch`, usually the end of the sync
+    /// committee period our validators are part of.
+    pub fn subscribe_to_sync_committee_subnets(
+        &mut self,
+        subnet_ids: impl IntoIterator<Item = u64>,
+        until_epoch: u64,
+    ) {
+        for subnet_id in subnet_ids {
+            let expiry = self.sync_committee_subnets.entry(subnet_id).or_default();
+            *expiry = (*expiry).max(until_epoch);
+        }
+        self.update_subscriptions();
+    }
+
+    /// Drops expired subscriptions and rotates the long-lived subnets.
+    pub fn on_slot(&mut self, slot: u64) {
+        let epoch = compute_epoch_at_slot(slot);
+        self.short_lived_subnets
+            .retain(|_, until_slot| *until_slot >= slot);
+        self.sync_committee_subnets
+            .retain(|_, until_epoch| *until_epoch >= epoch);
+
+        match compute_subscribed_subnets(self.node_id, epoch) {
+            Ok(subnets) => self.long_lived_subnets = subnets.into_iter().collect(),
+            Err(err) => warn!("Failed to compute long-lived attestation subnets: {err:?}"),
+        }
+        self.update_subscriptions();
+    }
+
+    pub fn long_lived_subnets(&self) -> &HashSet<u64> {
+        &self.long_lived_subnets
+    }
+
+    /// Subscribes to and unsubscribes from topics so they match the wanted subnets, and updates
+    /// the ENR if the advertised subnets changed.
+    fn update_subscriptions(&mut self) {
+        let wanted_topics: HashSet<GossipTopicKind> = self
+            .long_lived_subnets
+            .iter()
+            .chain(self.short_lived_subnets.keys())
+            .map(|subnet_id| GossipTopicKind::BeaconAttestation(*subnet_id))
+            .chain(
+                self.sync_committee_subnets
+                    .keys()
+                    .map(|subnet_id| GossipTopicKind::SyncCommittee(*subnet_id)),
+            )
+            .collect();
+
+        for kind in wanted_topics.difference(&self.subscribed_topics) {
+            self.p2p_sender.subscribe_to_topic(*kind);
+        }
+        for kind in self.subscribed_topics.difference(&wanted_topics) {
+            self.p2p_sender.unsubscribe_from_topic(*kind);
+        }
+        self.subscribed_topics = wanted_topics;
+
+        let mut attestation_subnets = AttestationSubnets::new();
+        for subnet_id in &self.long_lived_subnets {
+            if let Err(err) = attestation_subnets.enable_attestation_subnet(*subnet_id as u8) {
+                warn!("Failed to enable attestation subnet {subnet_id}: {err:?}");
+            }
+        }
+        let mut sync_committee_subnets = SyncCommitteeSubnets::new();
+        for subnet_id in self.sync_committee_subnets.keys() {
+            if let Err(err) = sync_committee_subnets.enable_sync_committee_subnet(*subnet_id as u8)
+            {
+                warn!("Failed to enable sync committee subnet {subnet_id}: {err:?}");
+            }
+        }
+
+        if self.advertised_attestation_subnets.as_ref() != Some(&attestation_subnets)
+            || self.advertised_sync_committee_subnets.as_ref() != Some(&sync_committee_subnets)
+        {
+            info!(
+                "Advertising attestation subnets {:?} and sync committee subnets {:?}",
+                self.long_lived_subnets,
+                self.sync_committee_subnets.keys().collect::<Vec<_>>()
+            );
+            self.p2p_sender
+                .update_subnets(attestation_subnets.clone(), sync_committee_subnets.clone());
+            self.advertised_attestation_subnets = Some(attestation_subnets);
+            self.advertised_sync_committee_subnets = Some(sync_committee_subnets);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use ream_p2p::channel::P2PMessages;
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    fn drain_topic_changes(
+        receiver: &mut mpsc::UnboundedReceiver<P2PMessages>,
+    ) -> (Vec<GossipTopicKind>, Vec<GossipTopicKind>) {
+        let mut subscribed = vec![];
+        let mut unsubscribed = vec![];
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                P2PMessages::SubscribeToTopic(kind) => subscribed.push(kind),
+                P2PMessages::UnsubscribeFromTopic(kind) => unsubscribed.push(kind),
+                _ => {}
+            }
+        }
+        (subscribed, unsubscribed)
+    }
+
+    #[test]
+    fn test_short_lived_subscription_expires() {
+        initialize_test_network_spec();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut subnet_service = SubnetService::new(B256::ZERO, P2PSender(sender));
+
+        subnet_service.on_slot(0);
+        let (subscribed, _) = drain_topic_changes(&mut receiver);
+        assert_eq!(
+            subscribed.len(),
+            subnet_service.long_lived_subnets().len(),
+            "Only the long-lived subnets should be subscribed"
+        );
+
+        let short_lived_subnet = (0..64)
+            .find(|subnet_id| !subnet_service.long_lived_subnets().contains(subnet_id))
+            .unwrap();
+        subnet_service.subscribe_to_attestation_subnet(short_lived_subnet, 2);
+        let (subscribed, _) = drain_topic_changes(&mut receiver);
+        assert_eq!(
+            subscribed,
+            vec![GossipTopicKind::BeaconAttestation(short_lived_subnet)]
+        );
+
+        subnet_service.on_slot(2);
+        assert_eq!(drain_topic_changes(&mut receiver), (vec![], vec![]));
+
+        subnet_service.on_slot(3);
+        let (_, unsubscribed) = drain_topic_changes(&mut receiver);
+        assert_eq!(
+            unsubscribed,
+            vec![GossipTopicKind::BeaconAttestation(short_lived_subnet)]
+        );
+    }
+}
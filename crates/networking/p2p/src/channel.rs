@@ -1,7 +1,38 @@
-use libp2p::PeerId;
-use tokio::sync::mpsc;
+use alloy_primitives::aliases::B32;
+use libp2p::{
+    PeerId,
+    gossipsub::{MessageAcceptance, MessageId},
+    swarm::ConnectionId,
+};
+use ream_consensus::{
+    attester_slashing::AttesterSlashing, blob_sidecar::BlobSidecar,
+    bls_to_execution_change::SignedBLSToExecutionChange, electra::beacon_block::SignedBeaconBlock,
+    proposer_slashing::ProposerSlashing, single_attestation::SingleAttestation,
+    voluntary_exit::SignedVoluntaryExit,
+};
+use ream_discv5::{
+    eth2::EnrForkId,
+    subnet::{AttestationSubnets, SyncCommitteeSubnets},
+};
+use ream_validator::{
+    aggregate_and_proof::SignedAggregateAndProof,
+    contribution_and_proof::SignedContributionAndProof, sync_committee::SyncCommitteeMessage,
+};
+use tokio::sync::{mpsc, oneshot};
 
-use crate::req_resp::messages::ResponseMessage;
+use crate::{
+    gossipsub::{error::GossipsubError, topics::GossipTopicKind},
+    peer_manager::peer_info::PeerAction,
+    req_resp::{
+        handler::RespMessage,
+        messages::{ResponseMessage, goodbye::Goodbye, status::Status},
+    },
+};
+
+/// Receives the outcome of publishing a gossip message. It is
+/// [GossipsubError::InsufficientPeers] if no peers are subscribed to the topic, or if the message
+/// was published to fewer mesh peers than the gossipsub `mesh_n_low`.
+pub type PublishCallback = oneshot::Sender<Result<(), GossipsubError>>;
 
 pub enum P2PResponse {
     ResponseMessage(ResponseMessage),
@@ -15,4 +46,94 @@ pub enum P2PMessages {
         count: u64,
         callback: mpsc::Sender<anyhow::Result<P2PResponse>>,
     },
+    RequestStatus {
+        peer_id: PeerId,
+        status: Status,
+        callback: mpsc::Sender<anyhow::Result<P2PResponse>>,
+    },
+    Response {
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        message: RespMessage,
+    },
+    ValidationResult {
+        message_id: MessageId,
+        propagation_source: PeerId,
+        acceptance: MessageAcceptance,
+    },
+    DisconnectPeer {
+        peer_id: PeerId,
+        reason: Goodbye,
+    },
+    /// Lowers the score of a peer which misbehaved outside of the network, e.g. served blocks
+    /// which failed to import.
+    ReportPeer {
+        peer_id: PeerId,
+        action: PeerAction,
+        source: &'static str,
+    },
+    /// Recompute the gossipsub topic score parameters, which depend on the size of the validator
+    /// set.
+    UpdateGossipsubScoreParams {
+        active_validators: u64,
+        current_slot: u64,
+    },
+    /// Subscribe to a topic under every fork digest we are subscribed to.
+    SubscribeToTopic(GossipTopicKind),
+    UnsubscribeFromTopic(GossipTopicKind),
+    /// Subscribe to all our topics under the fork digest of an upcoming fork.
+    SubscribeToFork(B32),
+    /// Drop all topics of a fork digest once its fork is behind us.
+    UnsubscribeFromFork(B32),
+    /// Advertise our long-lived subnets in the ENR and metadata.
+    UpdateSubnets {
+        attestation_subnets: AttestationSubnets,
+        sync_committee_subnets: SyncCommitteeSubnets,
+    },
+    /// Advertise the current fork digest and the next scheduled fork in the ENR.
+    UpdateEnrForkId(EnrForkId),
+    PublishBeaconBlock {
+        signed_block: Box<SignedBeaconBlock>,
+        callback: PublishCallback,
+    },
+    /// Published on the subnet derived from the blob index.
+    PublishBlobSidecar {
+        blob_sidecar: Box<BlobSidecar>,
+        callback: PublishCallback,
+    },
+    PublishAttestation {
+        subnet_id: u64,
+        single_attestation: Box<SingleAttestation>,
+        callback: PublishCallback,
+    },
+    PublishAggregateAndProof {
+        aggregate_and_proof: Box<SignedAggregateAndProof>,
+        callback: PublishCallback,
+    },
+    PublishSyncCommitteeMessage {
+        subnet_id: u64,
+        sync_committee_message: Box<SyncCommitteeMessage>,
+        callback: PublishCallback,
+    },
+    PublishContributionAndProof {
+        contribution_and_proof: Box<SignedContributionAndProof>,
+        callback: PublishCallback,
+    },
+    PublishVoluntaryExit {
+        voluntary_exit: Box<SignedVoluntaryExit>,
+        callback: PublishCallback,
+    },
+    PublishProposerSlashing {
+        proposer_slashing: Box<ProposerSlashing>,
+        callback: PublishCallback,
+    },
+    PublishAttesterSlashing {
+        attester_slashing: Box<AttesterSlashing>,
+        callback: PublishCallback,
+    },
+    PublishBlsToExecutionChange {
+        bls_to_execution_change: Box<SignedBLSToExecutionChange>,
+        callback: PublishCallback,
+    },
 }
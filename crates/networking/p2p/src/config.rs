@@ -1,15 +1,28 @@
-use std::net::IpAddr;
+use std::path::PathBuf;
 
-use ream_discv5::config::DiscoveryConfig;
+use ream_discv5::{config::DiscoveryConfig, listen_address::ListenAddress};
 
-use crate::gossipsub::configurations::GossipsubConfig;
+use crate::{
+    gossipsub::configurations::GossipsubConfig, peer_manager::PeerManagerConfig,
+    req_resp::rate_limiter::RateLimiterConfig,
+};
 
 pub struct NetworkConfig {
-    pub socket_address: IpAddr,
-
-    pub socket_port: u16,
+    /// Addresses and ports to listen on for TCP and QUIC, for each IP family
+    pub listen_address: ListenAddress,
 
     pub discv5_config: DiscoveryConfig,
 
     pub gossipsub_config: GossipsubConfig,
+
+    pub peer_manager_config: PeerManagerConfig,
+
+    /// Quotas of the requests we serve to each peer
+    pub rate_limiter_config: RateLimiterConfig,
+
+    /// Directory the network key and ENR are persisted in
+    pub data_dir: PathBuf,
+
+    /// Key file to use instead of the one stored in `data_dir`
+    pub private_key_file: Option<PathBuf>,
 }
@@ -1,6 +1,27 @@
+use std::time::Duration;
+
 use alloy_primitives::{aliases::B32, fixed_bytes};
 
 /// The maximum allowed size of uncompressed payload in gossipsub messages and RPC chunks
 pub const MAX_PAYLOAD_SIZE: u64 = 10485760;
 pub const MESSAGE_DOMAIN_VALID_SNAPPY: B32 = fixed_bytes!("0x01000000");
 pub const MESSAGE_DOMAIN_INVALID_SNAPPY: B32 = fixed_bytes!("0x00000000");
+
+/// Subscribed subnets with fewer mesh peers than this trigger a discovery query for more peers on
+/// the subnet. Up to this many mesh peers per subnet are protected from pruning.
+pub const MIN_SUBNET_PEERS: usize = 3;
+
+/// How often the ENRs of known peers are persisted, besides on shutdown
+pub const PERSIST_KNOWN_ENRS_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How long a peer is given to receive our `Goodbye` before we disconnect from it
+pub const GOODBYE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often peers whose `Goodbye` timed out are disconnected
+pub const PENDING_DISCONNECTS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Directory inside the ream data directory holding the network key and ENR
+pub const NETWORK_DIR: &str = "network";
+
+/// File the node's secp256k1 network key is stored in, inside the network directory
+pub const NETWORK_KEY_FILENAME: &str = "key";
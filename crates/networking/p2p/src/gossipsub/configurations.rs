@@ -5,12 +5,17 @@ use ream_consensus::constants::{SECONDS_PER_SLOT, SLOTS_PER_EPOCH};
 use sha2::{Digest, Sha256};
 
 use super::topics::GossipTopic;
-use crate::{constants::MESSAGE_DOMAIN_VALID_SNAPPY, utils::max_message_size};
+use crate::{
+    constants::MESSAGE_DOMAIN_VALID_SNAPPY, peer_manager::static_peer::StaticPeer,
+    utils::max_message_size,
+};
 
 #[derive(Debug, Clone)]
 pub struct GossipsubConfig {
     pub config: Config,
     pub topics: Vec<GossipTopic>,
+    /// Peers we always forward messages to and accept messages from, outside of the mesh
+    pub direct_peers: Vec<StaticPeer>,
 }
 
 impl Default for GossipsubConfig {
@@ -52,6 +57,7 @@ impl Default for GossipsubConfig {
         Self {
             config,
             topics: vec![],
+            direct_peers: vec![],
         }
     }
 }
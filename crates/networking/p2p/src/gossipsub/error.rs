@@ -4,6 +4,10 @@ pub enum GossipsubError {
     InvalidData(String),
     #[error("Invalid topic {0}")]
     InvalidTopic(String),
+    #[error("Not enough peers to publish on {0}")]
+    InsufficientPeers(String),
+    #[error("Failed to publish {0}")]
+    PublishFailed(String),
 }
 
 impl From<ssz::DecodeError> for GossipsubError {
@@ -1,18 +1,19 @@
 use libp2p::gossipsub::TopicHash;
 use ream_consensus::{
-    attestation::Attestation, attester_slashing::AttesterSlashing, blob_sidecar::BlobSidecar,
-    bls_to_execution_change::BLSToExecutionChange, constants::genesis_validators_root,
+    attester_slashing::AttesterSlashing, blob_sidecar::BlobSidecar,
+    bls_to_execution_change::SignedBLSToExecutionChange, constants::genesis_validators_root,
     electra::beacon_block::SignedBeaconBlock, proposer_slashing::ProposerSlashing,
-    sync_committee::SyncCommittee,
+    single_attestation::SingleAttestation, voluntary_exit::SignedVoluntaryExit,
 };
 use ream_light_client::{
     finality_update::LightClientFinalityUpdate, optimistic_update::LightClientOptimisticUpdate,
 };
 use ream_network_spec::networks::network_spec;
 use ream_validator::{
-    aggregate_and_proof::AggregateAndProof, contribution_and_proof::SignedContributionAndProof,
+    aggregate_and_proof::SignedAggregateAndProof,
+    contribution_and_proof::SignedContributionAndProof, sync_committee::SyncCommitteeMessage,
 };
-use ssz::Decode;
+use ssz::{Decode, Encode};
 
 use super::{
     error::GossipsubError,
@@ -24,11 +25,12 @@ pub enum GossipsubMessage {
     BeaconBlock(Box<SignedBeaconBlock>),
     AttesterSlashing(Box<AttesterSlashing>),
     ProposerSlashing(Box<ProposerSlashing>),
-    AggregateAndProof(Box<AggregateAndProof>),
+    AggregateAndProof(Box<SignedAggregateAndProof>),
     BlobSidecar(Box<BlobSidecar>),
-    BeaconAttestation(Box<Attestation>),
-    SyncCommittee(Box<SyncCommittee>),
-    BlsToExecutionChange(Box<BLSToExecutionChange>),
+    BeaconAttestation(Box<SingleAttestation>),
+    VoluntaryExit(Box<SignedVoluntaryExit>),
+    SyncCommittee(Box<SyncCommitteeMessage>),
+    BlsToExecutionChange(Box<SignedBLSToExecutionChange>),
     SyncCommitteeContributionAndProof(Box<SignedContributionAndProof>),
     LightClientFinalityUpdate(Box<LightClientFinalityUpdate>),
     LightClientOptimisticUpdate(Box<LightClientOptimisticUpdate>),
@@ -38,7 +40,7 @@ impl GossipsubMessage {
     pub fn decode(topic: &TopicHash, data: &[u8]) -> Result<Self, GossipsubError> {
         let gossip_topic = GossipTopic::from_topic_hash(topic)?;
 
-        if gossip_topic.fork != network_spec().fork_digest(genesis_validators_root()) {
+        if !network_spec().is_known_fork_digest(gossip_topic.fork, genesis_validators_root()) {
             return Err(GossipsubError::InvalidTopic(format!(
                 "Invalid topic fork: {topic:?}"
             )));
@@ -49,7 +51,7 @@ impl GossipsubMessage {
                 SignedBeaconBlock::from_ssz_bytes(data)?,
             ))),
             GossipTopicKind::SyncCommittee(_) => Ok(Self::SyncCommittee(Box::new(
-                SyncCommittee::from_ssz_bytes(data)?,
+                SyncCommitteeMessage::from_ssz_bytes(data)?,
             ))),
             GossipTopicKind::SyncCommitteeContributionAndProof => {
                 Ok(Self::SyncCommitteeContributionAndProof(Box::new(
@@ -57,13 +59,16 @@ impl GossipsubMessage {
                 )))
             }
             GossipTopicKind::AggregateAndProof => Ok(Self::AggregateAndProof(Box::new(
-                AggregateAndProof::from_ssz_bytes(data)?,
+                SignedAggregateAndProof::from_ssz_bytes(data)?,
             ))),
             GossipTopicKind::BeaconAttestation(_) => Ok(Self::BeaconAttestation(Box::new(
-                Attestation::from_ssz_bytes(data)?,
+                SingleAttestation::from_ssz_bytes(data)?,
             ))),
             GossipTopicKind::BlsToExecutionChange => Ok(Self::BlsToExecutionChange(Box::new(
-                BLSToExecutionChange::from_ssz_bytes(data)?,
+                SignedBLSToExecutionChange::from_ssz_bytes(data)?,
+            ))),
+            GossipTopicKind::VoluntaryExit => Ok(Self::VoluntaryExit(Box::new(
+                SignedVoluntaryExit::from_ssz_bytes(data)?,
             ))),
             GossipTopicKind::AttesterSlashing => Ok(Self::AttesterSlashing(Box::new(
                 AttesterSlashing::from_ssz_bytes(data)?,
@@ -80,9 +85,30 @@ impl GossipsubMessage {
             GossipTopicKind::LightClientOptimisticUpdate => Ok(Self::LightClientOptimisticUpdate(
                 Box::new(LightClientOptimisticUpdate::from_ssz_bytes(data)?),
             )),
-            _ => Err(GossipsubError::InvalidTopic(format!(
-                "Topic not supported: {topic:?}"
-            ))),
+        }
+    }
+
+    /// SSZ encodes the message. Snappy compression is applied by the gossipsub transform.
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::BeaconBlock(signed_block) => signed_block.as_ssz_bytes(),
+            Self::AttesterSlashing(attester_slashing) => attester_slashing.as_ssz_bytes(),
+            Self::ProposerSlashing(proposer_slashing) => proposer_slashing.as_ssz_bytes(),
+            Self::AggregateAndProof(aggregate_and_proof) => aggregate_and_proof.as_ssz_bytes(),
+            Self::BlobSidecar(blob_sidecar) => blob_sidecar.as_ssz_bytes(),
+            Self::BeaconAttestation(single_attestation) => single_attestation.as_ssz_bytes(),
+            Self::VoluntaryExit(voluntary_exit) => voluntary_exit.as_ssz_bytes(),
+            Self::SyncCommittee(sync_committee_message) => sync_committee_message.as_ssz_bytes(),
+            Self::BlsToExecutionChange(bls_to_execution_change) => {
+                bls_to_execution_change.as_ssz_bytes()
+            }
+            Self::SyncCommitteeContributionAndProof(contribution_and_proof) => {
+                contribution_and_proof.as_ssz_bytes()
+            }
+            Self::LightClientFinalityUpdate(finality_update) => finality_update.as_ssz_bytes(),
+            Self::LightClientOptimisticUpdate(optimistic_update) => {
+                optimistic_update.as_ssz_bytes()
+            }
         }
     }
 }
@@ -3,6 +3,7 @@
 pub mod configurations;
 pub mod error;
 pub mod message;
+pub mod scoring;
 pub mod snappy;
 pub mod topics;
 
@@ -0,0 +1,411 @@
+//! Gossipsub peer scoring parameters, following the recommendations in
+//! https://gist.github.com/blacktemplar/5c1862cb3f0e32a1a7fb0b25e79e6e2c
+
+use std::time::Duration;
+
+use libp2p::gossipsub::{PeerScoreParams, PeerScoreThresholds, TopicScoreParams};
+use ream_consensus::constants::{
+    MAX_COMMITTEES_PER_SLOT, SECONDS_PER_SLOT, SLOTS_PER_EPOCH, SYNC_COMMITTEE_SIZE,
+    TARGET_COMMITTEE_SIZE,
+};
+use ream_network_spec::networks::network_spec;
+use ream_validator::constants::{
+    ATTESTATION_SUBNET_COUNT, SYNC_COMMITTEE_SUBNET_COUNT, TARGET_AGGREGATORS_PER_COMMITTEE,
+    TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE,
+};
+
+use super::topics::GossipTopicKind;
+
+/// The most a peer can earn on a single topic from time spent in the mesh.
+const MAX_IN_MESH_SCORE: f64 = 10.0;
+
+/// The most a peer can earn on a single topic from being the first to deliver messages.
+const MAX_FIRST_MESSAGE_DELIVERIES_SCORE: f64 = 40.0;
+
+const BEACON_BLOCK_WEIGHT: f64 = 0.5;
+const BEACON_AGGREGATE_AND_PROOF_WEIGHT: f64 = 0.5;
+const VOLUNTARY_EXIT_WEIGHT: f64 = 0.05;
+const PROPOSER_SLASHING_WEIGHT: f64 = 0.05;
+const ATTESTER_SLASHING_WEIGHT: f64 = 0.05;
+const BLS_TO_EXECUTION_CHANGE_WEIGHT: f64 = 0.05;
+const SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF_WEIGHT: f64 = 0.2;
+const LIGHT_CLIENT_UPDATE_WEIGHT: f64 = 0.05;
+
+/// Weights shared evenly between all subnets of a kind.
+const BEACON_ATTESTATION_SUBNETS_WEIGHT: f64 = 1.0;
+const SYNC_COMMITTEE_SUBNETS_WEIGHT: f64 = 0.4;
+const BLOB_SIDECAR_SUBNETS_WEIGHT: f64 = 0.5;
+
+/// Sum of the weights of all topics, which bounds the positive score a peer can reach.
+const TOTAL_TOPIC_WEIGHT: f64 = BEACON_BLOCK_WEIGHT
+    + BEACON_AGGREGATE_AND_PROOF_WEIGHT
+    + BEACON_ATTESTATION_SUBNETS_WEIGHT
+    + VOLUNTARY_EXIT_WEIGHT
+    + PROPOSER_SLASHING_WEIGHT
+    + ATTESTER_SLASHING_WEIGHT
+    + BLS_TO_EXECUTION_CHANGE_WEIGHT
+    + SYNC_COMMITTEE_SUBNETS_WEIGHT
+    + SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF_WEIGHT
+    + BLOB_SIDECAR_SUBNETS_WEIGHT
+    + 2.0 * LIGHT_CLIENT_UPDATE_WEIGHT;
+
+pub fn peer_score_thresholds() -> PeerScoreThresholds {
+    PeerScoreThresholds {
+        gossip_threshold: -4000.0,
+        publish_threshold: -8000.0,
+        graylist_threshold: -16000.0,
+        accept_px_threshold: 100.0,
+        opportunistic_graft_threshold: 5.0,
+    }
+}
+
+/// Penalties for peers in our mesh that deliver fewer messages than expected.
+struct MeshDeliveries {
+    /// Time it takes for the delivery counter to decay to zero, in slots.
+    decay_slots: u64,
+    /// Cap of the delivery counter, as a multiple of the threshold.
+    cap_factor: f64,
+    /// Grace period after a peer joins the mesh before its deliveries are scored.
+    activation_window: Duration,
+}
+
+/// Derives the gossipsub score parameters from the slot timing and the size of the validator
+/// set.
+#[derive(Debug, Clone)]
+pub struct PeerScoreSettings {
+    slot: Duration,
+    epoch: Duration,
+    decay_interval: Duration,
+    decay_to_zero: f64,
+    mesh_n: usize,
+    max_positive_score: f64,
+}
+
+impl PeerScoreSettings {
+    pub fn new(mesh_n: usize) -> Self {
+        let slot = Duration::from_secs(SECONDS_PER_SLOT);
+        Self {
+            slot,
+            epoch: slot * SLOTS_PER_EPOCH as u32,
+            decay_interval: slot.max(Duration::from_secs(1)),
+            decay_to_zero: 0.01,
+            mesh_n,
+            max_positive_score: (MAX_IN_MESH_SCORE + MAX_FIRST_MESSAGE_DELIVERIES_SCORE)
+                * TOTAL_TOPIC_WEIGHT,
+        }
+    }
+
+    /// The global score parameters. Topic parameters are added per topic through
+    /// [`PeerScoreSettings::topic_score_params`].
+    pub fn peer_score_params(&self) -> PeerScoreParams {
+        let mut params = PeerScoreParams {
+            decay_interval: self.decay_interval,
+            decay_to_zero: self.decay_to_zero,
+            retain_score: self.epoch * 100,
+            // Allow up to 8 nodes per IP
+            ip_colocation_factor_threshold: 8.0,
+            behaviour_penalty_threshold: 6.0,
+            behaviour_penalty_decay: self.score_parameter_decay(self.epoch * 10),
+            ..Default::default()
+        };
+
+        // A peer misbehaving about 10 times per epoch drops below the gossip threshold
+        let target_value = decay_convergence(
+            params.behaviour_penalty_decay,
+            10.0 / SLOTS_PER_EPOCH as f64,
+        ) - params.behaviour_penalty_threshold;
+        params.behaviour_penalty_weight =
+            peer_score_thresholds().gossip_threshold / target_value.powi(2);
+
+        params.topic_score_cap = self.max_positive_score * 0.5;
+        params.ip_colocation_factor_weight = -params.topic_score_cap;
+        params
+    }
+
+    /// The score parameters of a topic. The expected message rates of the attestation topics
+    /// depend on the number of active validators, so these should be refreshed every epoch.
+    pub fn topic_score_params(
+        &self,
+        kind: GossipTopicKind,
+        active_validators: u64,
+        current_slot: u64,
+    ) -> TopicScoreParams {
+        let slots_per_epoch = SLOTS_PER_EPOCH as f64;
+        match kind {
+            GossipTopicKind::BeaconBlock => self.topic_params(
+                BEACON_BLOCK_WEIGHT,
+                1.0,
+                self.epoch * 20,
+                Some(MeshDeliveries {
+                    decay_slots: SLOTS_PER_EPOCH * 5,
+                    cap_factor: 3.0,
+                    activation_window: self.epoch,
+                }),
+                current_slot,
+            ),
+            GossipTopicKind::AggregateAndProof => self.topic_params(
+                BEACON_AGGREGATE_AND_PROOF_WEIGHT,
+                expected_aggregators_per_slot(active_validators),
+                self.epoch,
+                Some(MeshDeliveries {
+                    decay_slots: SLOTS_PER_EPOCH * 2,
+                    cap_factor: 4.0,
+                    activation_window: self.epoch,
+                }),
+                current_slot,
+            ),
+            GossipTopicKind::BeaconAttestation(_) => {
+                // With enough committees every subnet sees several bursts of attestations per
+                // epoch, otherwise the counters have to survive the quiet slots in between
+                let multiple_bursts_per_subnet_per_epoch = committees_per_slot(active_validators)
+                    >= 2 * ATTESTATION_SUBNET_COUNT / SLOTS_PER_EPOCH;
+                let (first_message_decay_time, mesh_deliveries) =
+                    if multiple_bursts_per_subnet_per_epoch {
+                        (
+                            self.epoch,
+                            MeshDeliveries {
+                                decay_slots: SLOTS_PER_EPOCH * 4,
+                                cap_factor: 16.0,
+                                activation_window: self.slot * (SLOTS_PER_EPOCH as u32 / 2 + 1),
+                            },
+                        )
+                    } else {
+                        (
+                            self.epoch * 4,
+                            MeshDeliveries {
+                                decay_slots: SLOTS_PER_EPOCH * 16,
+                                cap_factor: 16.0,
+                                activation_window: self.epoch * 3,
+                            },
+                        )
+                    };
+                self.topic_params(
+                    BEACON_ATTESTATION_SUBNETS_WEIGHT / ATTESTATION_SUBNET_COUNT as f64,
+                    active_validators as f64 / ATTESTATION_SUBNET_COUNT as f64 / slots_per_epoch,
+                    first_message_decay_time,
+                    Some(mesh_deliveries),
+                    current_slot,
+                )
+            }
+            GossipTopicKind::VoluntaryExit => self.topic_params(
+                VOLUNTARY_EXIT_WEIGHT,
+                4.0 / slots_per_epoch,
+                self.epoch * 100,
+                None,
+                current_slot,
+            ),
+            GossipTopicKind::ProposerSlashing => self.topic_params(
+                PROPOSER_SLASHING_WEIGHT,
+                1.0 / 5.0 / slots_per_epoch,
+                self.epoch * 100,
+                None,
+                current_slot,
+            ),
+            GossipTopicKind::AttesterSlashing => self.topic_params(
+                ATTESTER_SLASHING_WEIGHT,
+                1.0 / 5.0 / slots_per_epoch,
+                self.epoch * 100,
+                None,
+                current_slot,
+            ),
+            GossipTopicKind::BlsToExecutionChange => self.topic_params(
+                BLS_TO_EXECUTION_CHANGE_WEIGHT,
+                0.1,
+                self.epoch * 100,
+                None,
+                current_slot,
+            ),
+            GossipTopicKind::SyncCommittee(_) => self.topic_params(
+                SYNC_COMMITTEE_SUBNETS_WEIGHT / SYNC_COMMITTEE_SUBNET_COUNT as f64,
+                (SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT) as f64,
+                self.epoch,
+                None,
+                current_slot,
+            ),
+            GossipTopicKind::SyncCommitteeContributionAndProof => self.topic_params(
+                SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF_WEIGHT,
+                (SYNC_COMMITTEE_SUBNET_COUNT * TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE) as f64,
+                self.epoch,
+                None,
+                current_slot,
+            ),
+            GossipTopicKind::BlobSidecar(_) => self.topic_params(
+                BLOB_SIDECAR_SUBNETS_WEIGHT
+                    / network_spec().blob_sidecar_subnet_count_electra.max(1) as f64,
+                1.0,
+                self.epoch * 20,
+                None,
+                current_slot,
+            ),
+            GossipTopicKind::LightClientFinalityUpdate => self.topic_params(
+                LIGHT_CLIENT_UPDATE_WEIGHT,
+                1.0 / slots_per_epoch,
+                self.epoch * 20,
+                None,
+                current_slot,
+            ),
+            GossipTopicKind::LightClientOptimisticUpdate => self.topic_params(
+                LIGHT_CLIENT_UPDATE_WEIGHT,
+                1.0,
+                self.epoch * 20,
+                None,
+                current_slot,
+            ),
+        }
+    }
+
+    /// Builds the parameters of a topic from the rate of messages we expect on it per slot.
+    fn topic_params(
+        &self,
+        topic_weight: f64,
+        expected_message_rate: f64,
+        first_message_decay_time: Duration,
+        mesh_deliveries: Option<MeshDeliveries>,
+        current_slot: u64,
+    ) -> TopicScoreParams {
+        let mut params = TopicScoreParams {
+            topic_weight,
+            time_in_mesh_quantum: self.slot,
+            ..Default::default()
+        };
+
+        params.time_in_mesh_cap = 3600.0 / params.time_in_mesh_quantum.as_secs_f64();
+        params.time_in_mesh_weight = MAX_IN_MESH_SCORE / params.time_in_mesh_cap;
+
+        params.first_message_deliveries_decay =
+            self.score_parameter_decay(first_message_decay_time);
+        params.first_message_deliveries_cap =
+            MAX_FIRST_MESSAGE_DELIVERIES_SCORE.min(decay_convergence(
+                params.first_message_deliveries_decay,
+                2.0 * expected_message_rate / self.mesh_n as f64,
+            ));
+        params.first_message_deliveries_weight =
+            MAX_FIRST_MESSAGE_DELIVERIES_SCORE / params.first_message_deliveries_cap;
+
+        match mesh_deliveries {
+            Some(mesh_deliveries) => {
+                let decay_time = self.slot * mesh_deliveries.decay_slots as u32;
+                params.mesh_message_deliveries_decay = self.score_parameter_decay(decay_time);
+                params.mesh_message_deliveries_threshold = decay_threshold(
+                    params.mesh_message_deliveries_decay,
+                    expected_message_rate / 50.0,
+                );
+                params.mesh_message_deliveries_cap = (mesh_deliveries.cap_factor
+                    * params.mesh_message_deliveries_threshold)
+                    .max(2.0);
+                params.mesh_message_deliveries_activation = mesh_deliveries.activation_window;
+                params.mesh_message_deliveries_window = Duration::from_secs(2);
+                params.mesh_failure_penalty_decay = params.mesh_message_deliveries_decay;
+                params.mesh_message_deliveries_weight = -topic_weight;
+                params.mesh_failure_penalty_weight = params.mesh_message_deliveries_weight;
+
+                // Too early in the chain for the counters to have converged
+                if mesh_deliveries.decay_slots >= current_slot {
+                    params.mesh_message_deliveries_threshold = 0.0;
+                    params.mesh_message_deliveries_weight = 0.0;
+                }
+            }
+            None => {
+                params.mesh_message_deliveries_weight = 0.0;
+                params.mesh_message_deliveries_threshold = 0.0;
+                params.mesh_message_deliveries_decay = 0.0;
+                params.mesh_message_deliveries_cap = 0.0;
+                params.mesh_message_deliveries_window = Duration::ZERO;
+                params.mesh_message_deliveries_activation = Duration::ZERO;
+                params.mesh_failure_penalty_decay = 0.0;
+                params.mesh_failure_penalty_weight = 0.0;
+            }
+        }
+
+        // A single invalid message wipes out everything a peer can earn across all topics
+        params.invalid_message_deliveries_weight = -self.max_positive_score / topic_weight;
+        params.invalid_message_deliveries_decay = self.score_parameter_decay(self.epoch * 50);
+
+        params
+    }
+
+    /// The per-interval decay factor that brings a counter to `decay_to_zero` after
+    /// `decay_time`.
+    fn score_parameter_decay(&self, decay_time: Duration) -> f64 {
+        let ticks = decay_time.as_secs_f64() / self.decay_interval.as_secs_f64();
+        self.decay_to_zero.powf(1.0 / ticks)
+    }
+}
+
+/// The value a counter increasing by `rate` per interval converges to.
+fn decay_convergence(decay: f64, rate: f64) -> f64 {
+    rate / (1.0 - decay)
+}
+
+fn decay_threshold(decay: f64, rate: f64) -> f64 {
+    decay_convergence(decay, rate) * decay
+}
+
+/// https://ethereum.github.io/consensus-specs/specs/phase0/beacon-chain/#get_committee_count_per_slot
+fn committees_per_slot(active_validators: u64) -> u64 {
+    (active_validators / SLOTS_PER_EPOCH / TARGET_COMMITTEE_SIZE).clamp(1, MAX_COMMITTEES_PER_SLOT)
+}
+
+/// The number of aggregates we expect per slot, given that every committee selects roughly
+/// `TARGET_AGGREGATORS_PER_COMMITTEE` aggregators.
+fn expected_aggregators_per_slot(active_validators: u64) -> f64 {
+    let committees = committees_per_slot(active_validators) * SLOTS_PER_EPOCH;
+    let smaller_committee_size = active_validators / committees;
+    let larger_committees = active_validators - smaller_committee_size * committees;
+
+    let modulo_smaller = (smaller_committee_size / TARGET_AGGREGATORS_PER_COMMITTEE).max(1);
+    let modulo_larger = ((smaller_committee_size + 1) / TARGET_AGGREGATORS_PER_COMMITTEE).max(1);
+
+    (((committees - larger_committees) * smaller_committee_size) as f64 / modulo_smaller as f64
+        + (larger_committees * (smaller_committee_size + 1)) as f64 / modulo_larger as f64)
+        / SLOTS_PER_EPOCH as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_network_spec::networks::initialize_test_network_spec;
+
+    use super::*;
+
+    #[test]
+    fn test_topic_score_params_are_valid() {
+        initialize_test_network_spec();
+        let settings = PeerScoreSettings::new(8);
+
+        let kinds = [
+            GossipTopicKind::BeaconBlock,
+            GossipTopicKind::AggregateAndProof,
+            GossipTopicKind::VoluntaryExit,
+            GossipTopicKind::ProposerSlashing,
+            GossipTopicKind::AttesterSlashing,
+            GossipTopicKind::BeaconAttestation(0),
+            GossipTopicKind::SyncCommittee(0),
+            GossipTopicKind::SyncCommitteeContributionAndProof,
+            GossipTopicKind::BlsToExecutionChange,
+            GossipTopicKind::LightClientFinalityUpdate,
+            GossipTopicKind::LightClientOptimisticUpdate,
+            GossipTopicKind::BlobSidecar(0),
+        ];
+        for active_validators in [16_384, 1_000_000] {
+            for kind in kinds {
+                let params = settings.topic_score_params(kind, active_validators, 100_000);
+                assert!(params.topic_weight > 0.0, "{kind}");
+                assert!(params.first_message_deliveries_cap > 0.0, "{kind}");
+                assert!(params.invalid_message_deliveries_weight < 0.0, "{kind}");
+                assert!(params.mesh_message_deliveries_weight <= 0.0, "{kind}");
+            }
+        }
+
+        assert!(settings.peer_score_params().validate().is_ok());
+    }
+
+    #[test]
+    fn test_mesh_deliveries_disabled_early_in_chain() {
+        let settings = PeerScoreSettings::new(8);
+        let params = settings.topic_score_params(GossipTopicKind::BeaconBlock, 16_384, 1);
+        assert_eq!(params.mesh_message_deliveries_weight, 0.0);
+
+        let params = settings.topic_score_params(GossipTopicKind::BeaconBlock, 16_384, 100_000);
+        assert!(params.mesh_message_deliveries_weight < 0.0);
+    }
+}
@@ -4,5 +4,6 @@ pub mod config;
 pub mod constants;
 pub mod gossipsub;
 pub mod network;
+pub mod peer_manager;
 pub mod req_resp;
 pub mod utils;
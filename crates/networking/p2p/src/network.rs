@@ -8,47 +8,77 @@ use std::{
     time::{Duration, Instant},
 };
 
+use alloy_primitives::aliases::B32;
 use anyhow::anyhow;
 use discv5::Enr;
 use libp2p::{
     Multiaddr, PeerId, Swarm, SwarmBuilder, Transport,
     connection_limits::{self, ConnectionLimits},
     core::{
+        ConnectedPoint,
         muxing::StreamMuxerBox,
         transport::Boxed,
         upgrade::{SelectUpgrade, Version},
     },
     dns::Transport as DnsTransport,
-    futures::StreamExt,
-    gossipsub::{Event as GossipsubEvent, IdentTopic as Topic, MessageAuthenticity},
+    futures::{StreamExt, future::Either},
+    gossipsub::{
+        Event as GossipsubEvent, IdentTopic as Topic, MessageAcceptance, MessageAuthenticity,
+        MessageId, PublishError,
+    },
     identify,
-    multiaddr::Protocol,
     noise::Config as NoiseConfig,
-    swarm::{self, ConnectionId, NetworkBehaviour, SwarmEvent},
+    quic::{Config as QuicConfig, tokio::Transport as QuicTransport},
+    swarm::{self, ConnectionId, NetworkBehaviour, SwarmEvent, dial_opts::DialOpts},
     tcp::{Config as TcpConfig, tokio::Transport as TcpTransport},
     yamux,
 };
-use libp2p_identity::{Keypair, PublicKey, secp256k1};
+use libp2p_identity::{Keypair, PublicKey};
 use libp2p_mplex::{MaxBufferBehaviour, MplexConfig};
 use parking_lot::Mutex;
-use ream_discv5::discovery::{DiscoveredPeers, Discovery, QueryType};
+use ream_discv5::{
+    discovery::{DiscoveredPeers, Discovery, QueryType},
+    enr_ext::EnrExt,
+    eth2::EnrForkId,
+    subnet::{AttestationSubnets, SyncCommitteeSubnets},
+};
 use ream_executor::ReamExecutor;
+use ream_network_spec::networks::network_spec;
+use ream_validator::blob_sidecars::compute_subnet_for_blob_sidecar;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
-use tracing::{error, info, trace, warn};
-use tree_hash::TreeHash;
+use tracing::{debug, error, info, trace, warn};
 use yamux::Config as YamuxConfig;
 
 use crate::{
-    channel::{P2PMessages, P2PResponse},
+    channel::{P2PMessages, P2PResponse, PublishCallback},
     config::NetworkConfig,
+    constants::{
+        GOODBYE_TIMEOUT, MIN_SUBNET_PEERS, PENDING_DISCONNECTS_INTERVAL,
+        PERSIST_KNOWN_ENRS_INTERVAL,
+    },
     gossipsub::{
-        GossipsubBehaviour, message::GossipsubMessage, snappy::SnappyTransform, topics::GossipTopic,
+        GossipsubBehaviour,
+        error::GossipsubError,
+        message::GossipsubMessage,
+        scoring::{PeerScoreSettings, peer_score_thresholds},
+        snappy::SnappyTransform,
+        topics::{GossipTopic, GossipTopicKind},
+    },
+    peer_manager::{
+        HEARTBEAT_INTERVAL, PeerManager, PeerManagerEvent,
+        peer_info::{ConnectionDirection, PeerAction},
     },
     req_resp::{
         ReqResp, ReqRespMessage,
-        handler::ReqRespMessageReceived,
-        messages::{RequestMessage, beacon_blocks::BeaconBlocksByRangeV2Request},
+        error::ReqRespError,
+        handler::{ReqRespMessageReceived, RespMessage},
+        messages::{
+            RequestMessage, ResponseMessage, beacon_blocks::BeaconBlocksByRangeV2Request,
+            meta_data::GetMetaDataV2, ping::Ping,
+        },
+        rate_limiter::{RateLimitedError, RateLimiter},
     },
+    utils::load_private_key,
 };
 
 #[derive(NetworkBehaviour)]
@@ -84,14 +114,39 @@ pub enum ReamNetworkEvent {
         connection_id: ConnectionId,
         message: RequestMessage,
     },
+    GossipsubMessage {
+        propagation_source: PeerId,
+        message_id: MessageId,
+        topic: GossipTopic,
+        message: GossipsubMessage,
+    },
 }
 
 pub struct Network {
     peer_id: PeerId,
     swarm: Swarm<ReamBehaviour>,
     subscribed_topics: Arc<Mutex<HashSet<GossipTopic>>>,
+    /// Fork digests we are subscribed to topics for. Around a fork boundary these are the digests
+    /// of both the old and the new fork.
+    fork_digests: HashSet<B32>,
+    /// Digest of the current fork, which we publish messages under.
+    fork_digest: B32,
+    /// Fewest mesh peers a topic should have, publishing with fewer is reported to the caller.
+    mesh_n_low: usize,
     callbacks: HashMap<u64, mpsc::Sender<anyhow::Result<P2PResponse>>>,
     request_id: u64,
+    meta_data: GetMetaDataV2,
+    peer_manager: PeerManager,
+    /// Peers we sent a `Goodbye` to, with the time to disconnect them at.
+    pending_disconnects: Vec<(PeerId, Instant)>,
+    /// Whether we can dial the QUIC addresses of peers.
+    quic_enabled: bool,
+    /// Limits the requests each peer can make of us per protocol.
+    inbound_rate_limiter: RateLimiter,
+    peer_score_settings: PeerScoreSettings,
+    /// Inputs of the topic score parameters, refreshed by the manager every epoch.
+    active_validators: u64,
+    current_slot: u64,
 }
 
 struct Executor(ReamExecutor);
@@ -104,7 +159,7 @@ impl libp2p::swarm::Executor for Executor {
 
 impl Network {
     pub async fn init(executor: ReamExecutor, config: &NetworkConfig) -> anyhow::Result<Self> {
-        let local_key = secp256k1::Keypair::generate();
+        let local_key = load_private_key(&config.data_dir, config.private_key_file.as_deref())?;
 
         let discovery = {
             let mut discovery =
@@ -113,18 +168,42 @@ impl Network {
             discovery
         };
 
+        let meta_data = GetMetaDataV2 {
+            seq_number: discovery.local_enr().seq(),
+            attnets: config.discv5_config.attestation_subnets.0.clone(),
+            syncnets: config.discv5_config.sync_committee_subnets.0.clone(),
+        };
+
         let req_resp = ReqResp::new();
 
+        // Direct peers only exchange messages while connected, so they are kept connected like
+        // static peers
+        let mut peer_manager_config = config.peer_manager_config.clone();
+        peer_manager_config
+            .static_peers
+            .extend(config.gossipsub_config.direct_peers.iter().cloned());
+
+        let peer_score_settings = PeerScoreSettings::new(config.gossipsub_config.config.mesh_n());
         let gossipsub = {
             let snappy_transform =
                 SnappyTransform::new(config.gossipsub_config.config.max_transmit_size());
-            GossipsubBehaviour::new_with_transform(
+            let mut gossipsub = GossipsubBehaviour::new_with_transform(
                 MessageAuthenticity::Anonymous,
                 config.gossipsub_config.config.clone(),
                 None,
                 snappy_transform,
             )
-            .map_err(|err| anyhow!("Failed to create gossipsub behaviour: {err:?}"))?
+            .map_err(|err| anyhow!("Failed to create gossipsub behaviour: {err:?}"))?;
+            gossipsub
+                .with_peer_score(
+                    peer_score_settings.peer_score_params(),
+                    peer_score_thresholds(),
+                )
+                .map_err(|err| anyhow!("Failed to enable gossipsub peer scoring: {err}"))?;
+            for direct_peer in &config.gossipsub_config.direct_peers {
+                gossipsub.add_explicit_peer(&direct_peer.peer_id);
+            }
+            gossipsub
         };
 
         let connection_limits = {
@@ -158,8 +237,11 @@ impl Network {
             }
         };
 
-        let transport = build_transport(Keypair::from(local_key.clone()))
-            .map_err(|err| anyhow!("Failed to build transport: {err:?}"))?;
+        let transport = build_transport(
+            Keypair::from(local_key.clone()),
+            config.listen_address.quic_enabled(),
+        )
+        .map_err(|err| anyhow!("Failed to build transport: {err:?}"))?;
 
         let swarm = {
             let config = swarm::Config::with_executor(Executor(executor))
@@ -183,8 +265,25 @@ impl Network {
             peer_id: PeerId::from_public_key(&PublicKey::from(local_key.public().clone())),
             swarm,
             subscribed_topics: Arc::new(Mutex::new(HashSet::new())),
+            fork_digests: config
+                .gossipsub_config
+                .topics
+                .iter()
+                .map(|topic| topic.fork)
+                .collect(),
+            fork_digest: config.discv5_config.enr_fork_id.fork_digest,
+            mesh_n_low: config.gossipsub_config.config.mesh_n_low(),
             callbacks: HashMap::new(),
             request_id: 0,
+            meta_data,
+            peer_manager: PeerManager::new(peer_manager_config),
+            pending_disconnects: vec![],
+            quic_enabled: config.listen_address.quic_enabled(),
+            inbound_rate_limiter: RateLimiter::new(config.rate_limiter_config.clone()),
+            peer_score_settings,
+            // Until the manager tells us about the chain, assume the smallest validator set
+            active_validators: network_spec().min_genesis_active_validator_count,
+            current_slot: 0,
         };
 
         network.start_network_worker(config).await?;
@@ -195,30 +294,49 @@ impl Network {
     async fn start_network_worker(&mut self, config: &NetworkConfig) -> anyhow::Result<()> {
         info!("Libp2p starting .... ");
 
-        let mut multi_addr: Multiaddr = config.socket_address.into();
-        multi_addr.push(Protocol::Tcp(config.socket_port));
-
-        match self.swarm.listen_on(multi_addr.clone()) {
-            Ok(listener_id) => {
-                info!(
-                    "Listening on {:?} with peer_id {:?} {listener_id:?}",
-                    multi_addr, self.peer_id
-                );
+        for multi_addr in config.listen_address.tcp_multiaddrs() {
+            match self.swarm.listen_on(multi_addr.clone()) {
+                Ok(listener_id) => {
+                    info!(
+                        "Listening on {:?} with peer_id {:?} {listener_id:?}",
+                        multi_addr, self.peer_id
+                    );
+                }
+                Err(err) => {
+                    error!("Failed to start libp2p peer listen on {multi_addr:?}, error: {err:?}",);
+                }
             }
-            Err(err) => {
-                error!("Failed to start libp2p peer listen on {multi_addr:?}, error: {err:?}",);
+        }
+
+        for multi_addr in config.listen_address.quic_multiaddrs() {
+            match self.swarm.listen_on(multi_addr.clone()) {
+                Ok(listener_id) => {
+                    info!("Listening on {multi_addr:?} with QUIC {listener_id:?}");
+                }
+                Err(err) => {
+                    error!("Failed to start QUIC listener on {multi_addr:?}, error: {err:?}");
+                }
             }
         }
 
         for bootnode in &config.discv5_config.bootnodes {
-            if let (Some(ipv4), Some(tcp_port)) = (bootnode.ip4(), bootnode.tcp4()) {
-                let mut multi_addr = Multiaddr::empty();
-                multi_addr.push(ipv4.into());
-                multi_addr.push(Protocol::Tcp(tcp_port));
+            if let Some(multi_addr) = self.dial_addresses(bootnode).into_iter().next() {
                 self.swarm.dial(multi_addr).unwrap();
             }
         }
 
+        self.peer_manager.dial_static_peers();
+        self.handle_peer_manager_events();
+
+        // Peers we knew in a previous run are dialed right away instead of waiting for discovery
+        let known_enrs = self.swarm.behaviour_mut().discovery.take_known_enrs();
+        if !known_enrs.is_empty() {
+            self.handle_discovered_peers(
+                QueryType::Peers,
+                known_enrs.into_iter().map(|enr| (enr, None)).collect(),
+            );
+        }
+
         for topic in &config.gossipsub_config.topics {
             if self.subscribe_to_topic(*topic) {
                 info!("Subscribed to topic: {topic}");
@@ -250,6 +368,9 @@ impl Network {
         manager_sender: UnboundedSender<ReamNetworkEvent>,
         mut p2p_receiver: UnboundedReceiver<P2PMessages>,
     ) {
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        let mut persist_known_enrs = tokio::time::interval(PERSIST_KNOWN_ENRS_INTERVAL);
+        let mut pending_disconnects = tokio::time::interval(PENDING_DISCONNECTS_INTERVAL);
         loop {
             tokio::select! {
                 Some(event) = self.swarm.next() => {
@@ -258,6 +379,16 @@ impl Network {
                             warn!("Failed to send event: {err:?}");
                         }
                     }
+                    self.handle_peer_manager_events();
+                }
+                _ = heartbeat.tick() => {
+                    self.peer_manager_heartbeat();
+                }
+                _ = persist_known_enrs.tick() => {
+                    self.persist_known_enrs();
+                }
+                _ = pending_disconnects.tick() => {
+                    self.disconnect_pending_peers();
                 }
                 Some(event) = p2p_receiver.recv() => {
                     match event {
@@ -265,7 +396,79 @@ impl Network {
                             let request_id = self.request_id();
                             self.callbacks.insert(request_id, callback);
                             self.swarm.behaviour_mut().req_resp.send_request(peer_id, request_id, RequestMessage::BeaconBlocksByRange(BeaconBlocksByRangeV2Request::new(start, count)))
-                    },
+                        },
+                        P2PMessages::RequestStatus { peer_id, status, callback } => {
+                            let request_id = self.request_id();
+                            self.callbacks.insert(request_id, callback);
+                            self.swarm.behaviour_mut().req_resp.send_request(peer_id, request_id, RequestMessage::Status(status))
+                        }
+                        P2PMessages::Response { peer_id, connection_id, stream_id, message } => {
+                            self.swarm.behaviour_mut().req_resp.send_response(peer_id, connection_id, stream_id, message);
+                        }
+                        P2PMessages::ValidationResult { message_id, propagation_source, acceptance } => {
+                            self.report_message_validation_result(&message_id, &propagation_source, acceptance);
+                            self.handle_peer_manager_events();
+                        }
+                        P2PMessages::DisconnectPeer { peer_id, reason } => {
+                            self.peer_manager.disconnect(peer_id, reason);
+                            self.handle_peer_manager_events();
+                        }
+                        P2PMessages::ReportPeer { peer_id, action, source } => {
+                            self.peer_manager.report_peer(&peer_id, action, source);
+                            self.handle_peer_manager_events();
+                        }
+                        P2PMessages::UpdateGossipsubScoreParams { active_validators, current_slot } => {
+                            self.update_gossipsub_score_params(active_validators, current_slot);
+                        }
+                        P2PMessages::SubscribeToTopic(kind) => {
+                            self.subscribe_to_topic_kind(kind);
+                        }
+                        P2PMessages::UnsubscribeFromTopic(kind) => {
+                            self.unsubscribe_from_topic_kind(kind);
+                        }
+                        P2PMessages::SubscribeToFork(fork_digest) => {
+                            self.subscribe_to_fork(fork_digest);
+                        }
+                        P2PMessages::UnsubscribeFromFork(fork_digest) => {
+                            self.unsubscribe_from_fork(fork_digest);
+                        }
+                        P2PMessages::UpdateSubnets { attestation_subnets, sync_committee_subnets } => {
+                            self.update_subnets(attestation_subnets, sync_committee_subnets);
+                        }
+                        P2PMessages::UpdateEnrForkId(enr_fork_id) => {
+                            self.update_enr_fork_id(enr_fork_id);
+                        }
+                        P2PMessages::PublishBeaconBlock { signed_block, callback } => {
+                            self.publish(GossipTopicKind::BeaconBlock, GossipsubMessage::BeaconBlock(signed_block), callback);
+                        }
+                        P2PMessages::PublishBlobSidecar { blob_sidecar, callback } => {
+                            let subnet_id = compute_subnet_for_blob_sidecar(blob_sidecar.index);
+                            self.publish(GossipTopicKind::BlobSidecar(subnet_id), GossipsubMessage::BlobSidecar(blob_sidecar), callback);
+                        }
+                        P2PMessages::PublishAttestation { subnet_id, single_attestation, callback } => {
+                            self.publish(GossipTopicKind::BeaconAttestation(subnet_id), GossipsubMessage::BeaconAttestation(single_attestation), callback);
+                        }
+                        P2PMessages::PublishAggregateAndProof { aggregate_and_proof, callback } => {
+                            self.publish(GossipTopicKind::AggregateAndProof, GossipsubMessage::AggregateAndProof(aggregate_and_proof), callback);
+                        }
+                        P2PMessages::PublishSyncCommitteeMessage { subnet_id, sync_committee_message, callback } => {
+                            self.publish(GossipTopicKind::SyncCommittee(subnet_id), GossipsubMessage::SyncCommittee(sync_committee_message), callback);
+                        }
+                        P2PMessages::PublishContributionAndProof { contribution_and_proof, callback } => {
+                            self.publish(GossipTopicKind::SyncCommitteeContributionAndProof, GossipsubMessage::SyncCommitteeContributionAndProof(contribution_and_proof), callback);
+                        }
+                        P2PMessages::PublishVoluntaryExit { voluntary_exit, callback } => {
+                            self.publish(GossipTopicKind::VoluntaryExit, GossipsubMessage::VoluntaryExit(voluntary_exit), callback);
+                        }
+                        P2PMessages::PublishProposerSlashing { proposer_slashing, callback } => {
+                            self.publish(GossipTopicKind::ProposerSlashing, GossipsubMessage::ProposerSlashing(proposer_slashing), callback);
+                        }
+                        P2PMessages::PublishAttesterSlashing { attester_slashing, callback } => {
+                            self.publish(GossipTopicKind::AttesterSlashing, GossipsubMessage::AttesterSlashing(attester_slashing), callback);
+                        }
+                        P2PMessages::PublishBlsToExecutionChange { bls_to_execution_change, callback } => {
+                            self.publish(GossipTopicKind::BlsToExecutionChange, GossipsubMessage::BlsToExecutionChange(bls_to_execution_change), callback);
+                        }
                     }
                 }
             }
@@ -280,15 +483,22 @@ impl Network {
         info!("Event: {:?}", event);
         match event {
             SwarmEvent::Behaviour(behaviour_event) => match behaviour_event {
+                ReamBehaviourEvent::Identify(identify::Event::Received {
+                    peer_id, info, ..
+                }) => {
+                    self.peer_manager.on_identify(&peer_id, info.agent_version);
+                    None
+                }
                 ReamBehaviourEvent::Identify(_) => None,
-                ReamBehaviourEvent::Discovery(DiscoveredPeers { peers }) => {
-                    self.handle_discovered_peers(peers);
+                ReamBehaviourEvent::Discovery(DiscoveredPeers { query_type, peers }) => {
+                    self.handle_discovered_peers(query_type, peers);
                     None
                 }
                 ReamBehaviourEvent::ReqResp(message) => {
                     let ReqRespMessage {
                         peer_id,
                         connection_id,
+                        request_id,
                         message,
                     } = message;
 
@@ -296,23 +506,80 @@ impl Network {
                         Ok(message) => message,
                         Err(err) => {
                             warn!("Request Response failed: {err:?}");
+                            self.report_req_resp_error(&peer_id, &err);
+                            // The requester is told right away instead of waiting for its
+                            // timeout
+                            if let Some(callback) =
+                                request_id.and_then(|request_id| self.callbacks.remove(&request_id))
+                            {
+                                if let Err(err) = callback
+                                    .send(Err(anyhow!("Request to {peer_id} failed: {err}")))
+                                    .await
+                                {
+                                    warn!("Failed to send request error: {err:?}");
+                                }
+                            }
                             return None;
                         }
                     };
 
                     match message {
                         ReqRespMessageReceived::Request { stream_id, message } => {
-                            Some(ReamNetworkEvent::RequestMessage {
-                                peer_id,
-                                stream_id,
-                                connection_id,
-                                message,
-                            })
+                            if let Err(err) =
+                                self.inbound_rate_limiter
+                                    .allows(peer_id, &message, Instant::now())
+                            {
+                                self.reject_rate_limited_request(
+                                    peer_id,
+                                    connection_id,
+                                    stream_id,
+                                    err,
+                                );
+                                return None;
+                            }
+                            match message {
+                                // Ping and MetaData only depend on our local metadata, so they are
+                                // answered here instead of being forwarded to the manager
+                                RequestMessage::Ping(_) => {
+                                    self.send_single_response(
+                                        peer_id,
+                                        connection_id,
+                                        stream_id,
+                                        ResponseMessage::Ping(Ping {
+                                            sequence_number: self.meta_data.seq_number,
+                                        }),
+                                    );
+                                    None
+                                }
+                                RequestMessage::MetaData(_) => {
+                                    self.send_single_response(
+                                        peer_id,
+                                        connection_id,
+                                        stream_id,
+                                        ResponseMessage::MetaData(Arc::new(self.meta_data.clone())),
+                                    );
+                                    None
+                                }
+                                message => {
+                                    if let RequestMessage::Status(status) = &message {
+                                        self.peer_manager.on_status(&peer_id, status.clone());
+                                    }
+                                    Some(ReamNetworkEvent::RequestMessage {
+                                        peer_id,
+                                        stream_id,
+                                        connection_id,
+                                        message,
+                                    })
+                                }
+                            }
                         }
                         ReqRespMessageReceived::Response {
                             request_id,
                             message,
                         } => {
+                            if let ResponseMessage::Status(status) = &message {
+                                self.peer_manager.on_status(&peer_id, status.clone());
+                            }
                             let callback = self.callbacks.get(&request_id);
                             if let Some(callback) = callback {
                                 if let Err(err) = callback
@@ -336,15 +603,43 @@ impl Network {
                         }
                     }
                 }
-                ReamBehaviourEvent::Gossipsub(event) => {
-                    self.handle_gossipsub_event(event);
-                    None
-                }
+                ReamBehaviourEvent::Gossipsub(event) => self.handle_gossipsub_event(event),
                 ream_behavior_event => {
                     info!("Unhandled behaviour event: {ream_behavior_event:?}");
                     None
                 }
             },
+            SwarmEvent::ConnectionEstablished {
+                peer_id,
+                endpoint,
+                num_established,
+                ..
+            } => {
+                if num_established.get() > 1 {
+                    return None;
+                }
+                self.handle_connection_established(peer_id, &endpoint)
+            }
+            SwarmEvent::ConnectionClosed {
+                peer_id,
+                num_established,
+                ..
+            } => {
+                if num_established > 0 {
+                    return None;
+                }
+                self.peer_manager.on_connection_closed(&peer_id);
+                Some(ReamNetworkEvent::PeerDisconnected(peer_id))
+            }
+            SwarmEvent::OutgoingConnectionError {
+                peer_id: Some(peer_id),
+                error,
+                ..
+            } => {
+                trace!("Failed to connect to {peer_id}: {error:?}");
+                self.peer_manager.on_dial_failure(&peer_id);
+                None
+            }
             swarm_event => {
                 info!("Unhandled swarm event: {swarm_event:?}");
                 None
@@ -352,117 +647,317 @@ impl Network {
         }
     }
 
-    fn handle_discovered_peers(&mut self, peers: HashMap<Enr, Option<Instant>>) {
-        info!("Discovered peers: {:?}", peers);
+    /// Sends a single chunk response and closes the stream
+    fn send_single_response(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        message: ResponseMessage,
+    ) {
+        let req_resp = &mut self.swarm.behaviour_mut().req_resp;
+        req_resp.send_response(
+            peer_id,
+            connection_id,
+            stream_id,
+            RespMessage::Response(message),
+        );
+        req_resp.send_response(peer_id, connection_id, stream_id, RespMessage::EndOfStream);
+    }
+
+    /// Answers a request over its quota with an error, and reports the peer so that peers which
+    /// keep ignoring our limits are eventually banned.
+    fn reject_rate_limited_request(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        err: RateLimitedError,
+    ) {
+        let (reason, action) = match err {
+            RateLimitedError::TooLarge => (
+                "Request exceeds the quota of the protocol".to_string(),
+                PeerAction::LowToleranceError,
+            ),
+            RateLimitedError::Limited { wait } => (
+                format!("Retry in {}ms", wait.as_millis()),
+                PeerAction::HighToleranceError,
+            ),
+        };
+        debug!("Rate limited request from {peer_id}: {reason}");
+        self.swarm.behaviour_mut().req_resp.send_response(
+            peer_id,
+            connection_id,
+            stream_id,
+            RespMessage::Error(ReqRespError::RateLimited(reason)),
+        );
+        self.peer_manager
+            .report_peer(&peer_id, action, "rate_limited");
+    }
+
+    /// Saves the ENRs of the connected peers and of the routing table, to start from on restart.
+    fn persist_known_enrs(&self) {
+        let connected_enrs = self
+            .peer_manager
+            .connected_peers()
+            .filter_map(|(_, peer_info)| peer_info.enr.clone());
+        if let Err(err) = self
+            .swarm
+            .behaviour()
+            .discovery
+            .persist_known_enrs(connected_enrs)
+        {
+            warn!("Failed to persist known ENRs: {err:?}");
+        }
+    }
+
+    /// Returns the addresses of a peer in the order they should be dialed, QUIC before TCP.
+    fn dial_addresses(&self, enr: &Enr) -> Vec<Multiaddr> {
+        let mut multiaddrs = vec![];
+        if self.quic_enabled {
+            multiaddrs.extend(enr.quic_multiaddrs());
+        }
+        multiaddrs.extend(enr.tcp_multiaddrs());
+        multiaddrs
+    }
+
+    fn handle_discovered_peers(
+        &mut self,
+        query_type: QueryType,
+        peers: HashMap<Enr, Option<Instant>>,
+    ) {
+        info!("Discovered peers for {query_type:?}: {:?}", peers);
         for (enr, _) in peers {
-            let mut multiaddrs: Vec<Multiaddr> = Vec::new();
-            if let Some(ip) = enr.ip4() {
-                if let Some(tcp) = enr.tcp4() {
-                    let mut multiaddr: Multiaddr = ip.into();
-                    multiaddr.push(Protocol::Tcp(tcp));
-                    multiaddrs.push(multiaddr);
+            let peer_id = match enr.peer_id() {
+                Ok(peer_id) => peer_id,
+                Err(err) => {
+                    trace!("Skipping discovered peer with unsupported ENR: {err:?}");
+                    continue;
                 }
+            };
+
+            // Peers found for a subnet that is short of peers are dialed even above target
+            let should_dial = match query_type {
+                QueryType::Peers => self.peer_manager.should_dial(&peer_id),
+                QueryType::AttestationSubnetPeers(_) | QueryType::SyncCommitteeSubnetPeers(_) => {
+                    self.peer_manager.should_dial_subnet_peer(&peer_id)
+                }
+            };
+            if !should_dial {
+                continue;
+            }
+
+            let multiaddrs = self.dial_addresses(&enr);
+            if multiaddrs.is_empty() {
+                continue;
+            }
+
+            self.peer_manager.on_dialing(peer_id, Some(enr));
+            let dial_opts = DialOpts::peer_id(peer_id).addresses(multiaddrs).build();
+            if let Err(err) = self.swarm.dial(dial_opts) {
+                warn!("Failed to dial peer {peer_id}: {err:?}");
+                self.peer_manager.on_dial_failure(&peer_id);
             }
-            if let Some(ip6) = enr.ip6() {
-                if let Some(tcp6) = enr.tcp6() {
-                    let mut multiaddr: Multiaddr = ip6.into();
-                    multiaddr.push(Protocol::Tcp(tcp6));
-                    multiaddrs.push(multiaddr);
+        }
+    }
+
+    fn handle_peer_manager_events(&mut self) {
+        while let Some(event) = self.peer_manager.next_event() {
+            match event {
+                PeerManagerEvent::DisconnectPeer(peer_id, reason) => {
+                    info!("Disconnecting peer {peer_id}: {reason:?}");
+                    let request_id = self.request_id();
+                    self.swarm.behaviour_mut().req_resp.send_request(
+                        peer_id,
+                        request_id,
+                        RequestMessage::Goodbye(reason),
+                    );
+                    // Give the peer a moment to receive the goodbye
+                    self.pending_disconnects
+                        .push((peer_id, Instant::now() + GOODBYE_TIMEOUT));
+                }
+                PeerManagerEvent::DiscoverPeers(target_peers) => {
+                    self.swarm
+                        .behaviour_mut()
+                        .discovery
+                        .discover_peers(QueryType::Peers, target_peers);
+                }
+                PeerManagerEvent::DialPeer(peer_id, addresses) => {
+                    self.peer_manager.on_dialing(peer_id, None);
+                    let dial_opts = DialOpts::peer_id(peer_id).addresses(addresses).build();
+                    if let Err(err) = self.swarm.dial(dial_opts) {
+                        warn!("Failed to dial peer {peer_id}: {err:?}");
+                        self.peer_manager.on_dial_failure(&peer_id);
+                    }
                 }
             }
-            for multiaddr in multiaddrs {
-                if let Err(err) = self.swarm.dial(multiaddr) {
-                    warn!("Failed to dial peer: {err:?}");
+        }
+    }
+
+    /// Disconnects the peers whose `Goodbye` timed out.
+    fn disconnect_pending_peers(&mut self) {
+        let now = Instant::now();
+        let (expired, pending): (Vec<_>, Vec<_>) = self
+            .pending_disconnects
+            .drain(..)
+            .partition(|(_, deadline)| *deadline <= now);
+        self.pending_disconnects = pending;
+        for (peer_id, _) in expired {
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+        }
+    }
+
+    fn peer_manager_heartbeat(&mut self) {
+        let gossipsub = &self.swarm.behaviour().gossipsub;
+        let gossipsub_scores: Vec<(PeerId, f64)> = self
+            .peer_manager
+            .connected_peers()
+            .filter_map(|(peer_id, _)| Some((*peer_id, gossipsub.peer_score(peer_id)?)))
+            .collect();
+        for (peer_id, score) in gossipsub_scores {
+            self.peer_manager.update_gossipsub_score(&peer_id, score);
+        }
+        self.maintain_subnet_peers();
+        self.inbound_rate_limiter.prune(Instant::now());
+        self.swarm.behaviour_mut().req_resp.prune_rate_limiter();
+        self.peer_manager.heartbeat();
+        self.handle_peer_manager_events();
+    }
+
+    /// Protects the mesh peers of our subscribed subnets from pruning, and starts discovery
+    /// queries for subnets that are short of mesh peers.
+    fn maintain_subnet_peers(&mut self) {
+        let subnet_topics: Vec<GossipTopic> = self
+            .subscribed_topics
+            .lock()
+            .iter()
+            .filter(|topic| {
+                matches!(
+                    topic.kind,
+                    GossipTopicKind::BeaconAttestation(_) | GossipTopicKind::SyncCommittee(_)
+                )
+            })
+            .copied()
+            .collect();
+
+        let mut protected_peers = HashSet::new();
+        let mut attestation_subnets = vec![];
+        let mut sync_committee_subnets = vec![];
+        for topic in subnet_topics {
+            let topic_hash = Topic::from(topic).hash();
+            let mesh_peers: Vec<PeerId> = self
+                .swarm
+                .behaviour()
+                .gossipsub
+                .mesh_peers(&topic_hash)
+                .copied()
+                .collect();
+            trace!("{} mesh peers on {topic}", mesh_peers.len());
+            protected_peers.extend(mesh_peers.iter().take(MIN_SUBNET_PEERS));
+
+            if mesh_peers.len() < MIN_SUBNET_PEERS {
+                match topic.kind {
+                    GossipTopicKind::BeaconAttestation(subnet_id) => {
+                        attestation_subnets.push(subnet_id as u8)
+                    }
+                    GossipTopicKind::SyncCommittee(subnet_id) => {
+                        sync_committee_subnets.push(subnet_id as u8)
+                    }
+                    _ => {}
                 }
             }
         }
+        self.peer_manager.set_protected_peers(&protected_peers);
+
+        let discovery = &mut self.swarm.behaviour_mut().discovery;
+        if !attestation_subnets.is_empty() {
+            debug!("Searching for peers on attestation subnets {attestation_subnets:?}");
+            discovery.discover_subnet_peers(
+                QueryType::AttestationSubnetPeers(attestation_subnets),
+                MIN_SUBNET_PEERS,
+            );
+        }
+        if !sync_committee_subnets.is_empty() {
+            debug!("Searching for peers on sync committee subnets {sync_committee_subnets:?}");
+            discovery.discover_subnet_peers(
+                QueryType::SyncCommitteeSubnetPeers(sync_committee_subnets),
+                MIN_SUBNET_PEERS,
+            );
+        }
+    }
+
+    fn handle_connection_established(
+        &mut self,
+        peer_id: PeerId,
+        endpoint: &ConnectedPoint,
+    ) -> Option<ReamNetworkEvent> {
+        let direction = if endpoint.is_dialer() {
+            ConnectionDirection::Outgoing
+        } else {
+            ConnectionDirection::Incoming
+        };
+
+        if !self
+            .peer_manager
+            .on_connection_established(peer_id, direction)
+        {
+            return None;
+        }
+
+        Some(match direction {
+            ConnectionDirection::Incoming => ReamNetworkEvent::PeerConnectedIncoming(peer_id),
+            ConnectionDirection::Outgoing => ReamNetworkEvent::PeerConnectedOutgoing(peer_id),
+        })
+    }
+
+    /// Lowers the score of a peer whose request or response failed.
+    fn report_req_resp_error(&mut self, peer_id: &PeerId, err: &ReqRespError) {
+        let action = match err {
+            ReqRespError::InvalidData(_) | ReqRespError::IncompleteStream => {
+                PeerAction::LowToleranceError
+            }
+            ReqRespError::StreamTimedOut(_) => PeerAction::MidToleranceError,
+            ReqRespError::Disconnected => return,
+            _ => PeerAction::HighToleranceError,
+        };
+        self.peer_manager.report_peer(peer_id, action, "req_resp");
     }
 
-    fn handle_gossipsub_event(&mut self, event: GossipsubEvent) {
+    /// Decodes gossip messages, forwarding the ones fork choice needs to the manager.
+    fn handle_gossipsub_event(&mut self, event: GossipsubEvent) -> Option<ReamNetworkEvent> {
         info!("Gossipsub event: {:?}", event);
         match event {
             GossipsubEvent::Message {
-                propagation_source: _,
-                message_id: _,
+                propagation_source,
+                message_id,
                 message,
-            } => match GossipsubMessage::decode(&message.topic, &message.data) {
-                Ok(gossip_message) => match gossip_message {
-                    GossipsubMessage::BeaconBlock(signed_block) => {
-                        info!(
-                            "Beacon block received over gossipsub: slot: {}, root: {}",
-                            signed_block.message.slot,
-                            signed_block.message.block_root()
-                        );
-                    }
-                    GossipsubMessage::BeaconAttestation(attestation) => {
-                        info!(
-                            "Beacon Attestation received over gossipsub: root: {}",
-                            attestation.tree_hash_root()
-                        );
-                    }
-                    GossipsubMessage::BlsToExecutionChange(bls_to_execution_change) => {
-                        info!(
-                            "Bls To Execution Change received over gossipsub: root: {}",
-                            bls_to_execution_change.tree_hash_root()
-                        );
-                    }
-                    GossipsubMessage::AggregateAndProof(aggregate_and_proof) => {
-                        info!(
-                            "Aggregate And Proof received over gossipsub: root: {}",
-                            aggregate_and_proof.tree_hash_root()
-                        );
-                    }
-                    GossipsubMessage::SyncCommittee(sync_committee) => {
-                        info!(
-                            "Sync Committee received over gossipsub: root: {}",
-                            sync_committee.tree_hash_root()
-                        );
+            } => {
+                let decoded = GossipTopic::from_topic_hash(&message.topic).and_then(|topic| {
+                    Ok((
+                        topic,
+                        GossipsubMessage::decode(&message.topic, &message.data)?,
+                    ))
+                });
+                match decoded {
+                    Ok((topic, gossip_message)) => {
+                        trace!("Gossip message received on {topic} from {propagation_source}");
+                        return Some(ReamNetworkEvent::GossipsubMessage {
+                            propagation_source,
+                            message_id,
+                            topic,
+                            message: gossip_message,
+                        });
                     }
-                    GossipsubMessage::SyncCommitteeContributionAndProof(
-                        sync_committee_contribution_and_proof,
-                    ) => {
-                        info!(
-                            "Sync Committee Contribution And Proof received over gossipsub: root: {}",
-                            sync_committee_contribution_and_proof.tree_hash_root()
+                    Err(err) => {
+                        trace!("Failed to decode gossip message: {err:?}");
+                        self.report_message_validation_result(
+                            &message_id,
+                            &propagation_source,
+                            MessageAcceptance::Reject,
                         );
                     }
-                    GossipsubMessage::AttesterSlashing(attester_slashing) => {
-                        info!(
-                            "Attester Slashing received over gossipsub: root: {}",
-                            attester_slashing.tree_hash_root()
-                        );
-                    }
-                    GossipsubMessage::ProposerSlashing(proposer_slashing) => {
-                        info!(
-                            "Proposer Slashing received over gossipsub: root: {}",
-                            proposer_slashing.tree_hash_root()
-                        );
-                    }
-                    GossipsubMessage::BlobSidecar(blob_sidecar) => {
-                        info!(
-                            "Blob Sidecar received over gossipsub: root: {}",
-                            blob_sidecar.tree_hash_root()
-                        );
-                    }
-                    GossipsubMessage::LightClientFinalityUpdate(light_client_finality_update) => {
-                        info!(
-                            "Light Client Finality Update received over gossipsub: root: {}",
-                            light_client_finality_update.tree_hash_root()
-                        );
-                    }
-                    GossipsubMessage::LightClientOptimisticUpdate(
-                        light_client_optimistic_update,
-                    ) => {
-                        info!(
-                            "Light Client Optimistic Update received over gossipsub: root: {}",
-                            light_client_optimistic_update.tree_hash_root()
-                        );
-                    }
-                },
-                Err(err) => {
-                    trace!("Failed to decode gossip message: {err:?}");
                 }
-            },
+            }
             GossipsubEvent::Subscribed { peer_id, topic } => {
                 trace!("Peer {peer_id} subscribed to topic: {topic:?}");
             }
@@ -471,10 +966,156 @@ impl Network {
             }
             _ => {}
         }
+
+        None
+    }
+
+    /// Tells gossipsub whether to propagate a message, penalizing the peer that sent it if it was
+    /// invalid.
+    fn report_message_validation_result(
+        &mut self,
+        message_id: &MessageId,
+        propagation_source: &PeerId,
+        acceptance: MessageAcceptance,
+    ) {
+        if acceptance == MessageAcceptance::Reject {
+            self.peer_manager.report_peer(
+                propagation_source,
+                PeerAction::LowToleranceError,
+                "gossipsub",
+            );
+        }
+
+        self.swarm
+            .behaviour_mut()
+            .gossipsub
+            .report_message_validation_result(message_id, propagation_source, acceptance);
+    }
+
+    /// Updates the subnets advertised in our ENR and metadata, bumping their sequence number.
+    fn update_subnets(
+        &mut self,
+        attestation_subnets: AttestationSubnets,
+        sync_committee_subnets: SyncCommitteeSubnets,
+    ) {
+        if let Err(err) = self
+            .swarm
+            .behaviour_mut()
+            .discovery
+            .update_subnets(&attestation_subnets, &sync_committee_subnets)
+        {
+            warn!("Failed to update ENR subnets: {err:?}");
+            return;
+        }
+
+        self.meta_data = GetMetaDataV2 {
+            seq_number: self.swarm.behaviour().discovery.local_enr().seq(),
+            attnets: attestation_subnets.0,
+            syncnets: sync_committee_subnets.0,
+        };
+    }
+
+    /// Switches publishing to the fork digest of `enr_fork_id` and advertises it, along with the
+    /// next fork, in our ENR.
+    fn update_enr_fork_id(&mut self, enr_fork_id: EnrForkId) {
+        // Publishing follows the fork schedule even if the ENR can't be updated
+        self.fork_digest = enr_fork_id.fork_digest;
+
+        if let Err(err) = self
+            .swarm
+            .behaviour_mut()
+            .discovery
+            .update_enr_fork_id(&enr_fork_id)
+        {
+            warn!("Failed to update ENR fork id: {err:?}");
+            return;
+        }
+        self.meta_data.seq_number = self.swarm.behaviour().discovery.local_enr().seq();
+    }
+
+    /// Publishes a message on the topic of the current fork and reports the outcome to the
+    /// caller.
+    fn publish(
+        &mut self,
+        kind: GossipTopicKind,
+        message: GossipsubMessage,
+        callback: PublishCallback,
+    ) {
+        let topic = GossipTopic {
+            fork: self.fork_digest,
+            kind,
+        };
+        let topic_hash = Topic::from(topic).hash();
+        let mesh_n_low = self.mesh_n_low;
+        let gossipsub = &mut self.swarm.behaviour_mut().gossipsub;
+        let mesh_peers = gossipsub.mesh_peers(&topic_hash).count();
+        let result = gossipsub
+            .publish(topic_hash, message.encode())
+            .map_err(|err| match err {
+                PublishError::NoPeersSubscribedToTopic => {
+                    GossipsubError::InsufficientPeers(format!("{topic}: no peers subscribed"))
+                }
+                err => GossipsubError::PublishFailed(format!("on {topic}: {err:?}")),
+            })
+            .and_then(|message_id| {
+                trace!("Published message {message_id} on {topic} to {mesh_peers} mesh peers");
+                // The message went out, but may not reach the whole network
+                if mesh_peers < mesh_n_low {
+                    return Err(GossipsubError::InsufficientPeers(format!(
+                        "{topic}: {mesh_peers} mesh peers, expected at least {mesh_n_low}"
+                    )));
+                }
+                Ok(())
+            });
+        if let Err(err) = &result {
+            warn!("{err}");
+        }
+
+        // The caller may not wait for the outcome
+        let _ = callback.send(result);
+    }
+
+    /// Recomputes the score parameters of every subscribed topic and logs the resulting gossipsub
+    /// scores of our peers.
+    fn update_gossipsub_score_params(&mut self, active_validators: u64, current_slot: u64) {
+        self.active_validators = active_validators;
+        self.current_slot = current_slot;
+
+        let topics: Vec<GossipTopic> = self.subscribed_topics.lock().iter().copied().collect();
+        for topic in topics {
+            self.set_topic_score_params(topic);
+        }
+
+        let gossipsub = &self.swarm.behaviour().gossipsub;
+        for (peer_id, peer) in self.peer_manager.connected_peers() {
+            debug!(
+                "Peer {peer_id} gossipsub score: {:?}, peer manager score: {}",
+                gossipsub.peer_score(peer_id),
+                peer.effective_score()
+            );
+        }
+    }
+
+    fn set_topic_score_params(&mut self, topic: GossipTopic) {
+        let params = self.peer_score_settings.topic_score_params(
+            topic.kind,
+            self.active_validators,
+            self.current_slot,
+        );
+        let topic: Topic = topic.into();
+        if let Err(err) = self
+            .swarm
+            .behaviour_mut()
+            .gossipsub
+            .set_topic_params(topic.clone(), params)
+        {
+            warn!("Failed to set score parameters for topic {topic}: {err}");
+        }
     }
 
     fn subscribe_to_topic(&mut self, topic: GossipTopic) -> bool {
         self.subscribed_topics.lock().insert(topic);
+        self.set_topic_score_params(topic);
 
         let topic: Topic = topic.into();
 
@@ -485,7 +1126,6 @@ impl Network {
             .is_ok()
     }
 
-    #[allow(dead_code)]
     fn unsubscribe_from_topic(&mut self, topic: GossipTopic) -> bool {
         self.subscribed_topics.lock().remove(&topic);
 
@@ -493,9 +1133,77 @@ impl Network {
 
         self.swarm.behaviour_mut().gossipsub.unsubscribe(&topic)
     }
+
+    fn subscribe_to_topic_kind(&mut self, kind: GossipTopicKind) {
+        let fork_digests: Vec<B32> = self.fork_digests.iter().copied().collect();
+        for fork in fork_digests {
+            let topic = GossipTopic { fork, kind };
+            if !self.subscribe_to_topic(topic) {
+                error!("Failed to subscribe to topic: {topic}");
+            }
+        }
+    }
+
+    fn unsubscribe_from_topic_kind(&mut self, kind: GossipTopicKind) {
+        let fork_digests: Vec<B32> = self.fork_digests.iter().copied().collect();
+        for fork in fork_digests {
+            self.unsubscribe_from_topic(GossipTopic { fork, kind });
+        }
+    }
+
+    /// Subscribes to every topic we are subscribed to under the given fork digest as well.
+    fn subscribe_to_fork(&mut self, fork_digest: B32) {
+        if !self.fork_digests.insert(fork_digest) {
+            return;
+        }
+
+        let kinds: HashSet<GossipTopicKind> = self
+            .subscribed_topics
+            .lock()
+            .iter()
+            .map(|topic| topic.kind)
+            .collect();
+        for kind in kinds {
+            let topic = GossipTopic {
+                fork: fork_digest,
+                kind,
+            };
+            if !self.subscribe_to_topic(topic) {
+                error!("Failed to subscribe to topic: {topic}");
+            }
+        }
+        info!("Subscribed to topics of fork digest {fork_digest}");
+    }
+
+    fn unsubscribe_from_fork(&mut self, fork_digest: B32) {
+        if !self.fork_digests.remove(&fork_digest) {
+            return;
+        }
+
+        let topics: Vec<GossipTopic> = self
+            .subscribed_topics
+            .lock()
+            .iter()
+            .filter(|topic| topic.fork == fork_digest)
+            .copied()
+            .collect();
+        for topic in topics {
+            self.unsubscribe_from_topic(topic);
+        }
+        info!("Unsubscribed from topics of fork digest {fork_digest}");
+    }
+}
+
+impl Drop for Network {
+    fn drop(&mut self) {
+        self.persist_known_enrs();
+    }
 }
 
-pub fn build_transport(local_private_key: Keypair) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
+pub fn build_transport(
+    local_private_key: Keypair,
+    quic_enabled: bool,
+) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
     // mplex config
     let mut mplex_config = MplexConfig::new();
     mplex_config.set_max_buffer_size(256);
@@ -508,7 +1216,17 @@ pub fn build_transport(local_private_key: Keypair) -> io::Result<Boxed<(PeerId,
         .authenticate(NoiseConfig::new(&local_private_key).expect("Noise disabled"))
         .multiplex(SelectUpgrade::new(yamux_config, mplex_config))
         .timeout(Duration::from_secs(10));
-    let transport = tcp.boxed();
+    let transport = if quic_enabled {
+        let quic = QuicTransport::new(QuicConfig::new(&local_private_key));
+        tcp.or_transport(quic)
+            .map(|output, _| match output {
+                Either::Left((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+                Either::Right((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+            })
+            .boxed()
+    } else {
+        tcp.boxed()
+    };
 
     let transport = DnsTransport::system(transport)?.boxed();
 
@@ -520,19 +1238,24 @@ mod tests {
     use std::net::IpAddr;
 
     use alloy_primitives::{B256, aliases::B32};
-    use ream_consensus::constants::GENESIS_VALIDATORS_ROOT;
+    use ream_bls::BLSSignature;
+    use ream_consensus::{
+        constants::GENESIS_VALIDATORS_ROOT,
+        voluntary_exit::{SignedVoluntaryExit, VoluntaryExit},
+    };
     use ream_discv5::{
         config::DiscoveryConfig,
+        listen_address::ListenAddress,
         subnet::{AttestationSubnets, SyncCommitteeSubnets},
     };
     use ream_executor::ReamExecutor;
-    use ream_network_spec::networks::{DEV, set_network_spec};
-    use tokio::runtime::Runtime;
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use tokio::{runtime::Runtime, sync::oneshot};
 
     use super::*;
     use crate::{
-        config::NetworkConfig,
-        gossipsub::{configurations::GossipsubConfig, topics::GossipTopicKind},
+        config::NetworkConfig, gossipsub::configurations::GossipsubConfig,
+        peer_manager::PeerManagerConfig, req_resp::rate_limiter::RateLimiterConfig,
     };
 
     async fn create_network(
@@ -544,30 +1267,32 @@ mod tests {
         topics: Vec<GossipTopic>,
     ) -> anyhow::Result<Network> {
         let executor = ReamExecutor::new().unwrap();
+        let data_dir = tempfile::tempdir()?;
 
-        let discv5_config = discv5::ConfigBuilder::new(discv5::ListenConfig::from_ip(
-            socket_address,
-            discovery_port,
-        ))
-        .build();
+        let listen_address = ListenAddress::new(socket_address, socket_port, discovery_port, None);
+        let discv5_config =
+            discv5::ConfigBuilder::new(listen_address.discv5_listen_config()).build();
 
         let config = NetworkConfig {
-            socket_address,
-            socket_port,
+            listen_address: listen_address.clone(),
             discv5_config: DiscoveryConfig {
                 discv5_config,
                 bootnodes,
-                socket_address,
-                socket_port,
-                discovery_port,
+                listen_address,
                 disable_discovery,
                 attestation_subnets: AttestationSubnets::new(),
                 sync_committee_subnets: SyncCommitteeSubnets::new(),
+                enr_fork_id: EnrForkId::default(),
+                data_dir: Some(data_dir.path().to_path_buf()),
             },
             gossipsub_config: GossipsubConfig {
                 topics,
                 ..Default::default()
             },
+            peer_manager_config: PeerManagerConfig::default(),
+            rate_limiter_config: RateLimiterConfig::default(),
+            data_dir: data_dir.path().to_path_buf(),
+            private_key_file: None,
         };
 
         Network::init(executor, &config).await
@@ -576,7 +1301,7 @@ mod tests {
     #[test]
     fn test_p2p_gossipsub() {
         let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
-        set_network_spec(DEV.clone());
+        initialize_test_network_spec();
 
         let runtime = Runtime::new().unwrap();
 
@@ -642,4 +1367,117 @@ mod tests {
             }
         });
     }
+
+    fn voluntary_exit_message() -> GossipsubMessage {
+        GossipsubMessage::VoluntaryExit(Box::new(SignedVoluntaryExit {
+            message: VoluntaryExit {
+                epoch: 0,
+                validator_index: 0,
+            },
+            signature: BLSSignature::infinity(),
+        }))
+    }
+
+    #[test]
+    fn test_publish_without_peers() {
+        let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
+        initialize_test_network_spec();
+
+        let runtime = Runtime::new().unwrap();
+        let topic = GossipTopic {
+            fork: B32::ZERO,
+            kind: GossipTopicKind::VoluntaryExit,
+        };
+        let mut network = runtime
+            .block_on(create_network(
+                "127.0.0.1".parse::<IpAddr>().unwrap(),
+                9004,
+                9005,
+                vec![],
+                true,
+                vec![topic],
+            ))
+            .unwrap();
+
+        let (callback, receiver) = oneshot::channel();
+        network.publish(topic.kind, voluntary_exit_message(), callback);
+        assert!(matches!(
+            receiver.blocking_recv().unwrap(),
+            Err(GossipsubError::InsufficientPeers(_))
+        ));
+
+        // Publishing moves to the new fork even if the ENR can't be updated
+        let fork_digest = B32::repeat_byte(1);
+        network.update_enr_fork_id(EnrForkId {
+            fork_digest,
+            ..Default::default()
+        });
+        assert_eq!(network.fork_digest, fork_digest);
+    }
+
+    #[test]
+    fn test_publish_with_too_few_mesh_peers() {
+        let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
+        initialize_test_network_spec();
+
+        let runtime = Runtime::new().unwrap();
+        let topic = GossipTopic {
+            fork: B32::ZERO,
+            kind: GossipTopicKind::VoluntaryExit,
+        };
+
+        let mut network1 = runtime
+            .block_on(create_network(
+                "127.0.0.1".parse::<IpAddr>().unwrap(),
+                9006,
+                9007,
+                vec![],
+                true,
+                vec![topic],
+            ))
+            .unwrap();
+        let network1_enr = network1.enr();
+        let mut network2 = runtime
+            .block_on(create_network(
+                "127.0.0.1".parse::<IpAddr>().unwrap(),
+                9008,
+                9009,
+                vec![network1_enr],
+                false,
+                vec![topic],
+            ))
+            .unwrap();
+
+        runtime.block_on(async {
+            let topic_hash = Topic::from(topic).hash();
+            let network1_future = async {
+                loop {
+                    let event = network1.swarm.select_next_some().await;
+                    let _ = network1.parse_swarm_event(event).await;
+                    let gossipsub = &network1.swarm.behaviour().gossipsub;
+                    if gossipsub.mesh_peers(&topic_hash).next().is_some() {
+                        let (callback, receiver) = oneshot::channel();
+                        network1.publish(topic.kind, voluntary_exit_message(), callback);
+                        return receiver.await.unwrap();
+                    }
+                }
+            };
+
+            let network2_future = async {
+                loop {
+                    let event = network2.swarm.select_next_some().await;
+                    let _ = network2.parse_swarm_event(event).await;
+                }
+            };
+
+            // A single mesh peer is below `mesh_n_low`, the message is still sent
+            tokio::select! {
+                result = network1_future => assert!(matches!(
+                    result,
+                    Err(GossipsubError::InsufficientPeers(_))
+                )),
+                _ = network2_future => {}
+            }
+        });
+    }
 }
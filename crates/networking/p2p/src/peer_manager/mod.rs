@@ -0,0 +1,554 @@
+pub mod peer_info;
+pub mod static_peer;
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+use discv5::Enr;
+use libp2p::{Multiaddr, PeerId};
+use peer_info::{
+    ConnectionDirection, ConnectionState, MIN_SCORE_BEFORE_BAN, MIN_SCORE_BEFORE_DISCONNECT,
+    PeerAction, PeerInfo,
+};
+use static_peer::StaticPeer;
+use tracing::{debug, info};
+
+use crate::req_resp::messages::{goodbye::Goodbye, status::Status};
+
+/// How often the peer manager decays scores, lifts bans and prunes excess peers.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Fraction of a peer's score kept on every heartbeat, so misbehaviour is eventually forgiven.
+const SCORE_DECAY_FACTOR: f64 = 0.9;
+
+/// Delay before redialing a static peer, doubled on every failed attempt up to the maximum.
+const STATIC_PEER_MIN_BACKOFF: Duration = Duration::from_secs(5);
+const STATIC_PEER_MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone)]
+pub struct PeerManagerConfig {
+    /// Number of connected peers we try to maintain.
+    pub target_peers: usize,
+    /// Inbound connections beyond this are refused.
+    pub max_peers: usize,
+    /// How long a peer stays banned after a protocol violation.
+    pub ban_duration: Duration,
+    /// Peers we keep connected to, redialing them with backoff. They are never pruned.
+    pub static_peers: Vec<StaticPeer>,
+    /// Peers exempt from scoring, bans and the peer limits.
+    pub trusted_peers: HashSet<PeerId>,
+}
+
+impl Default for PeerManagerConfig {
+    fn default() -> Self {
+        Self {
+            target_peers: 50,
+            max_peers: 55,
+            ban_duration: Duration::from_secs(30 * 60),
+            static_peers: vec![],
+            trusted_peers: HashSet::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerManagerEvent {
+    /// Send a `Goodbye` with the given reason and disconnect from the peer.
+    DisconnectPeer(PeerId, Goodbye),
+    /// Run a discovery query for this many new peers.
+    DiscoverPeers(usize),
+    /// Dial a static peer at the given addresses.
+    DialPeer(PeerId, Vec<Multiaddr>),
+}
+
+/// Redial schedule of a static peer.
+struct StaticPeerDial {
+    addresses: Vec<Multiaddr>,
+    next_dial: Instant,
+    backoff: Duration,
+}
+
+/// Keeps track of every peer we know about, their reputation and the connection limits.
+pub struct PeerManager {
+    config: PeerManagerConfig,
+    peers: HashMap<PeerId, PeerInfo>,
+    static_peers: HashMap<PeerId, StaticPeerDial>,
+    events: VecDeque<PeerManagerEvent>,
+}
+
+impl PeerManager {
+    pub fn new(config: PeerManagerConfig) -> Self {
+        let now = Instant::now();
+        let static_peers = config
+            .static_peers
+            .iter()
+            .map(|static_peer| {
+                (
+                    static_peer.peer_id,
+                    StaticPeerDial {
+                        addresses: static_peer.addresses.clone(),
+                        next_dial: now,
+                        backoff: STATIC_PEER_MIN_BACKOFF,
+                    },
+                )
+            })
+            .collect();
+        Self {
+            config,
+            peers: HashMap::new(),
+            static_peers,
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn peer(&self, peer_id: &PeerId) -> Option<&PeerInfo> {
+        self.peers.get(peer_id)
+    }
+
+    pub fn connected_peers(&self) -> impl Iterator<Item = (&PeerId, &PeerInfo)> {
+        self.peers.iter().filter(|(_, peer)| peer.is_connected())
+    }
+
+    pub fn connected_peer_count(&self) -> usize {
+        self.connected_peers().count()
+    }
+
+    fn dialing_peer_count(&self) -> usize {
+        self.peers
+            .values()
+            .filter(|peer| peer.state == ConnectionState::Dialing)
+            .count()
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.peers.get(peer_id).is_some_and(PeerInfo::is_banned)
+    }
+
+    pub fn is_trusted(&self, peer_id: &PeerId) -> bool {
+        self.config.trusted_peers.contains(peer_id)
+    }
+
+    pub fn is_static(&self, peer_id: &PeerId) -> bool {
+        self.static_peers.contains_key(peer_id)
+    }
+
+    /// Returns whether a newly discovered peer should be dialed, i.e. we are below target and
+    /// the peer isn't banned or already being dealt with.
+    pub fn should_dial(&self, peer_id: &PeerId) -> bool {
+        !self.is_known(peer_id)
+            && self.connected_peer_count() + self.dialing_peer_count() < self.config.target_peers
+    }
+
+    /// Like [`PeerManager::should_dial`], but for peers on a subnet that is short of peers. These
+    /// may take us above target, up to the maximum number of peers.
+    pub fn should_dial_subnet_peer(&self, peer_id: &PeerId) -> bool {
+        !self.is_known(peer_id)
+            && self.connected_peer_count() + self.dialing_peer_count() < self.config.max_peers
+    }
+
+    /// Returns whether the peer is banned or we are already dialing, connected to or
+    /// disconnecting from it.
+    fn is_known(&self, peer_id: &PeerId) -> bool {
+        self.peers.get(peer_id).is_some_and(|peer| {
+            peer.is_banned()
+                || matches!(
+                    peer.state,
+                    ConnectionState::Dialing
+                        | ConnectionState::Connected(_)
+                        | ConnectionState::Disconnecting
+                )
+        })
+    }
+
+    /// Marks the given peers as needed for our subnets, which excludes them from pruning.
+    pub fn set_protected_peers(&mut self, protected_peers: &HashSet<PeerId>) {
+        for (peer_id, peer) in self.peers.iter_mut() {
+            peer.is_protected = protected_peers.contains(peer_id);
+        }
+    }
+
+    pub fn on_dialing(&mut self, peer_id: PeerId, enr: Option<Enr>) {
+        let peer = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerInfo::new(ConnectionState::Dialing));
+        peer.state = ConnectionState::Dialing;
+        if enr.is_some() {
+            peer.enr = enr;
+        }
+    }
+
+    pub fn on_dial_failure(&mut self, peer_id: &PeerId) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            if peer.state == ConnectionState::Dialing {
+                peer.state = ConnectionState::Disconnected;
+            }
+        }
+    }
+
+    /// Registers a new connection. Returns `false` if the peer has to be disconnected, in which
+    /// case a [`PeerManagerEvent::DisconnectPeer`] has been queued.
+    pub fn on_connection_established(
+        &mut self,
+        peer_id: PeerId,
+        direction: ConnectionDirection,
+    ) -> bool {
+        if self.is_banned(&peer_id) {
+            self.disconnect(peer_id, Goodbye::Banned);
+            return false;
+        }
+
+        if direction == ConnectionDirection::Incoming
+            && self.connected_peer_count() >= self.config.max_peers
+            && !self.is_trusted(&peer_id)
+            && !self.is_static(&peer_id)
+        {
+            self.disconnect(peer_id, Goodbye::TooManyPeers);
+            return false;
+        }
+
+        self.peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerInfo::new(ConnectionState::Connected(direction)))
+            .state = ConnectionState::Connected(direction);
+        if let Some(static_peer) = self.static_peers.get_mut(&peer_id) {
+            static_peer.backoff = STATIC_PEER_MIN_BACKOFF;
+        }
+
+        debug!(
+            "Peer {peer_id} connected ({direction:?}), {} peers connected",
+            self.connected_peer_count()
+        );
+        true
+    }
+
+    pub fn on_connection_closed(&mut self, peer_id: &PeerId) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            if !peer.is_banned() {
+                peer.state = ConnectionState::Disconnected;
+            }
+        }
+        if self.is_static(peer_id) {
+            self.dial_static_peers();
+        }
+    }
+
+    /// Queues a dial for every static peer we aren't connected to whose backoff has passed.
+    pub fn dial_static_peers(&mut self) {
+        let now = Instant::now();
+        for (peer_id, static_peer) in self.static_peers.iter_mut() {
+            let is_known = self.peers.get(peer_id).is_some_and(|peer| {
+                peer.is_banned()
+                    || matches!(
+                        peer.state,
+                        ConnectionState::Dialing
+                            | ConnectionState::Connected(_)
+                            | ConnectionState::Disconnecting
+                    )
+            });
+            if is_known || static_peer.next_dial > now {
+                continue;
+            }
+
+            debug!("Dialing static peer {peer_id}");
+            self.events.push_back(PeerManagerEvent::DialPeer(
+                *peer_id,
+                static_peer.addresses.clone(),
+            ));
+            static_peer.next_dial = now + static_peer.backoff;
+            static_peer.backoff = (static_peer.backoff * 2).min(STATIC_PEER_MAX_BACKOFF);
+        }
+    }
+
+    pub fn on_identify(&mut self, peer_id: &PeerId, agent_version: String) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.agent_version = Some(agent_version);
+        }
+    }
+
+    pub fn on_status(&mut self, peer_id: &PeerId, status: Status) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.status = Some(status);
+        }
+    }
+
+    pub fn update_gossipsub_score(&mut self, peer_id: &PeerId, score: f64) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.gossipsub_score = score;
+        }
+        self.enforce_score(peer_id);
+    }
+
+    /// Lowers a peer's score for misbehaving, disconnecting or banning it once the score gets
+    /// too low.
+    pub fn report_peer(&mut self, peer_id: &PeerId, action: PeerAction, source: &str) {
+        if self.is_trusted(peer_id) {
+            debug!("Ignoring report of trusted peer {peer_id} for {action:?} from {source}");
+            return;
+        }
+        let Some(peer) = self.peers.get_mut(peer_id) else {
+            return;
+        };
+        peer.add_to_score(action.score_delta());
+        debug!(
+            "Reported peer {peer_id} for {action:?} from {source}, new score: {}",
+            peer.effective_score()
+        );
+        self.enforce_score(peer_id);
+    }
+
+    /// Disconnects or bans a peer whose score got too low.
+    fn enforce_score(&mut self, peer_id: &PeerId) {
+        if self.is_trusted(peer_id) {
+            return;
+        }
+        let Some(peer) = self.peers.get(peer_id) else {
+            return;
+        };
+        let score = peer.effective_score();
+        if score <= MIN_SCORE_BEFORE_BAN {
+            self.ban(*peer_id);
+        } else if score <= MIN_SCORE_BEFORE_DISCONNECT && peer.is_connected() {
+            self.disconnect(*peer_id, Goodbye::BadScore);
+        }
+    }
+
+    /// Bans a peer for the configured ban duration, disconnecting it if needed.
+    pub fn ban(&mut self, peer_id: PeerId) {
+        if self.is_trusted(&peer_id) {
+            return;
+        }
+        let until = Instant::now() + self.config.ban_duration;
+        let peer = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerInfo::new(ConnectionState::Disconnected));
+        let was_connected = matches!(
+            peer.state,
+            ConnectionState::Connected(_) | ConnectionState::Dialing
+        );
+        peer.state = ConnectionState::Banned { until };
+
+        info!("Banned peer {peer_id}");
+        if was_connected {
+            self.events
+                .push_back(PeerManagerEvent::DisconnectPeer(peer_id, Goodbye::Banned));
+        }
+    }
+
+    /// Sends the peer a `Goodbye` with the given reason and disconnects from it.
+    pub fn disconnect(&mut self, peer_id: PeerId, reason: Goodbye) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            if !peer.is_banned() {
+                peer.state = ConnectionState::Disconnecting;
+            }
+        }
+        self.events
+            .push_back(PeerManagerEvent::DisconnectPeer(peer_id, reason));
+    }
+
+    /// Decays scores, lifts expired bans, prunes peers above target and asks for more peers when
+    /// below target.
+    pub fn heartbeat(&mut self) {
+        let now = Instant::now();
+        for peer in self.peers.values_mut() {
+            peer.score *= SCORE_DECAY_FACTOR;
+            if let ConnectionState::Banned { until } = peer.state {
+                if until <= now {
+                    peer.state = ConnectionState::Disconnected;
+                }
+            }
+        }
+
+        let connected_peers = self.connected_peer_count();
+        if connected_peers > self.config.target_peers {
+            // Prune the worst scoring peers first, preferring inbound ones so we keep the peers
+            // we chose ourselves
+            let mut candidates: Vec<(PeerId, bool, f64)> = self
+                .connected_peers()
+                .filter(|(peer_id, peer)| {
+                    !peer.is_protected && !self.is_trusted(peer_id) && !self.is_static(peer_id)
+                })
+                .map(|(peer_id, peer)| {
+                    let is_outgoing =
+                        peer.state == ConnectionState::Connected(ConnectionDirection::Outgoing);
+                    (*peer_id, is_outgoing, peer.effective_score())
+                })
+                .collect();
+            candidates.sort_by(|(_, a_outgoing, a_score), (_, b_outgoing, b_score)| {
+                a_outgoing.cmp(b_outgoing).then(a_score.total_cmp(b_score))
+            });
+
+            for (peer_id, _, _) in candidates
+                .into_iter()
+                .take(connected_peers - self.config.target_peers)
+            {
+                self.disconnect(peer_id, Goodbye::TooManyPeers);
+            }
+        } else if connected_peers < self.config.target_peers {
+            self.events.push_back(PeerManagerEvent::DiscoverPeers(
+                self.config.target_peers - connected_peers,
+            ));
+        }
+
+        self.dial_static_peers();
+
+        // Forget about disconnected peers nobody cares about anymore
+        self.peers.retain(|_, peer| {
+            peer.state != ConnectionState::Disconnected
+                || peer.effective_score() < MIN_SCORE_BEFORE_DISCONNECT
+        });
+    }
+
+    pub fn next_event(&mut self) -> Option<PeerManagerEvent> {
+        self.events.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_manager(target_peers: usize, max_peers: usize) -> PeerManager {
+        PeerManager::new(PeerManagerConfig {
+            target_peers,
+            max_peers,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_max_peers_refuses_incoming() {
+        let mut peer_manager = peer_manager(1, 2);
+        assert!(
+            peer_manager.on_connection_established(PeerId::random(), ConnectionDirection::Incoming)
+        );
+        assert!(
+            peer_manager.on_connection_established(PeerId::random(), ConnectionDirection::Incoming)
+        );
+
+        let peer_id = PeerId::random();
+        assert!(!peer_manager.on_connection_established(peer_id, ConnectionDirection::Incoming));
+        assert_eq!(
+            peer_manager.next_event(),
+            Some(PeerManagerEvent::DisconnectPeer(
+                peer_id,
+                Goodbye::TooManyPeers
+            ))
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_prunes_to_target() {
+        let mut peer_manager = peer_manager(1, 3);
+        let outgoing = PeerId::random();
+        let incoming = PeerId::random();
+        peer_manager.on_connection_established(outgoing, ConnectionDirection::Outgoing);
+        peer_manager.on_connection_established(incoming, ConnectionDirection::Incoming);
+
+        peer_manager.heartbeat();
+        assert_eq!(
+            peer_manager.next_event(),
+            Some(PeerManagerEvent::DisconnectPeer(
+                incoming,
+                Goodbye::TooManyPeers
+            ))
+        );
+        assert_eq!(peer_manager.next_event(), None);
+    }
+
+    #[test]
+    fn test_heartbeat_keeps_protected_peers() {
+        let mut peer_manager = peer_manager(1, 3);
+        let protected = PeerId::random();
+        let unprotected = PeerId::random();
+        peer_manager.on_connection_established(protected, ConnectionDirection::Incoming);
+        peer_manager.on_connection_established(unprotected, ConnectionDirection::Outgoing);
+        peer_manager.set_protected_peers(&HashSet::from([protected]));
+
+        peer_manager.heartbeat();
+        assert_eq!(
+            peer_manager.next_event(),
+            Some(PeerManagerEvent::DisconnectPeer(
+                unprotected,
+                Goodbye::TooManyPeers
+            ))
+        );
+        assert_eq!(peer_manager.next_event(), None);
+    }
+
+    #[test]
+    fn test_static_and_trusted_peers() {
+        let static_peer = StaticPeer {
+            peer_id: PeerId::random(),
+            addresses: vec![Multiaddr::empty()],
+        };
+        let trusted_peer = PeerId::random();
+        let mut peer_manager = PeerManager::new(PeerManagerConfig {
+            target_peers: 0,
+            max_peers: 0,
+            static_peers: vec![static_peer.clone()],
+            trusted_peers: HashSet::from([trusted_peer]),
+            ..Default::default()
+        });
+
+        peer_manager.dial_static_peers();
+        assert_eq!(
+            peer_manager.next_event(),
+            Some(PeerManagerEvent::DialPeer(
+                static_peer.peer_id,
+                static_peer.addresses
+            ))
+        );
+        // Backing off before the next attempt
+        peer_manager.dial_static_peers();
+        assert_eq!(peer_manager.next_event(), None);
+
+        // Neither the peer limits nor pruning apply to them
+        assert!(
+            peer_manager
+                .on_connection_established(static_peer.peer_id, ConnectionDirection::Incoming)
+        );
+        assert!(
+            peer_manager.on_connection_established(trusted_peer, ConnectionDirection::Incoming)
+        );
+        peer_manager.heartbeat();
+        assert_eq!(peer_manager.next_event(), None);
+
+        peer_manager.report_peer(&trusted_peer, PeerAction::Fatal, "test");
+        assert!(!peer_manager.is_banned(&trusted_peer));
+    }
+
+    #[test]
+    fn test_low_score_bans_peer() {
+        let mut peer_manager = peer_manager(10, 10);
+        let peer_id = PeerId::random();
+        peer_manager.on_connection_established(peer_id, ConnectionDirection::Outgoing);
+
+        peer_manager.report_peer(&peer_id, PeerAction::Fatal, "test");
+        assert!(peer_manager.is_banned(&peer_id));
+        assert!(!peer_manager.should_dial(&peer_id));
+        assert_eq!(
+            peer_manager.next_event(),
+            Some(PeerManagerEvent::DisconnectPeer(peer_id, Goodbye::Banned))
+        );
+    }
+
+    #[test]
+    fn test_low_gossipsub_score_disconnects_peer() {
+        let mut peer_manager = peer_manager(10, 10);
+        let peer_id = PeerId::random();
+        peer_manager.on_connection_established(peer_id, ConnectionDirection::Outgoing);
+
+        peer_manager.update_gossipsub_score(&peer_id, -1000.0);
+        assert_eq!(peer_manager.next_event(), None);
+
+        peer_manager.update_gossipsub_score(&peer_id, -5000.0);
+        assert_eq!(
+            peer_manager.next_event(),
+            Some(PeerManagerEvent::DisconnectPeer(peer_id, Goodbye::BadScore))
+        );
+    }
+}
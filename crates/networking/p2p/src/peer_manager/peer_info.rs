@@ -0,0 +1,110 @@
+use std::time::Instant;
+
+use discv5::Enr;
+
+use crate::req_resp::messages::status::Status;
+
+/// Score a new peer starts out with.
+pub const DEFAULT_SCORE: f64 = 0.0;
+
+/// Scores are clamped to `[MIN_SCORE, MAX_SCORE]`.
+pub const MIN_SCORE: f64 = -100.0;
+pub const MAX_SCORE: f64 = 100.0;
+
+/// Peers at or below this score are disconnected.
+pub const MIN_SCORE_BEFORE_DISCONNECT: f64 = -20.0;
+
+/// Peers at or below this score are disconnected and banned.
+pub const MIN_SCORE_BEFORE_BAN: f64 = -50.0;
+
+/// Weight of a negative gossipsub score in the peer's score, chosen so that a peer reaching the
+/// gossipsub gossip threshold of -4000 gets disconnected.
+const GOSSIPSUB_SCORE_WEIGHT: f64 = MIN_SCORE_BEFORE_DISCONNECT / -4000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionDirection {
+    Incoming,
+    Outgoing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Dialing,
+    Connected(ConnectionDirection),
+    /// We sent a `Goodbye` and are waiting for the connection to close.
+    Disconnecting,
+    Disconnected,
+    Banned {
+        until: Instant,
+    },
+}
+
+/// How bad a peer's misbehaviour was, which decides how much its score drops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerAction {
+    /// The peer is banned straight away.
+    Fatal,
+    /// Around 5 occurrences get the peer banned.
+    LowToleranceError,
+    /// Around 10 occurrences get the peer banned.
+    MidToleranceError,
+    /// Around 50 occurrences get the peer banned.
+    HighToleranceError,
+}
+
+impl PeerAction {
+    pub fn score_delta(&self) -> f64 {
+        match self {
+            PeerAction::Fatal => MIN_SCORE,
+            PeerAction::LowToleranceError => -10.0,
+            PeerAction::MidToleranceError => -5.0,
+            PeerAction::HighToleranceError => -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PeerInfo {
+    pub state: ConnectionState,
+    pub score: f64,
+    /// The peer's score as computed by gossipsub, refreshed on every heartbeat.
+    pub gossipsub_score: f64,
+    pub agent_version: Option<String>,
+    pub status: Option<Status>,
+    pub enr: Option<Enr>,
+    /// The peer serves a subnet we have duties on and is kept when pruning.
+    pub is_protected: bool,
+}
+
+impl PeerInfo {
+    pub fn new(state: ConnectionState) -> Self {
+        Self {
+            state,
+            score: DEFAULT_SCORE,
+            gossipsub_score: 0.0,
+            agent_version: None,
+            status: None,
+            enr: None,
+            is_protected: false,
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        matches!(self.state, ConnectionState::Connected(_))
+    }
+
+    pub fn is_banned(&self) -> bool {
+        matches!(self.state, ConnectionState::Banned { until } if until > Instant::now())
+    }
+
+    pub fn add_to_score(&mut self, delta: f64) {
+        self.score = (self.score + delta).clamp(MIN_SCORE, MAX_SCORE);
+    }
+
+    /// The score we act on, combining our own score with penalties from gossipsub. A good
+    /// gossipsub score doesn't make up for misbehaving elsewhere.
+    pub fn effective_score(&self) -> f64 {
+        (self.score + self.gossipsub_score.min(0.0) * GOSSIPSUB_SCORE_WEIGHT)
+            .clamp(MIN_SCORE, MAX_SCORE)
+    }
+}
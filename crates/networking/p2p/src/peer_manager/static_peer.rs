@@ -0,0 +1,65 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use discv5::Enr;
+use libp2p::{Multiaddr, PeerId, multiaddr::Protocol};
+use ream_discv5::enr_ext::EnrExt;
+
+/// A peer we always keep a connection to, given as an ENR or as a multiaddr ending in
+/// `/p2p/<peer id>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticPeer {
+    pub peer_id: PeerId,
+    /// Addresses in the order they are dialed
+    pub addresses: Vec<Multiaddr>,
+}
+
+impl FromStr for StaticPeer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("enr:") {
+            let enr = Enr::from_str(s).map_err(|err| anyhow!("Failed to parse ENR: {err}"))?;
+            let addresses: Vec<Multiaddr> = enr
+                .quic_multiaddrs()
+                .into_iter()
+                .chain(enr.tcp_multiaddrs())
+                .collect();
+            if addresses.is_empty() {
+                bail!("ENR {s} has no TCP or QUIC address");
+            }
+            return Ok(Self {
+                peer_id: enr.peer_id()?,
+                addresses,
+            });
+        }
+
+        let mut address = Multiaddr::from_str(s)?;
+        let Some(Protocol::P2p(peer_id)) = address.pop() else {
+            bail!("Multiaddr {s} must end in /p2p/<peer id>");
+        };
+        Ok(Self {
+            peer_id,
+            addresses: vec![address],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_static_peer() {
+        let peer_id = PeerId::random();
+        let static_peer =
+            StaticPeer::from_str(&format!("/ip4/127.0.0.1/tcp/9000/p2p/{peer_id}")).unwrap();
+        assert_eq!(static_peer.peer_id, peer_id);
+        assert_eq!(
+            static_peer.addresses,
+            vec![Multiaddr::from_str("/ip4/127.0.0.1/tcp/9000").unwrap()]
+        );
+
+        assert!(StaticPeer::from_str("/ip4/127.0.0.1/tcp/9000").is_err());
+    }
+}
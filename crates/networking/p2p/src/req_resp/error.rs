@@ -24,6 +24,12 @@ pub enum ReqRespError {
 
     #[error("Raw error message {0}")]
     RawError(String),
+
+    #[error("Resource unavailable {0}")]
+    ResourceUnavailable(String),
+
+    #[error("Rate limited {0}")]
+    RateLimited(String),
 }
 
 impl From<ssz::DecodeError> for ReqRespError {
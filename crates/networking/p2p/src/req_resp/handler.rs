@@ -59,9 +59,10 @@ impl RespMessage {
                 | ReqRespError::Anyhow(_)
                 | ReqRespError::IoError(_) => Some(ResponseCode::ServerError),
                 ReqRespError::InvalidData(_) => Some(ResponseCode::InvalidRequest),
-                ReqRespError::Disconnected | ReqRespError::StreamTimedOut(_) => {
-                    Some(ResponseCode::ResourceUnavailable)
-                }
+                ReqRespError::Disconnected
+                | ReqRespError::StreamTimedOut(_)
+                | ReqRespError::ResourceUnavailable(_)
+                | ReqRespError::RateLimited(_) => Some(ResponseCode::ResourceUnavailable),
             },
             RespMessage::EndOfStream => None,
         }
@@ -72,6 +73,11 @@ impl RespMessage {
 pub enum HandlerEvent {
     Ok(ReqRespMessageReceived),
     Err(ReqRespError),
+    /// One of our requests failed, no further responses will arrive for it
+    OutboundErr {
+        request_id: u64,
+        err: ReqRespError,
+    },
     Close,
 }
 
@@ -190,9 +196,17 @@ impl ReqRespConnectionHandler {
     fn on_dial_upgrade_error(
         &mut self,
         error: StreamUpgradeError<ReqRespError>,
-        _info: OutboundOpenInfo,
+        info: OutboundOpenInfo,
     ) {
         error!("REQRESP: Dial upgrade error: {:?}", error);
+        let err = match error {
+            StreamUpgradeError::Apply(err) => err,
+            err => ReqRespError::RawError(err.to_string()),
+        };
+        self.behaviour_events.push(HandlerEvent::OutboundErr {
+            request_id: info.request_id,
+            err,
+        });
     }
 
     fn request(&mut self, request_id: u64, message: RequestMessage) {
@@ -202,8 +216,10 @@ impl ReqRespConnectionHandler {
                 message,
             });
         } else {
-            self.behaviour_events
-                .push(HandlerEvent::Err(ReqRespError::Disconnected));
+            self.behaviour_events.push(HandlerEvent::OutboundErr {
+                request_id,
+                err: ReqRespError::Disconnected,
+            });
         }
     }
 
@@ -213,9 +229,13 @@ impl ReqRespConnectionHandler {
             return;
         };
 
+        // Rate limited peers are reported by the network, depending on how far over the limit
+        // they are
         if let RespMessage::Error(err) = &message {
-            self.behaviour_events
-                .push(HandlerEvent::Err(ReqRespError::RawError(err.to_string())));
+            if !matches!(err, ReqRespError::RateLimited(_)) {
+                self.behaviour_events
+                    .push(HandlerEvent::Err(ReqRespError::RawError(err.to_string())));
+            }
         }
 
         if let ConnectionState::Closed = self.connection_state {
@@ -357,8 +377,10 @@ impl ConnectionHandler for ReqRespConnectionHandler {
                 } => {
                     if let ConnectionState::Closed = self.connection_state {
                         outbound_stream.state = Some(OutboundStreamState::Closing(stream));
-                        self.behaviour_events
-                            .push(HandlerEvent::Err(ReqRespError::Disconnected));
+                        self.behaviour_events.push(HandlerEvent::OutboundErr {
+                            request_id: outbound_stream.request_id,
+                            err: ReqRespError::Disconnected,
+                        });
                         continue;
                     }
 
@@ -379,7 +401,10 @@ impl ConnectionHandler for ReqRespConnectionHandler {
                                 Err(err) => {
                                     streams_to_remove.push(*stream_id);
                                     return Poll::Ready(ConnectionHandlerEvent::NotifyBehaviour(
-                                        HandlerEvent::Err(err),
+                                        HandlerEvent::OutboundErr {
+                                            request_id: outbound_stream.request_id,
+                                            err,
+                                        },
                                     ));
                                 }
                             };
@@ -400,7 +425,10 @@ impl ConnectionHandler for ReqRespConnectionHandler {
                                         })
                                     }
                                     RespMessage::Error(req_resp_error) => {
-                                        HandlerEvent::Err(req_resp_error)
+                                        HandlerEvent::OutboundErr {
+                                            request_id: outbound_stream.request_id,
+                                            err: req_resp_error,
+                                        }
                                     }
                                     RespMessage::EndOfStream => HandlerEvent::Close,
                                 },
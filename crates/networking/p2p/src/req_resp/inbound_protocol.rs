@@ -15,7 +15,7 @@ use libp2p::{
     bytes::{Buf, BufMut},
     core::UpgradeInfo,
 };
-use ream_consensus::constants::genesis_validators_root;
+use ream_consensus::{constants::genesis_validators_root, misc::compute_epoch_at_slot};
 use ream_network_spec::networks::network_spec;
 use snap::{read::FrameDecoder, write::FrameEncoder};
 use ssz::{Decode, Encode};
@@ -41,6 +41,9 @@ use crate::{
     req_resp::messages::{
         beacon_blocks::{BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request},
         blob_sidecars::{BlobSidecarsByRangeV1Request, BlobSidecarsByRootV1Request},
+        light_client::{
+            EmptyRequest, LightClientBootstrapV1Request, LightClientUpdatesByRangeV1Request,
+        },
     },
     utils::max_message_size,
 };
@@ -80,6 +83,14 @@ where
                     RequestMessage::MetaData(GetMetaDataV2::default().into()),
                     socket,
                 )),
+                SupportedProtocol::LightClientFinalityUpdateV1 => Ok((
+                    RequestMessage::LightClientFinalityUpdate(EmptyRequest),
+                    socket,
+                )),
+                SupportedProtocol::LightClientOptimisticUpdateV1 => Ok((
+                    RequestMessage::LightClientOptimisticUpdate(EmptyRequest),
+                    socket,
+                )),
                 _ => match timeout(Duration::from_secs(15), socket.into_future()).await {
                     Ok((Some(Ok(message)), stream)) => Ok((message, stream)),
                     Ok((Some(Err(err)), _)) => Err(err),
@@ -115,6 +126,13 @@ impl Encoder<RespMessage> for InboundSSZSnappyCodec {
         let response_code = item.as_response_code().expect("EndOfStream cannot be sent");
         dst.put_u8(u8::from(response_code));
 
+        // Responses are tagged with the digest of the fork they belong to, which may predate the
+        // current one for historical blocks
+        let slot = match &item {
+            RespMessage::Response(message) => message.slot(),
+            _ => None,
+        };
+
         let bytes = match item {
             RespMessage::Response(messages) => messages.as_ssz_bytes(),
             RespMessage::Error(req_resp_error) => {
@@ -135,7 +153,16 @@ impl Encoder<RespMessage> for InboundSSZSnappyCodec {
         }
 
         if self.protocol.protocol.has_context_bytes() && response_code == ResponseCode::Success {
-            dst.extend(network_spec().fork_digest(genesis_validators_root()));
+            let slot = slot.ok_or_else(|| {
+                ReqRespError::Anyhow(anyhow::anyhow!(
+                    "Response on {:?} has no slot for its context bytes",
+                    self.protocol.protocol
+                ))
+            })?;
+            dst.extend(
+                network_spec()
+                    .fork_digest_at_epoch(compute_epoch_at_slot(slot), genesis_validators_root()),
+            );
         }
 
         Uvi::<usize>::default().encode(bytes.len(), dst)?;
@@ -154,10 +181,23 @@ impl Decoder for InboundSSZSnappyCodec {
     type Error = ReqRespError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        if self.protocol.protocol == SupportedProtocol::GetMetaDataV2 {
-            return Ok(Some(RequestMessage::MetaData(
-                GetMetaDataV2::default().into(),
-            )));
+        match self.protocol.protocol {
+            SupportedProtocol::GetMetaDataV2 => {
+                return Ok(Some(RequestMessage::MetaData(
+                    GetMetaDataV2::default().into(),
+                )));
+            }
+            SupportedProtocol::LightClientFinalityUpdateV1 => {
+                return Ok(Some(RequestMessage::LightClientFinalityUpdate(
+                    EmptyRequest,
+                )));
+            }
+            SupportedProtocol::LightClientOptimisticUpdateV1 => {
+                return Ok(Some(RequestMessage::LightClientOptimisticUpdate(
+                    EmptyRequest,
+                )));
+            }
+            _ => {}
         }
 
         let length = match Uvi::<usize>::default().decode(src)? {
@@ -204,9 +244,26 @@ impl Decoder for InboundSSZSnappyCodec {
                                 .map_err(ReqRespError::from)?,
                         )))
                     }
-                    SupportedProtocol::GetMetaDataV2 => Err(ReqRespError::InvalidData(
-                        "GetMetaDataV2 is already handled above".to_string(),
-                    )),
+                    SupportedProtocol::LightClientBootstrapV1 => {
+                        Ok(Some(RequestMessage::LightClientBootstrap(
+                            LightClientBootstrapV1Request::from_ssz_bytes(&buf)
+                                .map_err(ReqRespError::from)?,
+                        )))
+                    }
+                    SupportedProtocol::LightClientUpdatesByRangeV1 => {
+                        Ok(Some(RequestMessage::LightClientUpdatesByRange(
+                            LightClientUpdatesByRangeV1Request::from_ssz_bytes(&buf)
+                                .map_err(ReqRespError::from)?,
+                        )))
+                    }
+                    SupportedProtocol::GetMetaDataV2
+                    | SupportedProtocol::LightClientFinalityUpdateV1
+                    | SupportedProtocol::LightClientOptimisticUpdateV1 => {
+                        Err(ReqRespError::InvalidData(format!(
+                            "{:?} has no request body and is already handled above",
+                            self.protocol.protocol
+                        )))
+                    }
                 }
             }
             Err(err) => Err(ReqRespError::from(err)),
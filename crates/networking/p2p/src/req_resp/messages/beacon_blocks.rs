@@ -1,5 +1,4 @@
 use alloy_primitives::B256;
-use ream_consensus::electra::beacon_block::SignedBeaconBlock;
 use ssz_derive::{Decode, Encode};
 use ssz_types::{VariableList, typenum::U1024};
 
@@ -26,9 +25,3 @@ impl BeaconBlocksByRangeV2Request {
 pub struct BeaconBlocksByRootV2Request {
     pub inner: VariableList<B256, U1024>,
 }
-
-#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
-#[ssz(struct_behaviour = "transparent")]
-pub struct BeaconBlocksResponse {
-    pub inner: VariableList<SignedBeaconBlock, U1024>,
-}
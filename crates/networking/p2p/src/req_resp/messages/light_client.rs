@@ -0,0 +1,60 @@
+use alloy_primitives::B256;
+use ssz::{Decode, DecodeError, Encode};
+use ssz_derive::{Decode, Encode};
+
+/// https://ethereum.github.io/consensus-specs/specs/altair/light-client/p2p-interface/#lightclientupdatesbyrange
+pub const MAX_REQUEST_LIGHT_CLIENT_UPDATES: u64 = 128;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+#[ssz(struct_behaviour = "transparent")]
+pub struct LightClientBootstrapV1Request {
+    pub block_root: B256,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct LightClientUpdatesByRangeV1Request {
+    pub start_period: u64,
+    pub count: u64,
+}
+
+/// Body of requests which carry no data, like `GetLightClientFinalityUpdate` and
+/// `GetLightClientOptimisticUpdate`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EmptyRequest;
+
+impl Encode for EmptyRequest {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        0
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        0
+    }
+
+    fn ssz_append(&self, _buf: &mut Vec<u8>) {}
+}
+
+impl Decode for EmptyRequest {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        0
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.is_empty() {
+            Ok(Self)
+        } else {
+            Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: 0,
+            })
+        }
+    }
+}
@@ -1,24 +1,31 @@
 pub mod beacon_blocks;
 pub mod blob_sidecars;
 pub mod goodbye;
+pub mod light_client;
 pub mod meta_data;
 pub mod ping;
 pub mod status;
 
 use std::sync::Arc;
 
-use beacon_blocks::{
-    BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request, BeaconBlocksResponse,
-};
-use blob_sidecars::{
-    BlobSidecarsByRangeV1Request, BlobSidecarsByRootV1Request, BlobSidecarsResponse,
-};
+use beacon_blocks::{BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request};
+use blob_sidecars::{BlobSidecarsByRangeV1Request, BlobSidecarsByRootV1Request};
 use goodbye::Goodbye;
+use light_client::{
+    EmptyRequest, LightClientBootstrapV1Request, LightClientUpdatesByRangeV1Request,
+};
 use meta_data::GetMetaDataV2;
 use ping::Ping;
+use ream_consensus::{blob_sidecar::BlobSidecar, electra::beacon_block::SignedBeaconBlock};
+use ream_light_client::{
+    bootstrap::LightClientBootstrap, finality_update::LightClientFinalityUpdate,
+    optimistic_update::LightClientOptimisticUpdate, update::LightClientUpdate,
+};
 use ssz_derive::{Decode, Encode};
 use status::Status;
 
+use super::protocol_id::SupportedProtocol;
+
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 #[ssz(enum_behaviour = "transparent")]
 pub enum RequestMessage {
@@ -30,6 +37,35 @@ pub enum RequestMessage {
     BeaconBlocksByRoot(BeaconBlocksByRootV2Request),
     BlobSidecarsByRange(BlobSidecarsByRangeV1Request),
     BlobSidecarsByRoot(BlobSidecarsByRootV1Request),
+    LightClientBootstrap(LightClientBootstrapV1Request),
+    LightClientUpdatesByRange(LightClientUpdatesByRangeV1Request),
+    LightClientFinalityUpdate(EmptyRequest),
+    LightClientOptimisticUpdate(EmptyRequest),
+}
+
+impl RequestMessage {
+    pub fn protocol(&self) -> SupportedProtocol {
+        match self {
+            RequestMessage::MetaData(_) => SupportedProtocol::GetMetaDataV2,
+            RequestMessage::Goodbye(_) => SupportedProtocol::GoodbyeV1,
+            RequestMessage::Status(_) => SupportedProtocol::StatusV1,
+            RequestMessage::Ping(_) => SupportedProtocol::PingV1,
+            RequestMessage::BeaconBlocksByRange(_) => SupportedProtocol::BeaconBlocksByRangeV2,
+            RequestMessage::BeaconBlocksByRoot(_) => SupportedProtocol::BeaconBlocksByRootV2,
+            RequestMessage::BlobSidecarsByRange(_) => SupportedProtocol::BlobSidecarsByRangeV1,
+            RequestMessage::BlobSidecarsByRoot(_) => SupportedProtocol::BlobSidecarsByRootV1,
+            RequestMessage::LightClientBootstrap(_) => SupportedProtocol::LightClientBootstrapV1,
+            RequestMessage::LightClientUpdatesByRange(_) => {
+                SupportedProtocol::LightClientUpdatesByRangeV1
+            }
+            RequestMessage::LightClientFinalityUpdate(_) => {
+                SupportedProtocol::LightClientFinalityUpdateV1
+            }
+            RequestMessage::LightClientOptimisticUpdate(_) => {
+                SupportedProtocol::LightClientOptimisticUpdateV1
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
@@ -39,8 +75,41 @@ pub enum ResponseMessage {
     Goodbye(Goodbye),
     Status(Status),
     Ping(Ping),
-    BeaconBlocksByRange(BeaconBlocksResponse),
-    BeaconBlocksByRoot(BeaconBlocksResponse),
-    BlobSidecarsByRange(BlobSidecarsResponse),
-    BlobSidecarsByRoot(BlobSidecarsResponse),
+    BeaconBlocksByRange(Arc<SignedBeaconBlock>),
+    BeaconBlocksByRoot(Arc<SignedBeaconBlock>),
+    BlobSidecarsByRange(Arc<BlobSidecar>),
+    BlobSidecarsByRoot(Arc<BlobSidecar>),
+    LightClientBootstrap(Arc<LightClientBootstrap>),
+    LightClientUpdatesByRange(Arc<LightClientUpdate>),
+    LightClientFinalityUpdate(Arc<LightClientFinalityUpdate>),
+    LightClientOptimisticUpdate(Arc<LightClientOptimisticUpdate>),
+}
+
+impl ResponseMessage {
+    /// The slot whose fork determines the context bytes of the response, for the responses which
+    /// carry them.
+    pub fn slot(&self) -> Option<u64> {
+        match self {
+            ResponseMessage::MetaData(_)
+            | ResponseMessage::Goodbye(_)
+            | ResponseMessage::Status(_)
+            | ResponseMessage::Ping(_) => None,
+            ResponseMessage::BeaconBlocksByRange(block)
+            | ResponseMessage::BeaconBlocksByRoot(block) => Some(block.message.slot),
+            ResponseMessage::BlobSidecarsByRange(blob_sidecar)
+            | ResponseMessage::BlobSidecarsByRoot(blob_sidecar) => {
+                Some(blob_sidecar.signed_block_header.message.slot)
+            }
+            ResponseMessage::LightClientBootstrap(bootstrap) => Some(bootstrap.header.beacon.slot),
+            ResponseMessage::LightClientUpdatesByRange(update) => {
+                Some(update.attested_header.beacon.slot)
+            }
+            ResponseMessage::LightClientFinalityUpdate(update) => {
+                Some(update.attested_header.beacon.slot)
+            }
+            ResponseMessage::LightClientOptimisticUpdate(update) => {
+                Some(update.attested_header.beacon.slot)
+            }
+        }
+    }
 }
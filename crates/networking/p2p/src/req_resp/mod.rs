@@ -5,8 +5,15 @@ pub mod inbound_protocol;
 pub mod messages;
 pub mod outbound_protocol;
 pub mod protocol_id;
-
-use std::task::{Context, Poll};
+pub mod rate_limiter;
+
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
 
 use error::ReqRespError;
 use handler::{HandlerEvent, ReqRespConnectionHandler, ReqRespMessageReceived, RespMessage};
@@ -20,6 +27,8 @@ use libp2p::{
     },
 };
 use messages::RequestMessage;
+use rate_limiter::{RateLimitedError, RateLimiter, RateLimiterConfig};
+use tokio::time::Sleep;
 use tracing::{debug, info};
 
 /// Maximum number of concurrent requests per protocol ID that a client may issue.
@@ -29,6 +38,8 @@ pub const MAX_CONCURRENT_REQUESTS: usize = 2;
 pub struct ReqRespMessage {
     pub peer_id: PeerId,
     pub connection_id: ConnectionId,
+    /// Our request an error belongs to, if it failed one of them
+    pub request_id: Option<u64>,
     pub message: Result<ReqRespMessageReceived, ReqRespError>,
 }
 
@@ -47,14 +58,45 @@ pub enum ConnectionRequest {
 
 pub struct ReqResp {
     pub events: Vec<ToSwarm<ReqRespMessage, ConnectionRequest>>,
+    /// Keeps our requests within the quotas of other clients, so they don't rate limit us.
+    self_rate_limiter: RateLimiter,
+    /// Requests held back by the self rate limiter, in the order they were made.
+    delayed_requests: VecDeque<(PeerId, u64, RequestMessage)>,
+    next_retry: Option<Pin<Box<Sleep>>>,
 }
 
 impl ReqResp {
     pub fn new() -> Self {
-        ReqResp { events: vec![] }
+        ReqResp {
+            events: vec![],
+            self_rate_limiter: RateLimiter::new(RateLimiterConfig::default()),
+            delayed_requests: VecDeque::new(),
+            next_retry: None,
+        }
     }
 
     pub fn send_request(&mut self, peer_id: PeerId, request_id: u64, message: RequestMessage) {
+        match self
+            .self_rate_limiter
+            .allows(peer_id, &message, Instant::now())
+        {
+            Ok(()) => {}
+            Err(RateLimitedError::TooLarge) => {
+                debug!("REQRESP: Request to {peer_id} exceeds its quota: {message:?}");
+            }
+            Err(RateLimitedError::Limited { wait }) => {
+                debug!("REQRESP: Delaying request to {peer_id} by {wait:?}");
+                self.delayed_requests
+                    .push_back((peer_id, request_id, message));
+                if self.next_retry.as_ref().is_none_or(|next_retry| {
+                    next_retry.deadline() > tokio::time::Instant::now() + wait
+                }) {
+                    self.next_retry = Some(Box::pin(tokio::time::sleep(wait)));
+                }
+                return;
+            }
+        }
+
         self.events.push(ToSwarm::NotifyHandler {
             peer_id,
             handler: NotifyHandler::Any,
@@ -65,6 +107,11 @@ impl ReqResp {
         });
     }
 
+    /// Drops the self rate limiter state of peers we haven't sent requests to recently.
+    pub fn prune_rate_limiter(&mut self) {
+        self.self_rate_limiter.prune(Instant::now());
+    }
+
     pub fn send_response(
         &mut self,
         peer_id: PeerId,
@@ -140,13 +187,23 @@ impl NetworkBehaviour for ReqResp {
             HandlerEvent::Ok(message) => self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
                 peer_id,
                 connection_id,
+                request_id: None,
                 message: Ok(message),
             })),
             HandlerEvent::Err(err) => self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
                 peer_id,
                 connection_id,
+                request_id: None,
                 message: Err(err),
             })),
+            HandlerEvent::OutboundErr { request_id, err } => {
+                self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
+                    peer_id,
+                    connection_id,
+                    request_id: Some(request_id),
+                    message: Err(err),
+                }))
+            }
             HandlerEvent::Close => self.events.push(ToSwarm::CloseConnection {
                 peer_id,
                 connection: CloseConnection::All,
@@ -156,8 +213,19 @@ impl NetworkBehaviour for ReqResp {
 
     fn poll(
         &mut self,
-        _cx: &mut Context<'_>,
+        cx: &mut Context<'_>,
     ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        // Requests delayed again arm a new sleep, which has to be polled to register our waker
+        while let Some(next_retry) = &mut self.next_retry {
+            if next_retry.as_mut().poll(cx).is_pending() {
+                break;
+            }
+            self.next_retry = None;
+            for (peer_id, request_id, message) in std::mem::take(&mut self.delayed_requests) {
+                self.send_request(peer_id, request_id, message);
+            }
+        }
+
         debug!("REQRESP: Polling events {:?}", self.events);
         if !self.events.is_empty() {
             return Poll::Ready(self.events.remove(0));
@@ -2,6 +2,7 @@ use std::{
     future::Future,
     io::{Cursor, Read, Write},
     pin::Pin,
+    sync::Arc,
 };
 
 use alloy_primitives::aliases::B32;
@@ -11,7 +12,14 @@ use futures::{
     prelude::{AsyncRead, AsyncWrite},
 };
 use libp2p::{OutboundUpgrade, bytes::Buf, core::UpgradeInfo};
-use ream_consensus::constants::genesis_validators_root;
+use ream_consensus::{
+    blob_sidecar::BlobSidecar, constants::genesis_validators_root,
+    electra::beacon_block::SignedBeaconBlock,
+};
+use ream_light_client::{
+    bootstrap::LightClientBootstrap, finality_update::LightClientFinalityUpdate,
+    optimistic_update::LightClientOptimisticUpdate, update::LightClientUpdate,
+};
 use ream_network_spec::networks::network_spec;
 use snap::{read::FrameDecoder, write::FrameEncoder};
 use ssz::{Decode, Encode};
@@ -30,12 +38,7 @@ use super::{
     messages::{RequestMessage, meta_data::GetMetaDataV2, ping::Ping, status::Status},
     protocol_id::{ProtocolId, SupportedProtocol},
 };
-use crate::{
-    req_resp::messages::{
-        ResponseMessage, beacon_blocks::BeaconBlocksResponse, blob_sidecars::BlobSidecarsResponse,
-    },
-    utils::max_message_size,
-};
+use crate::{req_resp::messages::ResponseMessage, utils::max_message_size};
 
 #[derive(Debug, Clone)]
 pub struct OutboundReqRespProtocol {
@@ -95,7 +98,9 @@ impl Encoder<RequestMessage> for OutboundSSZSnappyCodec {
 
     fn encode(&mut self, item: RequestMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
         let bytes = match item {
-            RequestMessage::MetaData(_) => return Ok(()),
+            RequestMessage::MetaData(_)
+            | RequestMessage::LightClientFinalityUpdate(_)
+            | RequestMessage::LightClientOptimisticUpdate(_) => return Ok(()),
             message => message.as_ssz_bytes(),
         };
 
@@ -145,9 +150,9 @@ impl Decoder for OutboundSSZSnappyCodec {
         }
 
         if let Some(context_bytes) = self.context_bytes {
-            if context_bytes != network_spec().fork_digest(genesis_validators_root()) {
+            if !network_spec().is_known_fork_digest(context_bytes, genesis_validators_root()) {
                 return Ok(Some(RespMessage::Error(ReqRespError::InvalidData(
-                    "Invalid context bytes, we only support Electra".to_string(),
+                    format!("Unknown fork digest in context bytes: {context_bytes}"),
                 ))));
             }
         }
@@ -195,29 +200,57 @@ impl Decoder for OutboundSSZSnappyCodec {
                             ))))
                         }
                         SupportedProtocol::BeaconBlocksByRangeV2 => Ok(Some(
-                            RespMessage::Response(ResponseMessage::BeaconBlocksByRange(
-                                BeaconBlocksResponse::from_ssz_bytes(&buf)
+                            RespMessage::Response(ResponseMessage::BeaconBlocksByRange(Arc::new(
+                                SignedBeaconBlock::from_ssz_bytes(&buf)
                                     .map_err(ReqRespError::from)?,
-                            )),
+                            ))),
                         )),
                         SupportedProtocol::BeaconBlocksByRootV2 => Ok(Some(RespMessage::Response(
-                            ResponseMessage::BeaconBlocksByRoot(
-                                BeaconBlocksResponse::from_ssz_bytes(&buf)
+                            ResponseMessage::BeaconBlocksByRoot(Arc::new(
+                                SignedBeaconBlock::from_ssz_bytes(&buf)
                                     .map_err(ReqRespError::from)?,
-                            ),
+                            )),
                         ))),
                         SupportedProtocol::BlobSidecarsByRangeV1 => Ok(Some(
-                            RespMessage::Response(ResponseMessage::BlobSidecarsByRange(
-                                BlobSidecarsResponse::from_ssz_bytes(&buf)
-                                    .map_err(ReqRespError::from)?,
-                            )),
+                            RespMessage::Response(ResponseMessage::BlobSidecarsByRange(Arc::new(
+                                BlobSidecar::from_ssz_bytes(&buf).map_err(ReqRespError::from)?,
+                            ))),
                         )),
                         SupportedProtocol::BlobSidecarsByRootV1 => Ok(Some(RespMessage::Response(
-                            ResponseMessage::BlobSidecarsByRoot(
-                                BlobSidecarsResponse::from_ssz_bytes(&buf)
-                                    .map_err(ReqRespError::from)?,
-                            ),
+                            ResponseMessage::BlobSidecarsByRoot(Arc::new(
+                                BlobSidecar::from_ssz_bytes(&buf).map_err(ReqRespError::from)?,
+                            )),
                         ))),
+                        SupportedProtocol::LightClientBootstrapV1 => Ok(Some(
+                            RespMessage::Response(ResponseMessage::LightClientBootstrap(Arc::new(
+                                LightClientBootstrap::from_ssz_bytes(&buf)
+                                    .map_err(ReqRespError::from)?,
+                            ))),
+                        )),
+                        SupportedProtocol::LightClientUpdatesByRangeV1 => {
+                            Ok(Some(RespMessage::Response(
+                                ResponseMessage::LightClientUpdatesByRange(Arc::new(
+                                    LightClientUpdate::from_ssz_bytes(&buf)
+                                        .map_err(ReqRespError::from)?,
+                                )),
+                            )))
+                        }
+                        SupportedProtocol::LightClientFinalityUpdateV1 => {
+                            Ok(Some(RespMessage::Response(
+                                ResponseMessage::LightClientFinalityUpdate(Arc::new(
+                                    LightClientFinalityUpdate::from_ssz_bytes(&buf)
+                                        .map_err(ReqRespError::from)?,
+                                )),
+                            )))
+                        }
+                        SupportedProtocol::LightClientOptimisticUpdateV1 => {
+                            Ok(Some(RespMessage::Response(
+                                ResponseMessage::LightClientOptimisticUpdate(Arc::new(
+                                    LightClientOptimisticUpdate::from_ssz_bytes(&buf)
+                                        .map_err(ReqRespError::from)?,
+                                )),
+                            )))
+                        }
                     }
                 } else {
                     Ok(Some(RespMessage::Error(
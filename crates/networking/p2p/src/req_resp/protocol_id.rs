@@ -29,7 +29,7 @@ impl AsRef<str> for ProtocolId {
 }
 
 /// All valid protocol name and version combinations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SupportedProtocol {
     BeaconBlocksByRangeV2,
     BeaconBlocksByRootV2,
@@ -39,6 +39,10 @@ pub enum SupportedProtocol {
     GoodbyeV1,
     PingV1,
     StatusV1,
+    LightClientBootstrapV1,
+    LightClientUpdatesByRangeV1,
+    LightClientFinalityUpdateV1,
+    LightClientOptimisticUpdateV1,
 }
 
 impl SupportedProtocol {
@@ -52,6 +56,10 @@ impl SupportedProtocol {
             SupportedProtocol::GoodbyeV1 => "goodbye",
             SupportedProtocol::PingV1 => "ping",
             SupportedProtocol::StatusV1 => "status",
+            SupportedProtocol::LightClientBootstrapV1 => "light_client_bootstrap",
+            SupportedProtocol::LightClientUpdatesByRangeV1 => "light_client_updates_by_range",
+            SupportedProtocol::LightClientFinalityUpdateV1 => "light_client_finality_update",
+            SupportedProtocol::LightClientOptimisticUpdateV1 => "light_client_optimistic_update",
         }
     }
 
@@ -65,6 +73,10 @@ impl SupportedProtocol {
             SupportedProtocol::GoodbyeV1 => "1",
             SupportedProtocol::PingV1 => "1",
             SupportedProtocol::StatusV1 => "1",
+            SupportedProtocol::LightClientBootstrapV1 => "1",
+            SupportedProtocol::LightClientUpdatesByRangeV1 => "1",
+            SupportedProtocol::LightClientFinalityUpdateV1 => "1",
+            SupportedProtocol::LightClientOptimisticUpdateV1 => "1",
         }
     }
 
@@ -78,6 +90,10 @@ impl SupportedProtocol {
             SupportedProtocol::BeaconBlocksByRootV2,
             SupportedProtocol::BlobSidecarsByRangeV1,
             SupportedProtocol::BlobSidecarsByRootV1,
+            SupportedProtocol::LightClientBootstrapV1,
+            SupportedProtocol::LightClientUpdatesByRangeV1,
+            SupportedProtocol::LightClientFinalityUpdateV1,
+            SupportedProtocol::LightClientOptimisticUpdateV1,
         ]
         .into_iter()
         .map(ProtocolId::new)
@@ -94,6 +110,10 @@ impl SupportedProtocol {
             SupportedProtocol::BeaconBlocksByRootV2 => true,
             SupportedProtocol::BlobSidecarsByRangeV1 => true,
             SupportedProtocol::BlobSidecarsByRootV1 => true,
+            SupportedProtocol::LightClientBootstrapV1 => true,
+            SupportedProtocol::LightClientUpdatesByRangeV1 => true,
+            SupportedProtocol::LightClientFinalityUpdateV1 => true,
+            SupportedProtocol::LightClientOptimisticUpdateV1 => true,
         }
     }
 }
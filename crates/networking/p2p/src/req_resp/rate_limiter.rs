@@ -0,0 +1,217 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use libp2p::PeerId;
+use ream_network_spec::networks::network_spec;
+
+use super::{messages::RequestMessage, protocol_id::SupportedProtocol};
+
+/// Allows `max_tokens` requested items per protocol, replenished evenly over
+/// `replenish_all_every`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quota {
+    pub max_tokens: u64,
+    pub replenish_all_every: Duration,
+}
+
+impl Quota {
+    pub const fn new(max_tokens: u64, seconds: u64) -> Self {
+        Self {
+            max_tokens,
+            replenish_all_every: Duration::from_secs(seconds),
+        }
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        self.max_tokens as f64 / self.replenish_all_every.as_secs_f64()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimiterConfig {
+    pub ping: Quota,
+    pub meta_data: Quota,
+    pub status: Quota,
+    pub goodbye: Quota,
+    pub beacon_blocks_by_range: Quota,
+    pub beacon_blocks_by_root: Quota,
+    pub blob_sidecars_by_range: Quota,
+    pub blob_sidecars_by_root: Quota,
+    pub light_client_bootstrap: Quota,
+    pub light_client_updates_by_range: Quota,
+    pub light_client_finality_update: Quota,
+    pub light_client_optimistic_update: Quota,
+}
+
+impl Default for RateLimiterConfig {
+    /// Quotas in line with the ones other clients enforce, so the same config can be used to
+    /// limit our own requests.
+    fn default() -> Self {
+        Self {
+            ping: Quota::new(2, 10),
+            meta_data: Quota::new(2, 5),
+            status: Quota::new(5, 15),
+            goodbye: Quota::new(1, 10),
+            beacon_blocks_by_range: Quota::new(1024, 10),
+            beacon_blocks_by_root: Quota::new(128, 10),
+            // MAX_REQUEST_BLOB_SIDECARS_ELECTRA
+            blob_sidecars_by_range: Quota::new(1152, 10),
+            blob_sidecars_by_root: Quota::new(1152, 10),
+            light_client_bootstrap: Quota::new(1, 10),
+            light_client_updates_by_range: Quota::new(128, 10),
+            light_client_finality_update: Quota::new(1, 10),
+            light_client_optimistic_update: Quota::new(1, 10),
+        }
+    }
+}
+
+impl RateLimiterConfig {
+    pub fn quota(&self, protocol: SupportedProtocol) -> Quota {
+        match protocol {
+            SupportedProtocol::PingV1 => self.ping,
+            SupportedProtocol::GetMetaDataV2 => self.meta_data,
+            SupportedProtocol::StatusV1 => self.status,
+            SupportedProtocol::GoodbyeV1 => self.goodbye,
+            SupportedProtocol::BeaconBlocksByRangeV2 => self.beacon_blocks_by_range,
+            SupportedProtocol::BeaconBlocksByRootV2 => self.beacon_blocks_by_root,
+            SupportedProtocol::BlobSidecarsByRangeV1 => self.blob_sidecars_by_range,
+            SupportedProtocol::BlobSidecarsByRootV1 => self.blob_sidecars_by_root,
+            SupportedProtocol::LightClientBootstrapV1 => self.light_client_bootstrap,
+            SupportedProtocol::LightClientUpdatesByRangeV1 => self.light_client_updates_by_range,
+            SupportedProtocol::LightClientFinalityUpdateV1 => self.light_client_finality_update,
+            SupportedProtocol::LightClientOptimisticUpdateV1 => self.light_client_optimistic_update,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateLimitedError {
+    /// The request asks for more items than the quota ever allows.
+    TooLarge,
+    /// Not enough tokens yet, the request is allowed again after `wait`.
+    Limited { wait: Duration },
+}
+
+/// Number of tokens a request costs, which is the number of items it may return.
+pub fn request_cost(request: &RequestMessage) -> u64 {
+    let cost = match request {
+        RequestMessage::BeaconBlocksByRange(request) => request.count,
+        RequestMessage::BeaconBlocksByRoot(request) => request.inner.len() as u64,
+        RequestMessage::BlobSidecarsByRange(request) => request
+            .count
+            .saturating_mul(network_spec().max_blobs_per_block_electra),
+        RequestMessage::BlobSidecarsByRoot(request) => request.inner.len() as u64,
+        RequestMessage::LightClientUpdatesByRange(request) => request.count,
+        _ => 1,
+    };
+    cost.max(1)
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_update: Instant,
+}
+
+/// Token bucket rate limiter keyed by peer and protocol.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimiterConfig,
+    buckets: HashMap<(PeerId, SupportedProtocol), TokenBucket>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimiterConfig) -> Self {
+        Self {
+            config,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Takes the tokens for `request` from the bucket of `peer_id` if there are enough.
+    pub fn allows(
+        &mut self,
+        peer_id: PeerId,
+        request: &RequestMessage,
+        now: Instant,
+    ) -> Result<(), RateLimitedError> {
+        let protocol = request.protocol();
+        let quota = self.config.quota(protocol);
+        let cost = request_cost(request);
+        if cost > quota.max_tokens {
+            return Err(RateLimitedError::TooLarge);
+        }
+
+        let bucket = self
+            .buckets
+            .entry((peer_id, protocol))
+            .or_insert(TokenBucket {
+                tokens: quota.max_tokens as f64,
+                last_update: now,
+            });
+        let elapsed = now.saturating_duration_since(bucket.last_update);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * quota.tokens_per_second())
+            .min(quota.max_tokens as f64);
+        bucket.last_update = now;
+
+        let cost = cost as f64;
+        if bucket.tokens < cost {
+            return Err(RateLimitedError::Limited {
+                wait: Duration::from_secs_f64((cost - bucket.tokens) / quota.tokens_per_second()),
+            });
+        }
+        bucket.tokens -= cost;
+        Ok(())
+    }
+
+    /// Drops the buckets which have been replenished completely, as they are equivalent to new
+    /// ones.
+    pub fn prune(&mut self, now: Instant) {
+        let config = &self.config;
+        self.buckets.retain(|(_, protocol), bucket| {
+            now.saturating_duration_since(bucket.last_update)
+                < config.quota(*protocol).replenish_all_every
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::req_resp::messages::beacon_blocks::BeaconBlocksByRangeV2Request;
+
+    #[test]
+    fn test_rate_limiter_weights_requests_by_count() {
+        let mut rate_limiter = RateLimiter::new(RateLimiterConfig::default());
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+        let request = |count| {
+            RequestMessage::BeaconBlocksByRange(BeaconBlocksByRangeV2Request::new(0, count))
+        };
+
+        assert_eq!(
+            rate_limiter.allows(peer_id, &request(2048), now),
+            Err(RateLimitedError::TooLarge)
+        );
+        assert_eq!(rate_limiter.allows(peer_id, &request(1000), now), Ok(()));
+        assert!(matches!(
+            rate_limiter.allows(peer_id, &request(100), now),
+            Err(RateLimitedError::Limited { .. })
+        ));
+
+        // Other peers have their own bucket
+        assert_eq!(
+            rate_limiter.allows(PeerId::random(), &request(100), now),
+            Ok(())
+        );
+
+        // 1024 tokens are replenished every 10 seconds
+        let later = now + Duration::from_secs(1);
+        assert_eq!(rate_limiter.allows(peer_id, &request(100), later), Ok(()));
+
+        rate_limiter.prune(now + Duration::from_secs(20));
+        assert!(rate_limiter.buckets.is_empty());
+    }
+}
@@ -1,6 +1,11 @@
-use std::cmp::max;
+use std::{cmp::max, fs, io::ErrorKind, path::Path};
 
-use crate::constants::MAX_PAYLOAD_SIZE;
+use alloy_primitives::hex;
+use anyhow::anyhow;
+use libp2p_identity::secp256k1;
+use tracing::info;
+
+use crate::constants::{MAX_PAYLOAD_SIZE, NETWORK_KEY_FILENAME};
 
 /// Worst-case compressed length for a given payload of size n when using snappy:
 /// https://github.com/google/snappy/blob/32ded457c0b1fe78ceb8397632c416568d6714a0/snappy.cc#L218C1-L218C47
@@ -13,3 +18,86 @@ pub fn max_compressed_len(n: u64) -> u64 {
 pub fn max_message_size() -> u64 {
     max(max_compressed_len(MAX_PAYLOAD_SIZE) + 1024, 1024 * 1024)
 }
+
+/// Loads the node's secp256k1 key, so its peer id and ENR stay the same across restarts.
+///
+/// - If `key_file` is set, the key is read from it, either as 32 raw bytes or hex encoded.
+/// - Otherwise the key stored in `data_dir` is used, generating and storing a new one on first run.
+pub fn load_private_key(
+    data_dir: &Path,
+    key_file: Option<&Path>,
+) -> anyhow::Result<secp256k1::Keypair> {
+    if let Some(key_file) = key_file {
+        let bytes = fs::read(key_file)
+            .map_err(|err| anyhow!("Failed to read key file {key_file:?}: {err:?}"))?;
+        return decode_private_key(&bytes)
+            .map_err(|err| anyhow!("Invalid key file {key_file:?}: {err:?}"));
+    }
+
+    let key_path = data_dir.join(NETWORK_KEY_FILENAME);
+    match fs::read(&key_path) {
+        Ok(bytes) => {
+            return decode_private_key(&bytes)
+                .map_err(|err| anyhow!("Invalid key file {key_path:?}: {err:?}"));
+        }
+        // Only a missing key is replaced, any other error would otherwise change our identity
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => return Err(anyhow!("Failed to read key file {key_path:?}: {err:?}")),
+    }
+
+    let keypair = secp256k1::Keypair::generate();
+    fs::create_dir_all(data_dir)?;
+    fs::write(&key_path, keypair.secret().to_bytes())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&key_path, fs::Permissions::from_mode(0o600))?;
+    }
+    info!("Generated new network key at {key_path:?}");
+
+    Ok(keypair)
+}
+
+fn decode_private_key(bytes: &[u8]) -> anyhow::Result<secp256k1::Keypair> {
+    let mut secret = if bytes.len() == 32 {
+        bytes.to_vec()
+    } else {
+        hex::decode(String::from_utf8(bytes.to_vec())?.trim())?
+    };
+
+    let secret = secp256k1::SecretKey::try_from_bytes(&mut secret)?;
+    Ok(secp256k1::Keypair::from(secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_private_key_persists_generated_key() -> anyhow::Result<()> {
+        let data_dir = tempfile::tempdir()?;
+
+        let keypair = load_private_key(data_dir.path(), None)?;
+        let reloaded = load_private_key(data_dir.path(), None)?;
+        assert_eq!(keypair.public(), reloaded.public());
+
+        let key_file = data_dir.path().join("hex_key");
+        fs::write(&key_file, hex::encode_prefixed(keypair.secret().to_bytes()))?;
+        let from_key_file = load_private_key(data_dir.path(), Some(&key_file))?;
+        assert_eq!(keypair.public(), from_key_file.public());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_private_key_keeps_unreadable_key() -> anyhow::Result<()> {
+        let data_dir = tempfile::tempdir()?;
+        // Reading a directory fails with an error other than `NotFound`
+        fs::create_dir(data_dir.path().join(NETWORK_KEY_FILENAME))?;
+
+        assert!(load_private_key(data_dir.path(), None).is_err());
+        assert!(data_dir.path().join(NETWORK_KEY_FILENAME).is_dir());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,376 @@
+use std::{collections::HashMap, fmt, str::FromStr, sync::Arc, time::Duration};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use libp2p::PeerId;
+use parking_lot::RwLock;
+use ream_beacon_chain::slot_clock::{SlotClock, SystemClock};
+use ream_consensus::{electra::beacon_block::SignedBeaconBlock, misc::compute_start_slot_at_epoch};
+use ream_network_spec::networks::network_spec;
+use ream_p2p::{channel::P2PMessages, req_resp::messages::status::Status};
+use ream_storage::{
+    db::ReamDB,
+    tables::{Field, Table},
+};
+use tokio::{sync::mpsc::UnboundedSender, time::sleep};
+use tracing::{info, warn};
+
+use crate::{block_range::BATCH_SIZE, utils::request_block_range};
+
+/// How long to wait before retrying when no peer is available or a batch failed.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// How many times a range is requested before it is taken as empty without another peer
+/// confirming it, e.g. when we only have a single peer.
+const MAX_EMPTY_RANGE_ATTEMPTS: u32 = 3;
+
+/// How far back the backfill goes before it stops.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackfillTarget {
+    /// Download every block down to genesis.
+    #[default]
+    Genesis,
+    /// Stop at the weak subjectivity boundary, i.e. `MIN_EPOCHS_FOR_BLOCK_REQUESTS` before the
+    /// current slot.
+    WeakSubjectivity,
+    /// Stop at the blob retention boundary, i.e. `MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS` before
+    /// the current slot.
+    BlobRetention,
+}
+
+impl BackfillTarget {
+    /// Returns the lowest slot the backfill needs to reach at `current_slot`. It doesn't depend on
+    /// how far the backfill got, so restarts don't move it further back.
+    pub fn stop_slot(&self, current_slot: u64) -> u64 {
+        let epochs = match self {
+            BackfillTarget::Genesis => return 0,
+            BackfillTarget::WeakSubjectivity => network_spec().min_epochs_for_block_requests,
+            BackfillTarget::BlobRetention => network_spec().min_epochs_for_blob_sidecars_requests,
+        };
+
+        current_slot.saturating_sub(compute_start_slot_at_epoch(epochs))
+    }
+}
+
+impl FromStr for BackfillTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "genesis" => Ok(BackfillTarget::Genesis),
+            "weak-subjectivity" => Ok(BackfillTarget::WeakSubjectivity),
+            "blob-retention" => Ok(BackfillTarget::BlobRetention),
+            _ => Err(format!(
+                "Invalid backfill target: {s}, expected genesis, weak-subjectivity or blob-retention"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BackfillTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackfillTarget::Genesis => write!(f, "genesis"),
+            BackfillTarget::WeakSubjectivity => write!(f, "weak-subjectivity"),
+            BackfillTarget::BlobRetention => write!(f, "blob-retention"),
+        }
+    }
+}
+
+/// Downloads historical blocks backwards from the oldest block in the database, verifying only
+/// the parent root hash chain.
+///
+/// Progress is the oldest block in the database, so an interrupted backfill resumes from where it
+/// stopped after a restart.
+pub struct BackfillSyncer {
+    pub db: ReamDB,
+    pub p2p_sender: UnboundedSender<P2PMessages>,
+    pub peer_statuses: Arc<RwLock<HashMap<PeerId, Status>>>,
+    pub target: BackfillTarget,
+}
+
+impl BackfillSyncer {
+    pub fn new(
+        db: ReamDB,
+        p2p_sender: UnboundedSender<P2PMessages>,
+        peer_statuses: Arc<RwLock<HashMap<PeerId, Status>>>,
+        target: BackfillTarget,
+    ) -> Self {
+        Self {
+            db,
+            p2p_sender,
+            peer_statuses,
+            target,
+        }
+    }
+
+    pub async fn start(self) {
+        let (anchor_slot, mut expected_root) = match self.oldest_block() {
+            Ok(oldest_block) => oldest_block,
+            Err(err) => {
+                warn!("Unable to start backfill: {err:?}");
+                return;
+            }
+        };
+        let current_slot = match self.db.genesis_time_provider().get() {
+            Ok(genesis_time) => SlotClock::new(genesis_time, SystemClock)
+                .now()
+                .map_or(0, |now| now.slot),
+            Err(err) => {
+                warn!("Unable to start backfill: {err:?}");
+                return;
+            }
+        };
+        let stop_slot = self.target.stop_slot(current_slot);
+        let mut end_slot = anchor_slot;
+
+        info!("Starting backfill from slot {anchor_slot} down to slot {stop_slot}");
+
+        let mut peer_index = 0;
+        // The peer which returned no blocks for the current range, it is only skipped once
+        // another peer confirms it is empty or after `MAX_EMPTY_RANGE_ATTEMPTS` empty responses
+        let mut empty_range_peer = None;
+        let mut empty_range_attempts = 0;
+        while end_slot > stop_slot && expected_root != B256::ZERO {
+            let peers: Vec<PeerId> = self.peer_statuses.read().keys().copied().collect();
+            if peers.is_empty() {
+                sleep(RETRY_DELAY).await;
+                continue;
+            }
+            peer_index = (peer_index + 1) % peers.len();
+            let peer_id = peers[peer_index];
+
+            let start_slot = end_slot.saturating_sub(BATCH_SIZE).max(stop_slot);
+            let result = match request_block_range(
+                &self.p2p_sender,
+                peer_id,
+                start_slot,
+                end_slot - start_slot,
+            )
+            .await
+            {
+                Ok(blocks) if blocks.is_empty() => {
+                    empty_range_attempts += 1;
+                    match empty_range_peer {
+                        Some(empty_peer_id) if empty_peer_id != peer_id => Ok(expected_root),
+                        _ if empty_range_attempts >= MAX_EMPTY_RANGE_ATTEMPTS => Ok(expected_root),
+                        _ => {
+                            empty_range_peer = Some(peer_id);
+                            if peers.len() > 1 {
+                                // Ask another peer right away
+                                continue;
+                            }
+                            Err(anyhow!("No blocks returned"))
+                        }
+                    }
+                }
+                Ok(blocks) => self.import_batch(blocks, start_slot, end_slot, expected_root),
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(parent_root) => {
+                    expected_root = parent_root;
+                    end_slot = start_slot;
+                    empty_range_peer = None;
+                    empty_range_attempts = 0;
+                    info!(
+                        "Backfill progress: slot {end_slot}, {} slots remaining",
+                        end_slot - stop_slot
+                    );
+                }
+                Err(err) => {
+                    warn!(
+                        "Failed to backfill slots {start_slot}..{end_slot} from {peer_id}: {err:?}"
+                    );
+                    sleep(RETRY_DELAY).await;
+                }
+            }
+        }
+
+        info!("Backfill completed at slot {end_slot}");
+    }
+
+    /// Returns the slot and parent root of the oldest block we have.
+    fn oldest_block(&self) -> anyhow::Result<(u64, B256)> {
+        let oldest_slot = self
+            .db
+            .slot_index_provider()
+            .get_oldest_slot()?
+            .ok_or_else(|| anyhow!("No blocks in the database to backfill from"))?;
+        let block_root = self
+            .db
+            .slot_index_provider()
+            .get(oldest_slot)?
+            .ok_or_else(|| anyhow!("Failed to find block root at slot {oldest_slot}"))?;
+        let block = self
+            .db
+            .beacon_block_provider()
+            .get(block_root)?
+            .ok_or_else(|| anyhow!("Failed to find block: {block_root}"))?;
+
+        Ok((oldest_slot, block.message.parent_root))
+    }
+
+    /// Verifies the batch hash chains into `expected_root` and stores it. Returns the parent root
+    /// the next (older) batch has to link to.
+    fn import_batch(
+        &self,
+        blocks: Vec<Arc<SignedBeaconBlock>>,
+        start_slot: u64,
+        end_slot: u64,
+        mut expected_root: B256,
+    ) -> anyhow::Result<B256> {
+        let mut previous_slot = end_slot;
+        for block in blocks.iter().rev() {
+            let slot = block.message.slot;
+            ensure!(
+                slot >= start_slot && slot < previous_slot,
+                "Block slot {slot} out of order or outside of {start_slot}..{end_slot}"
+            );
+
+            let block_root = block.message.block_root();
+            ensure!(
+                block_root == expected_root,
+                "Block at slot {slot} has root {block_root}, expected {expected_root}"
+            );
+
+            expected_root = block.message.parent_root;
+            previous_slot = slot;
+        }
+
+        // Resuming starts from the oldest stored block, so the batch is stored all at once to
+        // not leave a gap above it
+        let batch = self.db.write_batch();
+        for block in blocks {
+            batch
+                .db()
+                .beacon_block_provider()
+                .insert(block.message.block_root(), Arc::unwrap_or_clone(block))?;
+        }
+        batch.commit()?;
+
+        Ok(expected_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus::constants::SLOTS_PER_EPOCH;
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use ream_storage::test_utils::{import_test_chain, import_test_fork};
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    fn blocks(db: &ReamDB, roots: &[B256]) -> Vec<Arc<SignedBeaconBlock>> {
+        roots
+            .iter()
+            .map(|root| Arc::new(db.beacon_block_provider().get(*root).unwrap().unwrap()))
+            .collect()
+    }
+
+    /// A backfill of the chain at slots `1..=8`, starting from its block at slot 8. Returns the
+    /// syncer, the roots of the chain and the database holding all of it.
+    fn test_backfill() -> (BackfillSyncer, Vec<B256>, ReamDB) {
+        initialize_test_network_spec();
+        let chain_db = ReamDB::in_memory();
+        let roots = import_test_chain(&chain_db, B256::ZERO, 1..=8);
+
+        let db = ReamDB::in_memory();
+        let anchor = chain_db
+            .beacon_block_provider()
+            .get(roots[7])
+            .unwrap()
+            .unwrap();
+        db.beacon_block_provider().insert(roots[7], anchor).unwrap();
+        let syncer = BackfillSyncer::new(
+            db,
+            mpsc::unbounded_channel().0,
+            Arc::new(RwLock::new(HashMap::new())),
+            BackfillTarget::Genesis,
+        );
+        (syncer, roots, chain_db)
+    }
+
+    #[test]
+    fn test_import_batch() {
+        let (syncer, roots, chain_db) = test_backfill();
+        assert_eq!(syncer.oldest_block().unwrap(), (8, roots[6]));
+
+        assert_eq!(
+            syncer
+                .import_batch(blocks(&chain_db, &roots[3..7]), 4, 8, roots[6])
+                .unwrap(),
+            roots[2]
+        );
+        for root in &roots[3..7] {
+            assert!(
+                syncer
+                    .db
+                    .beacon_block_provider()
+                    .get(*root)
+                    .unwrap()
+                    .is_some()
+            );
+        }
+
+        // A restart resumes below the imported batch
+        assert_eq!(syncer.oldest_block().unwrap(), (4, roots[2]));
+    }
+
+    #[test]
+    fn test_import_batch_rejects_invalid_batches() {
+        let (syncer, roots, chain_db) = test_backfill();
+        let batch = blocks(&chain_db, &roots[3..7]);
+
+        // Not linking to the oldest block
+        assert!(
+            syncer
+                .import_batch(batch.clone(), 4, 8, B256::repeat_byte(1))
+                .is_err()
+        );
+        // Outside of the requested range
+        assert!(syncer.import_batch(batch.clone(), 5, 8, roots[6]).is_err());
+        // Not strictly increasing
+        let mut duplicated = batch.clone();
+        duplicated.push(batch[3].clone());
+        assert!(syncer.import_batch(duplicated, 4, 8, roots[6]).is_err());
+
+        // A batch whose newer blocks link but whose oldest block is from another chain is not
+        // stored at all
+        let fork_root = import_test_fork(&chain_db, roots[2], 4..=4, B256::repeat_byte(1))[0];
+        let mut forked = blocks(&chain_db, &[fork_root]);
+        forked.extend_from_slice(&batch[1..]);
+        assert!(syncer.import_batch(forked, 4, 8, roots[6]).is_err());
+
+        for root in &roots[3..7] {
+            assert!(
+                syncer
+                    .db
+                    .beacon_block_provider()
+                    .get(*root)
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        assert_eq!(syncer.oldest_block().unwrap(), (8, roots[6]));
+    }
+
+    #[test]
+    fn test_backfill_target_from_str() {
+        for target in [
+            BackfillTarget::Genesis,
+            BackfillTarget::WeakSubjectivity,
+            BackfillTarget::BlobRetention,
+        ] {
+            assert_eq!(target.to_string().parse::<BackfillTarget>(), Ok(target));
+        }
+        assert!("finalized".parse::<BackfillTarget>().is_err());
+    }
+
+    #[test]
+    fn test_backfill_genesis_stop_slot() {
+        assert_eq!(BackfillTarget::Genesis.stop_slot(100 * SLOTS_PER_EPOCH), 0);
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+use anyhow::ensure;
+use libp2p::PeerId;
+use ream_consensus::electra::beacon_block::SignedBeaconBlock;
+
+/// A contiguous range of slots requested from a single peer.
+#[derive(Debug, Clone)]
+pub struct Batch {
+    pub start_slot: u64,
+    pub count: u64,
+    pub blocks: Vec<Arc<SignedBeaconBlock>>,
+    /// The peer the blocks were downloaded from.
+    pub peer_id: Option<PeerId>,
+}
+
+impl Batch {
+    pub fn new(start_slot: u64, count: u64) -> Self {
+        Self {
+            start_slot,
+            count,
+            blocks: vec![],
+            peer_id: None,
+        }
+    }
+
+    pub fn end_slot(&self) -> u64 {
+        self.start_slot + self.count
+    }
+
+    /// Checks the downloaded blocks are in range, strictly increasing and form a chain on top of
+    /// `parent_root`. Returns the root of the last block, or `parent_root` if the batch is empty.
+    pub fn verify_linkage(&self, parent_root: B256) -> anyhow::Result<B256> {
+        let mut previous_root = parent_root;
+        let mut previous_slot = None;
+
+        for block in &self.blocks {
+            let slot = block.message.slot;
+            ensure!(
+                slot >= self.start_slot && slot < self.end_slot(),
+                "Block slot {slot} outside of requested range {}..{}",
+                self.start_slot,
+                self.end_slot()
+            );
+            ensure!(
+                previous_slot.is_none_or(|previous_slot| slot > previous_slot),
+                "Blocks are not in increasing slot order at slot {slot}"
+            );
+            ensure!(
+                block.message.parent_root == previous_root,
+                "Block at slot {slot} doesn't link to parent {previous_root}"
+            );
+
+            previous_root = block.message.block_root();
+            previous_slot = Some(slot);
+        }
+
+        Ok(previous_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use ream_storage::{db::ReamDB, tables::Table, test_utils::import_test_chain};
+
+    use super::*;
+
+    fn test_batch(db: &ReamDB, roots: &[B256], start_slot: u64, count: u64) -> Batch {
+        let mut batch = Batch::new(start_slot, count);
+        batch.blocks = roots
+            .iter()
+            .map(|root| Arc::new(db.beacon_block_provider().get(*root).unwrap().unwrap()))
+            .collect();
+        batch
+    }
+
+    #[test]
+    fn test_verify_linkage() {
+        initialize_test_network_spec();
+        let db = ReamDB::in_memory();
+        let parent_root = B256::repeat_byte(1);
+        let roots = import_test_chain(&db, parent_root, [1, 2, 4]);
+
+        assert_eq!(
+            test_batch(&db, &roots, 1, 4)
+                .verify_linkage(parent_root)
+                .unwrap(),
+            roots[2]
+        );
+        assert_eq!(
+            Batch::new(1, 4).verify_linkage(parent_root).unwrap(),
+            parent_root
+        );
+
+        // Not on top of the parent
+        assert!(
+            test_batch(&db, &roots, 1, 4)
+                .verify_linkage(B256::ZERO)
+                .is_err()
+        );
+        // Outside of the requested range
+        assert!(
+            test_batch(&db, &roots, 2, 4)
+                .verify_linkage(parent_root)
+                .is_err()
+        );
+        assert!(
+            test_batch(&db, &roots, 1, 3)
+                .verify_linkage(parent_root)
+                .is_err()
+        );
+        // Not in increasing slot order
+        assert!(
+            test_batch(&db, &[roots[0], roots[0]], 1, 4)
+                .verify_linkage(parent_root)
+                .is_err()
+        );
+        // With a block missing in between
+        assert!(
+            test_batch(&db, &[roots[0], roots[2]], 1, 4)
+                .verify_linkage(parent_root)
+                .is_err()
+        );
+    }
+}
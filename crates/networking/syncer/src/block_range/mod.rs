@@ -1,12 +1,38 @@
-use std::sync::Arc;
+pub mod batch;
 
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, bail};
+use batch::Batch;
+use futures::future::join_all;
+use libp2p::PeerId;
+use parking_lot::RwLock;
 use ream_beacon_chain::beacon_chain::BeaconChain;
-use ream_p2p::channel::P2PMessages;
-use tokio::sync::mpsc::UnboundedSender;
+use ream_consensus::constants::{SECONDS_PER_SLOT, SLOTS_PER_EPOCH};
+use ream_p2p::{
+    channel::P2PMessages, peer_manager::peer_info::PeerAction, req_resp::messages::status::Status,
+};
+use ream_storage::tables::{Field, Table};
+use tokio::{sync::mpsc::UnboundedSender, time::sleep};
+use tracing::{error, info, warn};
+
+use crate::utils::request_block_range;
+
+/// Number of slots requested in a single `BeaconBlocksByRange` request.
+pub const BATCH_SIZE: u64 = 2 * SLOTS_PER_EPOCH;
+
+/// Maximum number of batches downloaded in parallel.
+const MAX_PARALLEL_BATCHES: usize = 4;
 
+/// Number of times a batch is retried with another peer before the sync round is aborted.
+const MAX_BATCH_ATTEMPTS: usize = 5;
+
+#[derive(Clone)]
 pub struct BlockRangeSyncer {
     pub beacon_chain: Arc<BeaconChain>,
     pub p2p_sender: UnboundedSender<P2PMessages>,
+    pub peer_statuses: Arc<RwLock<HashMap<PeerId, Status>>>,
 }
 
 impl BlockRangeSyncer {
@@ -14,6 +40,426 @@ impl BlockRangeSyncer {
         Self {
             beacon_chain,
             p2p_sender,
+            peer_statuses: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn update_peer_status(&self, peer_id: PeerId, status: Status) {
+        self.peer_statuses.write().insert(peer_id, status);
+    }
+
+    pub fn remove_peer(&self, peer_id: &PeerId) {
+        self.peer_statuses.write().remove(peer_id);
+    }
+
+    /// Periodically checks whether any peer is ahead of us and syncs up to it.
+    pub async fn start(self) {
+        loop {
+            if let Err(err) = self.sync().await {
+                error!("Range sync failed: {err:?}");
+            }
+
+            sleep(Duration::from_secs(SECONDS_PER_SLOT)).await;
+        }
+    }
+
+    /// Runs a single round of range sync against the peers whose finalized epoch is ahead of
+    /// ours, importing blocks from our finalized block until we reach the highest head slot they
+    /// advertise.
+    pub async fn sync(&self) -> anyhow::Result<()> {
+        let (local_finalized_epoch, finalized_root, finalized_slot, head_slot) =
+            self.local_status().await?;
+
+        let peers = self.peers_ahead_of(local_finalized_epoch);
+        let Some(target_slot) = peers
+            .iter()
+            .map(|(_, status)| status.head_slot)
+            .max()
+            .filter(|target_slot| *target_slot > head_slot)
+        else {
+            return Ok(());
+        };
+        let peers: Vec<PeerId> = peers.into_iter().map(|(peer_id, _)| peer_id).collect();
+
+        info!(
+            "Starting range sync from slot {finalized_slot} to slot {target_slot} with {} peers",
+            peers.len()
+        );
+
+        // Our head may sit on a fork the peers abandoned, so the chain is rebuilt on top of the
+        // finalized block. Blocks we already have are skipped on import.
+        let start_slot = finalized_slot + 1;
+        let mut batches: Vec<Batch> = (start_slot..=target_slot)
+            .step_by(BATCH_SIZE as usize)
+            .map(|slot| Batch::new(slot, BATCH_SIZE.min(target_slot + 1 - slot)))
+            .collect();
+        batches.reverse();
+
+        let mut parent_root = finalized_root;
+        // The last imported batch and the root it was linked to
+        let mut previous_batch: Option<(Batch, B256)> = None;
+        let mut peer_index = 0;
+        while !batches.is_empty() {
+            let window: Vec<Batch> = (0..MAX_PARALLEL_BATCHES)
+                .map_while(|_| batches.pop())
+                .collect();
+
+            let downloads = window.into_iter().map(|batch| {
+                peer_index += 1;
+                self.download_batch_with_retries(batch, &peers, peer_index)
+            });
+
+            for batch in join_all(downloads).await {
+                let mut batch = batch?;
+
+                // A batch which doesn't link to our chain is re-downloaded from other peers.
+                // The previous batch may be the faulty one, e.g. if its peer withheld its last
+                // blocks, so it is re-downloaded as well.
+                let mut attempt = 0;
+                let last_root = loop {
+                    let err = match batch.verify_linkage(parent_root) {
+                        Ok(last_root) => break last_root,
+                        Err(err) => err,
+                    };
+                    attempt += 1;
+                    if attempt >= MAX_BATCH_ATTEMPTS {
+                        bail!(
+                            "Batch starting at slot {} failed linkage: {err:?}",
+                            batch.start_slot
+                        );
+                    }
+                    warn!(
+                        "Batch starting at slot {} failed linkage, retrying: {err:?}",
+                        batch.start_slot
+                    );
+
+                    if let Some((previous, previous_parent_root)) = &previous_batch {
+                        peer_index += 1;
+                        let previous = self
+                            .download_batch_with_retries(
+                                Batch::new(previous.start_slot, previous.count),
+                                &peers,
+                                peer_index,
+                            )
+                            .await?;
+                        match previous.verify_linkage(*previous_parent_root) {
+                            Ok(last_root) if last_root != parent_root => {
+                                self.import_batch_with_retries(
+                                    previous,
+                                    *previous_parent_root,
+                                    &peers,
+                                    &mut peer_index,
+                                )
+                                .await?;
+                                parent_root = last_root;
+                            }
+                            Ok(_) => {}
+                            Err(err) => warn!(
+                                "Re-downloaded batch starting at slot {} failed linkage: {err:?}",
+                                previous.start_slot
+                            ),
+                        }
+                    }
+
+                    peer_index += 1;
+                    batch = self
+                        .download_batch_with_retries(
+                            Batch::new(batch.start_slot, batch.count),
+                            &peers,
+                            peer_index,
+                        )
+                        .await?;
+                };
+
+                let synced_slot = batch.end_slot() - 1;
+                let batch_parent_root = parent_root;
+                let batch_range = Batch::new(batch.start_slot, batch.count);
+                self.import_batch_with_retries(batch, batch_parent_root, &peers, &mut peer_index)
+                    .await?;
+                parent_root = last_root;
+                previous_batch = Some((batch_range, batch_parent_root));
+
+                info!(
+                    "Range sync progress: slot {synced_slot}/{target_slot} ({:.2}%)",
+                    (synced_slot - finalized_slot) as f64 * 100.0
+                        / (target_slot - finalized_slot) as f64
+                );
+            }
+        }
+
+        info!("Range sync completed at slot {target_slot}");
+
+        Ok(())
+    }
+
+    /// Imports a batch linked to `parent_root`. If one of its blocks fails to import, the peer
+    /// which served it is reported and the batch is downloaded again from another peer.
+    async fn import_batch_with_retries(
+        &self,
+        mut batch: Batch,
+        parent_root: B256,
+        peers: &[PeerId],
+        peer_index: &mut usize,
+    ) -> anyhow::Result<()> {
+        let (start_slot, count) = (batch.start_slot, batch.count);
+        let mut attempt = 0;
+        loop {
+            let peer_id = batch.peer_id;
+            let err = match batch.verify_linkage(parent_root) {
+                Ok(_) => match self.import_batch(batch).await {
+                    Ok(()) => return Ok(()),
+                    Err(err) => {
+                        if let Some(peer_id) = peer_id {
+                            self.report_peer(peer_id);
+                        }
+                        err
+                    }
+                },
+                Err(err) => err,
+            };
+            attempt += 1;
+            if attempt >= MAX_BATCH_ATTEMPTS {
+                bail!("Failed to import batch starting at slot {start_slot}: {err:?}");
+            }
+            warn!("Failed to import batch starting at slot {start_slot}, retrying: {err:?}");
+
+            *peer_index += 1;
+            batch = self
+                .download_batch_with_retries(Batch::new(start_slot, count), peers, *peer_index)
+                .await?;
+        }
+    }
+
+    /// Tells the peer manager `peer_id` served blocks which failed to import.
+    fn report_peer(&self, peer_id: PeerId) {
+        if let Err(err) = self.p2p_sender.send(P2PMessages::ReportPeer {
+            peer_id,
+            action: PeerAction::LowToleranceError,
+            source: "range_sync",
+        }) {
+            warn!("Failed to report peer {peer_id}: {err:?}");
+        }
+    }
+
+    /// Imports the blocks of a linked batch we don't have yet.
+    async fn import_batch(&self, batch: Batch) -> anyhow::Result<()> {
+        for block in batch.blocks {
+            let block_root = block.message.block_root();
+            let known = {
+                let store = self.beacon_chain.store.lock().await;
+                store.db.beacon_block_provider().get(block_root)?.is_some()
+            };
+            if !known {
+                self.beacon_chain
+                    .process_block(Arc::unwrap_or_clone(block))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns our finalized epoch, finalized root, finalized block slot and head slot.
+    async fn local_status(&self) -> anyhow::Result<(u64, B256, u64, u64)> {
+        let store = self.beacon_chain.store.lock().await;
+        let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
+        let finalized_slot = store
+            .db
+            .beacon_block_provider()
+            .get(finalized_checkpoint.root)?
+            .ok_or_else(|| {
+                anyhow!(
+                    "Failed to find finalized block: {}",
+                    finalized_checkpoint.root
+                )
+            })?
+            .message
+            .slot;
+        let head_root = store.get_head()?;
+        let head_slot = store
+            .db
+            .beacon_block_provider()
+            .get(head_root)?
+            .ok_or_else(|| anyhow!("Failed to find head block: {head_root}"))?
+            .message
+            .slot;
+
+        Ok((
+            finalized_checkpoint.epoch,
+            finalized_checkpoint.root,
+            finalized_slot,
+            head_slot,
+        ))
+    }
+
+    fn peers_ahead_of(&self, finalized_epoch: u64) -> Vec<(PeerId, Status)> {
+        self.peer_statuses
+            .read()
+            .iter()
+            .filter(|(_, status)| status.finalized_epoch > finalized_epoch)
+            .map(|(peer_id, status)| (*peer_id, status.clone()))
+            .collect()
+    }
+
+    /// Downloads a batch, moving on to the next peer every time a request fails.
+    async fn download_batch_with_retries(
+        &self,
+        batch: Batch,
+        peers: &[PeerId],
+        peer_index: usize,
+    ) -> anyhow::Result<Batch> {
+        for attempt in 0..MAX_BATCH_ATTEMPTS {
+            let peer_id = peers[(peer_index + attempt) % peers.len()];
+            match self.download_batch(peer_id, batch.clone()).await {
+                Ok(batch) => return Ok(batch),
+                Err(err) => warn!(
+                    "Failed to download batch starting at slot {} from {peer_id}: {err:?}",
+                    batch.start_slot
+                ),
+            }
+        }
+
+        bail!(
+            "Failed to download batch starting at slot {} after {MAX_BATCH_ATTEMPTS} attempts",
+            batch.start_slot
+        )
+    }
+
+    async fn download_batch(&self, peer_id: PeerId, mut batch: Batch) -> anyhow::Result<Batch> {
+        batch.blocks =
+            request_block_range(&self.p2p_sender, peer_id, batch.start_slot, batch.count).await?;
+        batch.peer_id = Some(peer_id);
+
+        // A whole batch of empty slots is unlikely below the peer's head, the peer is more likely
+        // withholding blocks
+        if batch.blocks.is_empty() {
+            let peer_head_slot = self
+                .peer_statuses
+                .read()
+                .get(&peer_id)
+                .map(|status| status.head_slot);
+            if let Some(head_slot) =
+                peer_head_slot.filter(|head_slot| *head_slot >= batch.end_slot())
+            {
+                bail!(
+                    "Peer at head slot {head_slot} returned no blocks for slots {}..{}",
+                    batch.start_slot,
+                    batch.end_slot()
+                );
+            }
+        }
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus::constants::genesis_validators_root;
+    use ream_fork_choice::store::get_forkchoice_store;
+    use ream_network_spec::networks::{initialize_test_network_spec, network_spec};
+    use ream_p2p::{channel::P2PResponse, req_resp::messages::ResponseMessage};
+    use ream_storage::{db::ReamDB, test_utils::import_valid_test_chain};
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    /// Answers block range requests from `chain_db`, forwarding the peers which get reported.
+    /// The first response carries a block with an invalid signature, which links to its parent
+    /// but fails to import.
+    fn spawn_test_network(
+        chain_db: ReamDB,
+    ) -> (
+        UnboundedSender<P2PMessages>,
+        mpsc::UnboundedReceiver<PeerId>,
+    ) {
+        let (p2p_sender, mut p2p_receiver) = mpsc::unbounded_channel();
+        let (report_sender, report_receiver) = mpsc::unbounded_channel();
+        let mut corrupted = false;
+        tokio::spawn(async move {
+            while let Some(message) = p2p_receiver.recv().await {
+                match message {
+                    P2PMessages::RequestBlockRange {
+                        start,
+                        count,
+                        callback,
+                        ..
+                    } => {
+                        for slot in start..start + count {
+                            let Some(block_root) =
+                                chain_db.slot_index_provider().get(slot).unwrap()
+                            else {
+                                continue;
+                            };
+                            let mut block = chain_db
+                                .beacon_block_provider()
+                                .get(block_root)
+                                .unwrap()
+                                .unwrap();
+                            if slot == start + count - 1 && !corrupted {
+                                corrupted = true;
+                                block.signature = Default::default();
+                            }
+                            callback
+                                .send(Ok(P2PResponse::ResponseMessage(
+                                    ResponseMessage::BeaconBlocksByRange(Arc::new(block)),
+                                )))
+                                .await
+                                .unwrap();
+                        }
+                        callback.send(Ok(P2PResponse::EndOfStream)).await.unwrap();
+                    }
+                    P2PMessages::ReportPeer { peer_id, .. } => {
+                        report_sender.send(peer_id).unwrap();
+                    }
+                    _ => {}
+                }
+            }
+        });
+        (p2p_sender, report_receiver)
+    }
+
+    #[tokio::test]
+    async fn test_sync_retries_batch_failing_import() {
+        initialize_test_network_spec();
+        let chain_db = ReamDB::in_memory();
+        let roots = import_valid_test_chain(&chain_db, 8).await;
+        let genesis_block = chain_db
+            .beacon_block_provider()
+            .get(roots[0])
+            .unwrap()
+            .unwrap();
+        let genesis_state = chain_db
+            .beacon_state_provider()
+            .get(roots[0])
+            .unwrap()
+            .unwrap();
+
+        let db = ReamDB::in_memory();
+        get_forkchoice_store(genesis_state.clone(), genesis_block.message, db.clone()).unwrap();
+        db.time_provider()
+            .insert(genesis_state.genesis_time + 8 * SECONDS_PER_SLOT)
+            .unwrap();
+
+        let (p2p_sender, mut reported_peers) = spawn_test_network(chain_db);
+        let syncer =
+            BlockRangeSyncer::new(Arc::new(BeaconChain::new(db.clone(), None)), p2p_sender);
+        let peer_id = PeerId::random();
+        syncer.update_peer_status(
+            peer_id,
+            Status {
+                fork_digest: network_spec().fork_digest(genesis_validators_root()),
+                finalized_root: roots[0],
+                finalized_epoch: 1,
+                head_root: roots[8],
+                head_slot: 8,
+            },
+        );
+
+        syncer.sync().await.unwrap();
+
+        for root in &roots {
+            assert!(db.beacon_block_provider().get(*root).unwrap().is_some());
         }
+        assert_eq!(reported_peers.recv().await, Some(peer_id));
+        assert!(reported_peers.try_recv().is_err());
     }
 }
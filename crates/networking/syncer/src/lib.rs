@@ -1 +1,3 @@
+pub mod backfill;
 pub mod block_range;
+pub mod utils;
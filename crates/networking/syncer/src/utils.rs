@@ -0,0 +1,56 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{anyhow, bail};
+use libp2p::PeerId;
+use ream_consensus::electra::beacon_block::SignedBeaconBlock;
+use ream_p2p::{
+    channel::{P2PMessages, P2PResponse},
+    req_resp::messages::ResponseMessage,
+};
+use tokio::{
+    sync::mpsc::{self, UnboundedSender},
+    time::timeout,
+};
+
+/// How long a peer has to answer a `BeaconBlocksByRange` request in full.
+pub const BLOCK_RANGE_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Requests `count` slots starting at `start_slot` from `peer_id` and collects the returned
+/// blocks.
+pub async fn request_block_range(
+    p2p_sender: &UnboundedSender<P2PMessages>,
+    peer_id: PeerId,
+    start_slot: u64,
+    count: u64,
+) -> anyhow::Result<Vec<Arc<SignedBeaconBlock>>> {
+    let (callback, mut receiver) = mpsc::channel(count as usize + 1);
+    p2p_sender
+        .send(P2PMessages::RequestBlockRange {
+            peer_id,
+            start: start_slot,
+            count,
+            callback,
+        })
+        .map_err(|err| anyhow!("Failed to send block range request: {err:?}"))?;
+
+    let mut blocks = vec![];
+    timeout(BLOCK_RANGE_REQUEST_TIMEOUT, async {
+        while let Some(response) = receiver.recv().await {
+            match response? {
+                P2PResponse::ResponseMessage(ResponseMessage::BeaconBlocksByRange(block)) => {
+                    blocks.push(block)
+                }
+                P2PResponse::ResponseMessage(message) => {
+                    bail!("Unexpected response to block range request: {message:?}")
+                }
+                P2PResponse::EndOfStream => return Ok(()),
+            }
+        }
+
+        bail!("Block range response channel closed")
+    })
+    .await
+    .map_err(|_| anyhow!("Block range request timed out"))??;
+
+    Ok(blocks)
+}
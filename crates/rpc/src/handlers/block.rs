@@ -88,8 +88,8 @@ pub async fn get_block_root_from_id(block_id: ID, db: &ReamDB) -> Result<B256, A
 async fn get_beacon_state(block_id: ID, db: &ReamDB) -> Result<BeaconState, ApiError> {
     let block_root = get_block_root_from_id(block_id, db).await?;
 
-    db.beacon_state_provider()
-        .get(block_root)
+    db.get_state(block_root)
+        .await
         .map_err(|_| ApiError::InternalError)?
         .ok_or(ApiError::NotFound(format!(
             "Failed to find `beacon_state` from {block_root:?}"
@@ -27,8 +27,8 @@ pub async fn get_light_client_bootstrap(
         })?;
 
     let beacon_state = db
-        .beacon_state_provider()
-        .get(block_root)
+        .get_state(block_root)
+        .await
         .map_err(|_| ApiError::InternalError)?
         .ok_or(ApiError::NotFound(format!(
             "Failed to find `beacon_state` from {block_root:?}"
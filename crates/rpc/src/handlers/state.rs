@@ -83,8 +83,8 @@ pub async fn get_state_from_id(state_id: ID, db: &ReamDB) -> Result<BeaconState,
     })?
     .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))?;
 
-    db.beacon_state_provider()
-        .get(block_root)
+    db.get_state(block_root)
+        .await
         .map_err(|err| {
             error!("Failed to get block by block_root, error: {err:?}");
             ApiError::InternalError
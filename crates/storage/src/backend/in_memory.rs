@@ -0,0 +1,189 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{PoisonError, RwLock},
+};
+
+use super::{Entry, KeyRange, KeyValueBackend, WriteOp};
+use crate::errors::StoreError;
+
+#[derive(Debug, Default)]
+struct Tables {
+    tables: HashMap<&'static str, BTreeMap<Vec<u8>, Vec<u8>>>,
+    multimap_tables: HashMap<&'static str, BTreeMap<Vec<u8>, BTreeSet<Vec<u8>>>>,
+}
+
+/// Keeps all tables in memory, for tests and simulations. Nothing is persisted.
+#[derive(Debug, Default)]
+pub struct InMemoryBackend {
+    tables: RwLock<Tables>,
+}
+
+impl InMemoryBackend {
+    fn read<T>(
+        &self,
+        table: &'static str,
+        f: impl FnOnce(&BTreeMap<Vec<u8>, Vec<u8>>) -> T,
+    ) -> Option<T> {
+        let tables = self.tables.read().unwrap_or_else(PoisonError::into_inner);
+        tables.tables.get(table).map(f)
+    }
+}
+
+impl KeyValueBackend for InMemoryBackend {
+    fn get(&self, table: &'static str, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        Ok(self.read(table, |table| table.get(key).cloned()).flatten())
+    }
+
+    fn first(&self, table: &'static str, range: KeyRange<'_>) -> Result<Option<Entry>, StoreError> {
+        Ok(self
+            .read(table, |table| {
+                table
+                    .range::<[u8], _>(range)
+                    .next()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+            })
+            .flatten())
+    }
+
+    fn last(&self, table: &'static str, range: KeyRange<'_>) -> Result<Option<Entry>, StoreError> {
+        Ok(self
+            .read(table, |table| {
+                table
+                    .range::<[u8], _>(range)
+                    .next_back()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+            })
+            .flatten())
+    }
+
+    fn last_key(
+        &self,
+        table: &'static str,
+        range: KeyRange<'_>,
+    ) -> Result<Option<Vec<u8>>, StoreError> {
+        Ok(self
+            .read(table, |table| {
+                table
+                    .range::<[u8], _>(range)
+                    .next_back()
+                    .map(|(key, _)| key.clone())
+            })
+            .flatten())
+    }
+
+    fn keys(&self, table: &'static str) -> Result<Vec<Vec<u8>>, StoreError> {
+        Ok(self
+            .read(table, |table| table.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn entries(&self, table: &'static str) -> Result<Vec<Entry>, StoreError> {
+        Ok(self
+            .read(table, |table| {
+                table
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn multimap_get(&self, table: &'static str, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError> {
+        let tables = self.tables.read().unwrap_or_else(PoisonError::into_inner);
+        Ok(tables
+            .multimap_tables
+            .get(table)
+            .and_then(|table| table.get(key))
+            .map(|values| values.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn write(&self, ops: Vec<WriteOp>) -> Result<(), StoreError> {
+        // The ops can't fail, holding the lock while applying them makes them atomic
+        let mut tables = self.tables.write().unwrap_or_else(PoisonError::into_inner);
+        for op in ops {
+            match op {
+                WriteOp::Put { table, key, value } => {
+                    tables.tables.entry(table).or_default().insert(key, value);
+                }
+                WriteOp::Delete { table, key } => {
+                    if let Some(table) = tables.tables.get_mut(table) {
+                        table.remove(&key);
+                    }
+                }
+                WriteOp::MultimapInsert { table, key, value } => {
+                    tables
+                        .multimap_tables
+                        .entry(table)
+                        .or_default()
+                        .entry(key)
+                        .or_default()
+                        .insert(value);
+                }
+                WriteOp::MultimapRemove { table, key, value } => {
+                    if let Some(values) = tables
+                        .multimap_tables
+                        .get_mut(table)
+                        .and_then(|table| table.get_mut(&key))
+                    {
+                        values.remove(&value);
+                    }
+                }
+                WriteOp::MultimapRemoveAll { table, key } => {
+                    if let Some(table) = tables.multimap_tables.get_mut(table) {
+                        table.remove(&key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use crate::{
+        db::ReamDB,
+        errors::StoreError,
+        tables::{Field, MultimapTable, Table},
+    };
+
+    #[test]
+    fn test_in_memory_tables() {
+        let db = ReamDB::in_memory();
+
+        assert!(matches!(
+            db.genesis_time_provider().get(),
+            Err(StoreError::FieldNotInitilized)
+        ));
+        db.genesis_time_provider().insert(1_606_824_023).unwrap();
+        assert_eq!(db.genesis_time_provider().get().unwrap(), 1_606_824_023);
+
+        for slot in [255, 1, 256] {
+            db.slot_index_provider()
+                .insert(slot, B256::repeat_byte(slot as u8))
+                .unwrap();
+        }
+        assert_eq!(db.slot_index_provider().get_oldest_slot().unwrap(), Some(1));
+        assert_eq!(
+            db.slot_index_provider().get_highest_slot().unwrap(),
+            Some(256)
+        );
+
+        let parent_root = B256::repeat_byte(1);
+        db.parent_root_index_multimap_provider()
+            .insert(parent_root, B256::repeat_byte(2))
+            .unwrap();
+        db.parent_root_index_multimap_provider()
+            .insert(parent_root, B256::repeat_byte(3))
+            .unwrap();
+        assert_eq!(
+            db.parent_root_index_multimap_provider()
+                .get(parent_root)
+                .unwrap(),
+            Some(vec![B256::repeat_byte(2), B256::repeat_byte(3)])
+        );
+    }
+}
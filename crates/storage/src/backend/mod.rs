@@ -0,0 +1,77 @@
+pub mod in_memory;
+pub mod redb_backend;
+pub mod staged;
+
+use std::{fmt::Debug, ops::Bound};
+
+use crate::errors::StoreError;
+
+/// A key and a value as stored in the backend
+pub type Entry = (Vec<u8>, Vec<u8>);
+
+/// Bounds on the keys of a table, in the order of their bytes
+pub type KeyRange<'a> = (Bound<&'a [u8]>, Bound<&'a [u8]>);
+
+/// Every key of a table
+pub const ALL_KEYS: KeyRange<'static> = (Bound::Unbounded, Bound::Unbounded);
+
+/// A single write to a [`KeyValueBackend`] table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteOp {
+    Put {
+        table: &'static str,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        table: &'static str,
+        key: Vec<u8>,
+    },
+    MultimapInsert {
+        table: &'static str,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    MultimapRemove {
+        table: &'static str,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    MultimapRemoveAll {
+        table: &'static str,
+        key: Vec<u8>,
+    },
+}
+
+/// The key-value store the tables are kept in.
+///
+/// Keys and values are opaque bytes, encoded by the table definitions. Each table is a separate
+/// keyspace ordered by key bytes, multimap tables hold a set of values per key. Tables which
+/// were never written to read as empty.
+pub trait KeyValueBackend: Debug + Send + Sync {
+    fn get(&self, table: &'static str, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError>;
+
+    /// Returns the entry with the lowest key in `range`.
+    fn first(&self, table: &'static str, range: KeyRange<'_>) -> Result<Option<Entry>, StoreError>;
+
+    /// Returns the entry with the highest key in `range`.
+    fn last(&self, table: &'static str, range: KeyRange<'_>) -> Result<Option<Entry>, StoreError>;
+
+    /// Returns the highest key in `range`, without reading its value.
+    fn last_key(
+        &self,
+        table: &'static str,
+        range: KeyRange<'_>,
+    ) -> Result<Option<Vec<u8>>, StoreError>;
+
+    /// Returns all keys in order.
+    fn keys(&self, table: &'static str) -> Result<Vec<Vec<u8>>, StoreError>;
+
+    /// Returns all entries in key order.
+    fn entries(&self, table: &'static str) -> Result<Vec<Entry>, StoreError>;
+
+    fn multimap_get(&self, table: &'static str, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError>;
+
+    /// Applies all of `ops` or, if any of them fails, none.
+    fn write(&self, ops: Vec<WriteOp>) -> Result<(), StoreError>;
+}
@@ -0,0 +1,171 @@
+use redb::{
+    Database, Durability, MultimapTableDefinition, ReadOnlyMultimapTable, ReadOnlyTable,
+    ReadableTable, TableDefinition, TableError,
+};
+
+use super::{Entry, KeyRange, KeyValueBackend, WriteOp};
+use crate::errors::StoreError;
+
+type RawTable = TableDefinition<'static, &'static [u8], &'static [u8]>;
+
+type RawMultimapTable = MultimapTableDefinition<'static, &'static [u8], &'static [u8]>;
+
+fn raw_table(table: &'static str) -> RawTable {
+    TableDefinition::new(table)
+}
+
+fn raw_multimap_table(table: &'static str) -> RawMultimapTable {
+    MultimapTableDefinition::new(table)
+}
+
+/// Persists the tables in a redb database file.
+#[derive(Debug)]
+pub struct RedbBackend {
+    db: Database,
+}
+
+impl RedbBackend {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Opens `table` for reading, `None` if it was never written to.
+    fn open_table(
+        &self,
+        table: &'static str,
+    ) -> Result<Option<ReadOnlyTable<&'static [u8], &'static [u8]>>, StoreError> {
+        match self.db.begin_read()?.open_table(raw_table(table)) {
+            Ok(table) => Ok(Some(table)),
+            Err(TableError::TableDoesNotExist(_)) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn open_multimap_table(
+        &self,
+        table: &'static str,
+    ) -> Result<Option<ReadOnlyMultimapTable<&'static [u8], &'static [u8]>>, StoreError> {
+        match self
+            .db
+            .begin_read()?
+            .open_multimap_table(raw_multimap_table(table))
+        {
+            Ok(table) => Ok(Some(table)),
+            Err(TableError::TableDoesNotExist(_)) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+impl KeyValueBackend for RedbBackend {
+    fn get(&self, table: &'static str, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        let Some(table) = self.open_table(table)? else {
+            return Ok(None);
+        };
+        Ok(table.get(key)?.map(|value| value.value().to_vec()))
+    }
+
+    fn first(&self, table: &'static str, range: KeyRange<'_>) -> Result<Option<Entry>, StoreError> {
+        let Some(table) = self.open_table(table)? else {
+            return Ok(None);
+        };
+        Ok(table
+            .range(range)?
+            .next()
+            .transpose()?
+            .map(|(key, value)| (key.value().to_vec(), value.value().to_vec())))
+    }
+
+    fn last(&self, table: &'static str, range: KeyRange<'_>) -> Result<Option<Entry>, StoreError> {
+        let Some(table) = self.open_table(table)? else {
+            return Ok(None);
+        };
+        Ok(table
+            .range(range)?
+            .next_back()
+            .transpose()?
+            .map(|(key, value)| (key.value().to_vec(), value.value().to_vec())))
+    }
+
+    fn last_key(
+        &self,
+        table: &'static str,
+        range: KeyRange<'_>,
+    ) -> Result<Option<Vec<u8>>, StoreError> {
+        let Some(table) = self.open_table(table)? else {
+            return Ok(None);
+        };
+        Ok(table
+            .range(range)?
+            .next_back()
+            .transpose()?
+            .map(|(key, _)| key.value().to_vec()))
+    }
+
+    fn keys(&self, table: &'static str) -> Result<Vec<Vec<u8>>, StoreError> {
+        let Some(table) = self.open_table(table)? else {
+            return Ok(vec![]);
+        };
+        let mut keys = vec![];
+        for entry in table.iter()? {
+            keys.push(entry?.0.value().to_vec());
+        }
+        Ok(keys)
+    }
+
+    fn entries(&self, table: &'static str) -> Result<Vec<Entry>, StoreError> {
+        let Some(table) = self.open_table(table)? else {
+            return Ok(vec![]);
+        };
+        let mut entries = vec![];
+        for entry in table.iter()? {
+            let (key, value) = entry?;
+            entries.push((key.value().to_vec(), value.value().to_vec()));
+        }
+        Ok(entries)
+    }
+
+    fn multimap_get(&self, table: &'static str, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError> {
+        let Some(table) = self.open_multimap_table(table)? else {
+            return Ok(vec![]);
+        };
+        let mut values = vec![];
+        for value in table.get(key)? {
+            values.push(value?.value().to_vec());
+        }
+        Ok(values)
+    }
+
+    fn write(&self, ops: Vec<WriteOp>) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        for op in ops {
+            match op {
+                WriteOp::Put { table, key, value } => {
+                    let mut table = write_txn.open_table(raw_table(table))?;
+                    table.insert(key.as_slice(), value.as_slice())?;
+                }
+                WriteOp::Delete { table, key } => {
+                    let mut table = write_txn.open_table(raw_table(table))?;
+                    table.remove(key.as_slice())?;
+                }
+                WriteOp::MultimapInsert { table, key, value } => {
+                    let mut table = write_txn.open_multimap_table(raw_multimap_table(table))?;
+                    table.insert(key.as_slice(), value.as_slice())?;
+                }
+                WriteOp::MultimapRemove { table, key, value } => {
+                    let mut table = write_txn.open_multimap_table(raw_multimap_table(table))?;
+                    table.remove(key.as_slice(), value.as_slice())?;
+                }
+                WriteOp::MultimapRemoveAll { table, key } => {
+                    let mut table = write_txn.open_multimap_table(raw_multimap_table(table))?;
+                    table.remove_all(key.as_slice())?;
+                }
+            }
+        }
+        // Dropping the transaction without committing aborts it, so a failed op leaves nothing
+        // behind
+        write_txn.commit()?;
+        Ok(())
+    }
+}
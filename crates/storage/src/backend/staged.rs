@@ -0,0 +1,357 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Bound,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use super::{Entry, KeyRange, KeyValueBackend, WriteOp};
+use crate::errors::StoreError;
+
+/// Staged changes to the values of one multimap key
+#[derive(Debug, Default)]
+struct MultimapChanges {
+    /// All values which were in the inner backend are removed
+    cleared: bool,
+    inserted: BTreeSet<Vec<u8>>,
+    removed: BTreeSet<Vec<u8>>,
+}
+
+#[derive(Debug, Default)]
+struct Staged {
+    ops: Vec<WriteOp>,
+    /// Staged values by table and key, `None` if the key was deleted
+    tables: HashMap<&'static str, BTreeMap<Vec<u8>, Option<Vec<u8>>>>,
+    multimap_tables: HashMap<&'static str, HashMap<Vec<u8>, MultimapChanges>>,
+}
+
+/// Stages writes in memory on top of another backend, until they are committed to it all at
+/// once. Reads see the staged writes.
+#[derive(Debug)]
+pub struct StagedBackend {
+    inner: Arc<dyn KeyValueBackend>,
+    staged: Mutex<Staged>,
+}
+
+impl StagedBackend {
+    pub fn new(inner: Arc<dyn KeyValueBackend>) -> Self {
+        Self {
+            inner,
+            staged: Mutex::default(),
+        }
+    }
+
+    /// Writes the staged ops to the inner backend atomically.
+    pub fn commit(&self) -> Result<(), StoreError> {
+        let ops = std::mem::take(&mut *self.staged());
+        self.inner.write(ops.ops)
+    }
+
+    fn staged(&self) -> MutexGuard<'_, Staged> {
+        self.staged.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the lowest or, if `highest`, the highest key of `table` in `range` including the
+    /// staged ones. `inner_key` looks it up in the inner backend, which is only asked again past
+    /// keys that are shadowed by staged writes, so no more than the staged keys are skipped.
+    fn merged_key(
+        &self,
+        table: &'static str,
+        range: KeyRange<'_>,
+        highest: bool,
+        inner_key: impl Fn(KeyRange<'_>) -> Result<Option<Vec<u8>>, StoreError>,
+    ) -> Result<Option<Vec<u8>>, StoreError> {
+        let staged = self.staged();
+        let Some(staged_table) = staged.tables.get(table) else {
+            drop(staged);
+            return inner_key(range);
+        };
+
+        let mut staged_keys = staged_table
+            .range::<[u8], _>(range)
+            .filter(|(_, value)| value.is_some())
+            .map(|(key, _)| key);
+        let staged_key = if highest {
+            staged_keys.next_back()
+        } else {
+            staged_keys.next()
+        }
+        .cloned();
+
+        let mut shadowed_key: Option<Vec<u8>> = None;
+        let inner_key = loop {
+            let range = match &shadowed_key {
+                None => range,
+                Some(key) if highest => (range.0, Bound::Excluded(key.as_slice())),
+                Some(key) => (Bound::Excluded(key.as_slice()), range.1),
+            };
+            match inner_key(range)? {
+                Some(key) if staged_table.contains_key(&key) => shadowed_key = Some(key),
+                key => break key,
+            }
+        };
+
+        // Shadowed inner keys were skipped, so the two can't be equal
+        Ok(match (staged_key, inner_key) {
+            (Some(staged_key), Some(inner_key)) => Some(if (staged_key > inner_key) == highest {
+                staged_key
+            } else {
+                inner_key
+            }),
+            (staged_key, inner_key) => staged_key.or(inner_key),
+        })
+    }
+
+    fn entry(
+        &self,
+        table: &'static str,
+        key: Option<Vec<u8>>,
+    ) -> Result<Option<Entry>, StoreError> {
+        let Some(key) = key else {
+            return Ok(None);
+        };
+        Ok(self.get(table, &key)?.map(|value| (key, value)))
+    }
+}
+
+impl KeyValueBackend for StagedBackend {
+    fn get(&self, table: &'static str, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        if let Some(value) = self
+            .staged()
+            .tables
+            .get(table)
+            .and_then(|table| table.get(key))
+        {
+            return Ok(value.clone());
+        }
+        self.inner.get(table, key)
+    }
+
+    fn first(&self, table: &'static str, range: KeyRange<'_>) -> Result<Option<Entry>, StoreError> {
+        let key = self.merged_key(table, range, false, |range| {
+            Ok(self.inner.first(table, range)?.map(|(key, _)| key))
+        })?;
+        self.entry(table, key)
+    }
+
+    fn last(&self, table: &'static str, range: KeyRange<'_>) -> Result<Option<Entry>, StoreError> {
+        let key = self.last_key(table, range)?;
+        self.entry(table, key)
+    }
+
+    fn last_key(
+        &self,
+        table: &'static str,
+        range: KeyRange<'_>,
+    ) -> Result<Option<Vec<u8>>, StoreError> {
+        self.merged_key(table, range, true, |range| {
+            self.inner.last_key(table, range)
+        })
+    }
+
+    fn keys(&self, table: &'static str) -> Result<Vec<Vec<u8>>, StoreError> {
+        let mut keys: BTreeSet<Vec<u8>> = self.inner.keys(table)?.into_iter().collect();
+        if let Some(staged_table) = self.staged().tables.get(table) {
+            for (key, value) in staged_table {
+                match value {
+                    Some(_) => keys.insert(key.clone()),
+                    None => keys.remove(key),
+                };
+            }
+        }
+        Ok(keys.into_iter().collect())
+    }
+
+    fn entries(&self, table: &'static str) -> Result<Vec<Entry>, StoreError> {
+        let mut entries: BTreeMap<Vec<u8>, Vec<u8>> =
+            self.inner.entries(table)?.into_iter().collect();
+        if let Some(staged_table) = self.staged().tables.get(table) {
+            for (key, value) in staged_table {
+                match value {
+                    Some(value) => entries.insert(key.clone(), value.clone()),
+                    None => entries.remove(key),
+                };
+            }
+        }
+        Ok(entries.into_iter().collect())
+    }
+
+    fn multimap_get(&self, table: &'static str, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError> {
+        let staged = self.staged();
+        let Some(changes) = staged
+            .multimap_tables
+            .get(table)
+            .and_then(|table| table.get(key))
+        else {
+            drop(staged);
+            return self.inner.multimap_get(table, key);
+        };
+        let mut values = BTreeSet::new();
+        if !changes.cleared {
+            values.extend(self.inner.multimap_get(table, key)?);
+        }
+        values.retain(|value| !changes.removed.contains(value));
+        values.extend(changes.inserted.iter().cloned());
+        Ok(values.into_iter().collect())
+    }
+
+    /// Stages `ops` without writing them to the inner backend.
+    fn write(&self, ops: Vec<WriteOp>) -> Result<(), StoreError> {
+        let mut staged = self.staged();
+        for op in &ops {
+            match op {
+                WriteOp::Put { table, key, value } => {
+                    staged
+                        .tables
+                        .entry(*table)
+                        .or_default()
+                        .insert(key.clone(), Some(value.clone()));
+                }
+                WriteOp::Delete { table, key } => {
+                    staged
+                        .tables
+                        .entry(*table)
+                        .or_default()
+                        .insert(key.clone(), None);
+                }
+                WriteOp::MultimapInsert { table, key, value } => {
+                    let changes = staged
+                        .multimap_tables
+                        .entry(*table)
+                        .or_default()
+                        .entry(key.clone())
+                        .or_default();
+                    changes.removed.remove(value);
+                    changes.inserted.insert(value.clone());
+                }
+                WriteOp::MultimapRemove { table, key, value } => {
+                    let changes = staged
+                        .multimap_tables
+                        .entry(*table)
+                        .or_default()
+                        .entry(key.clone())
+                        .or_default();
+                    changes.inserted.remove(value);
+                    changes.removed.insert(value.clone());
+                }
+                WriteOp::MultimapRemoveAll { table, key } => {
+                    *staged
+                        .multimap_tables
+                        .entry(*table)
+                        .or_default()
+                        .entry(key.clone())
+                        .or_default() = MultimapChanges {
+                        cleared: true,
+                        ..Default::default()
+                    };
+                }
+            }
+        }
+        staged.ops.extend(ops);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use crate::{
+        db::ReamDB,
+        tables::{MultimapTable, Table, slot_index::SLOT_INDEX_TABLE},
+    };
+
+    #[test]
+    fn test_write_batch() {
+        let db = ReamDB::in_memory();
+        let parent_root = B256::repeat_byte(1);
+        db.parent_root_index_multimap_provider()
+            .insert(parent_root, B256::repeat_byte(2))
+            .unwrap();
+
+        let batch = db.write_batch();
+        batch
+            .db()
+            .slot_index_provider()
+            .insert(5, parent_root)
+            .unwrap();
+        batch
+            .db()
+            .parent_root_index_multimap_provider()
+            .insert(parent_root, B256::repeat_byte(3))
+            .unwrap();
+
+        // Staged writes are only visible through the batch until it is committed
+        assert_eq!(
+            batch.db().slot_index_provider().get_highest_slot().unwrap(),
+            Some(5)
+        );
+        assert_eq!(db.slot_index_provider().get(5).unwrap(), None);
+        assert_eq!(
+            batch
+                .db()
+                .parent_root_index_multimap_provider()
+                .get(parent_root)
+                .unwrap(),
+            Some(vec![B256::repeat_byte(2), B256::repeat_byte(3)])
+        );
+
+        batch.commit().unwrap();
+        assert_eq!(db.slot_index_provider().get(5).unwrap(), Some(parent_root));
+        assert_eq!(
+            db.parent_root_index_multimap_provider()
+                .get(parent_root)
+                .unwrap(),
+            Some(vec![B256::repeat_byte(2), B256::repeat_byte(3)])
+        );
+    }
+
+    #[test]
+    fn test_write_batch_ordered_reads() {
+        let db = ReamDB::in_memory();
+        for slot in 1..=3 {
+            db.slot_index_provider()
+                .insert(slot, B256::repeat_byte(slot as u8))
+                .unwrap();
+        }
+
+        // Staged deletes shadow the keys of the inner backend, staged inserts are merged in
+        let batch = db.write_batch();
+        batch
+            .db()
+            .db
+            .write(vec![SLOT_INDEX_TABLE.remove_op(&3)])
+            .unwrap();
+        batch
+            .db()
+            .slot_index_provider()
+            .insert(0, B256::ZERO)
+            .unwrap();
+        let slot_index_provider = batch.db().slot_index_provider();
+        assert_eq!(slot_index_provider.get_highest_slot().unwrap(), Some(2));
+        assert_eq!(slot_index_provider.get_oldest_slot().unwrap(), Some(0));
+        assert_eq!(
+            SLOT_INDEX_TABLE
+                .last_at_or_before(&*batch.db().db, &3)
+                .unwrap(),
+            Some((2, B256::repeat_byte(2)))
+        );
+        assert_eq!(
+            SLOT_INDEX_TABLE
+                .last_at_or_before(&*batch.db().db, &0)
+                .unwrap(),
+            Some((0, B256::ZERO))
+        );
+
+        batch
+            .db()
+            .db
+            .write(vec![
+                SLOT_INDEX_TABLE.remove_op(&0),
+                SLOT_INDEX_TABLE.remove_op(&1),
+                SLOT_INDEX_TABLE.remove_op(&2),
+            ])
+            .unwrap();
+        assert_eq!(slot_index_provider.get_highest_slot().unwrap(), None);
+        assert_eq!(slot_index_provider.get_oldest_slot().unwrap(), None);
+    }
+}
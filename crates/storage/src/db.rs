@@ -1,36 +1,26 @@
 use std::{fs, io, path::PathBuf, sync::Arc};
 
 use anyhow::Result;
-use redb::{Builder, Database};
+use redb::Builder;
 use tracing::info;
 
 use crate::{
+    backend::{KeyValueBackend, in_memory::InMemoryBackend, redb_backend::RedbBackend},
     errors::StoreError,
+    migrations::legacy_tables,
     tables::{
-        beacon_block::{BEACON_BLOCK_TABLE, BeaconBlockTable},
-        beacon_state::{BEACON_STATE_TABLE, BeaconStateTable},
-        blobs_and_proofs::{BLOBS_AND_PROOFS_TABLE, BlobsAndProofsTable},
-        block_timeliness::{BLOCK_TIMELINESS_TABLE, BlockTimelinessTable},
-        checkpoint_states::{CHECKPOINT_STATES_TABLE, CheckpointStatesTable},
-        equivocating_indices::{EQUIVOCATING_INDICES_FIELD, EquivocatingIndicesField},
-        finalized_checkpoint::{FINALIZED_CHECKPOINT_FIELD, FinalizedCheckpointField},
-        genesis_time::{GENESIS_TIME_FIELD, GenesisTimeField},
-        justified_checkpoint::{JUSTIFIED_CHECKPOINT_FIELD, JustifiedCheckpointField},
-        latest_messages::{LATEST_MESSAGES_TABLE, LatestMessagesTable},
-        parent_root_index::{PARENT_ROOT_INDEX_MULTIMAP_TABLE, ParentRootIndexMultimapTable},
-        proposer_boost_root::{PROPOSER_BOOST_ROOT_FIELD, ProposerBoostRootField},
-        slot_index::{SLOT_INDEX_TABLE, SlotIndexTable},
-        state_root_index::{STATE_ROOT_INDEX_TABLE, StateRootIndexTable},
-        time::{TIME_FIELD, TimeField},
-        unrealized_finalized_checkpoint::{
-            UNREALIZED_FINALIZED_CHECKPOINT_FIELD, UnrealizedFinalizedCheckpointField,
-        },
-        unrealized_justifications::{
-            UNREALIZED_JUSTIFICATIONS_TABLE, UnrealizedJustificationsTable,
-        },
-        unrealized_justified_checkpoint::{
-            UNREALIZED_JUSTIFED_CHECKPOINT_FIELD, UnrealizedJustifiedCheckpointField,
-        },
+        beacon_block::BeaconBlockTable, beacon_state::BeaconStateTable,
+        blobs_and_proofs::BlobsAndProofsTable, block_timeliness::BlockTimelinessTable,
+        checkpoint_states::CheckpointStatesTable, cold_beacon_state::ColdBeaconStateTable,
+        equivocating_indices::EquivocatingIndicesField,
+        finalized_checkpoint::FinalizedCheckpointField, freezer_slot::FreezerSlotField,
+        genesis_time::GenesisTimeField, justified_checkpoint::JustifiedCheckpointField,
+        latest_messages::LatestMessagesTable, parent_root_index::ParentRootIndexMultimapTable,
+        proposer_boost_root::ProposerBoostRootField, schema_version::SchemaVersionField,
+        slot_index::SlotIndexTable, state_root_index::StateRootIndexTable, time::TimeField,
+        unrealized_finalized_checkpoint::UnrealizedFinalizedCheckpointField,
+        unrealized_justifications::UnrealizedJustificationsTable,
+        unrealized_justified_checkpoint::UnrealizedJustifiedCheckpointField,
     },
 };
 
@@ -41,41 +31,44 @@ pub const REDB_FILE: &str = "ream.redb";
 /// 1 GiB
 pub const REDB_CACHE_SIZE: usize = 1_024 * 1_024 * 1_024;
 
+/// Default spacing of the finalized state snapshots in the cold store
+pub const DEFAULT_SLOTS_PER_STATE_SNAPSHOT: u64 = 2048;
+
 #[derive(Clone, Debug)]
 pub struct ReamDB {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
+    /// Spacing of the finalized state snapshots in the cold store. Smaller values take more disk
+    /// space and make historical states cheaper to rebuild.
+    pub slots_per_state_snapshot: u64,
 }
 
 impl ReamDB {
-    pub fn new(ream_dir: PathBuf) -> Result<Self, StoreError> {
+    pub fn new(ream_dir: PathBuf, slots_per_state_snapshot: u64) -> Result<Self, StoreError> {
         let ream_file = ream_dir.join(REDB_FILE);
 
         let db = Builder::new()
             .set_cache_size(REDB_CACHE_SIZE)
             .create(&ream_file)?;
+        legacy_tables::upgrade_legacy_tables(&db)?;
+
+        let ream_db = Self::from_backend(Arc::new(RedbBackend::new(db)), slots_per_state_snapshot);
+        ream_db.migrate_schema()?;
+        Ok(ream_db)
+    }
 
-        let write_txn = db.begin_write()?;
-        write_txn.open_table(BEACON_BLOCK_TABLE)?;
-        write_txn.open_table(BEACON_STATE_TABLE)?;
-        write_txn.open_table(BLOBS_AND_PROOFS_TABLE)?;
-        write_txn.open_table(BLOCK_TIMELINESS_TABLE)?;
-        write_txn.open_table(CHECKPOINT_STATES_TABLE)?;
-        write_txn.open_table(EQUIVOCATING_INDICES_FIELD)?;
-        write_txn.open_table(FINALIZED_CHECKPOINT_FIELD)?;
-        write_txn.open_table(GENESIS_TIME_FIELD)?;
-        write_txn.open_table(JUSTIFIED_CHECKPOINT_FIELD)?;
-        write_txn.open_table(LATEST_MESSAGES_TABLE)?;
-        write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
-        write_txn.open_table(PROPOSER_BOOST_ROOT_FIELD)?;
-        write_txn.open_table(SLOT_INDEX_TABLE)?;
-        write_txn.open_table(STATE_ROOT_INDEX_TABLE)?;
-        write_txn.open_table(TIME_FIELD)?;
-        write_txn.open_table(UNREALIZED_FINALIZED_CHECKPOINT_FIELD)?;
-        write_txn.open_table(UNREALIZED_JUSTIFICATIONS_TABLE)?;
-        write_txn.open_table(UNREALIZED_JUSTIFED_CHECKPOINT_FIELD)?;
-        write_txn.commit()?;
-
-        Ok(Self { db: Arc::new(db) })
+    /// A database which keeps everything in memory, for tests and simulations.
+    pub fn in_memory() -> Self {
+        Self::from_backend(
+            Arc::new(InMemoryBackend::default()),
+            DEFAULT_SLOTS_PER_STATE_SNAPSHOT,
+        )
+    }
+
+    pub fn from_backend(db: Arc<dyn KeyValueBackend>, slots_per_state_snapshot: u64) -> Self {
+        Self {
+            db,
+            slots_per_state_snapshot: slots_per_state_snapshot.max(1),
+        }
     }
 
     pub fn beacon_block_provider(&self) -> BeaconBlockTable {
@@ -90,6 +83,18 @@ impl ReamDB {
         }
     }
 
+    pub fn cold_beacon_state_provider(&self) -> ColdBeaconStateTable {
+        ColdBeaconStateTable {
+            db: self.db.clone(),
+        }
+    }
+
+    pub fn freezer_slot_provider(&self) -> FreezerSlotField {
+        FreezerSlotField {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn blobs_and_proofs_provider(&self) -> BlobsAndProofsTable {
         BlobsAndProofsTable {
             db: self.db.clone(),
@@ -156,6 +161,12 @@ impl ReamDB {
         }
     }
 
+    pub fn schema_version_provider(&self) -> SchemaVersionField {
+        SchemaVersionField {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn genesis_time_provider(&self) -> GenesisTimeField {
         GenesisTimeField {
             db: self.db.clone(),
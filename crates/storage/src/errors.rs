@@ -10,6 +10,25 @@ pub enum StoreError {
 
     #[error("Field not initilized")]
     FieldNotInitilized,
+
+    #[error("Failed to decode stored value, data corruption? {0}")]
+    Decode(String),
+
+    #[error(
+        "Database schema version {found} is newer than version {supported} supported by this release, upgrade or use a new data directory"
+    )]
+    SchemaVersionTooNew { found: u64, supported: u64 },
+
+    #[error("No migration from database schema version {0}")]
+    MissingMigration(u64),
+
+    #[error(
+        "Failed to upgrade the database written before the schema version was recorded, remove the data directory and resync: {0}"
+    )]
+    LegacyUpgrade(String),
+
+    #[error("Database holds data but no schema version, remove the data directory and resync")]
+    UnversionedDatabase,
 }
 
 impl From<redb::Error> for StoreError {
@@ -0,0 +1,237 @@
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use ream_consensus::{
+    electra::beacon_state::BeaconState, execution_engine::mock_engine::MockExecutionEngine,
+};
+use tracing::info;
+
+use crate::{
+    db::ReamDB,
+    errors::StoreError,
+    tables::{
+        Field, Table, beacon_state::BEACON_STATE_TABLE, cold_beacon_state::COLD_BEACON_STATE_TABLE,
+        freezer_slot::FREEZER_SLOT_FIELD,
+    },
+};
+
+impl ReamDB {
+    /// Moves the states of the finalized blocks before `finalized_root` out of the hot table.
+    /// Every `slots_per_state_snapshot` slots one of them is kept as a snapshot in the cold
+    /// store, the others are dropped and rebuilt from the snapshots when needed.
+    ///
+    /// The state of the finalized block itself stays hot, as fork choice is anchored on it.
+    pub fn migrate_to_cold(&self, finalized_root: B256) -> Result<(), StoreError> {
+        let beacon_block_provider = self.beacon_block_provider();
+        let Some(finalized_block) = beacon_block_provider.get(finalized_root)? else {
+            return Ok(());
+        };
+        let finalized_slot = finalized_block.message.slot;
+        let freezer_slot = match self.freezer_slot_provider().get() {
+            Ok(slot) => Some(slot),
+            Err(StoreError::FieldNotInitilized) => None,
+            Err(err) => return Err(err),
+        };
+        if freezer_slot.is_some_and(|freezer_slot| freezer_slot >= finalized_slot) {
+            return Ok(());
+        }
+
+        // Walk the finalized chain back to the previous migration
+        let mut finalized_blocks = vec![];
+        let mut block_root = finalized_block.message.parent_root;
+        while let Some(block) = beacon_block_provider.get(block_root)? {
+            if freezer_slot.is_some_and(|freezer_slot| block.message.slot < freezer_slot) {
+                break;
+            }
+            finalized_blocks.push((block.message.slot, block_root));
+            block_root = block.message.parent_root;
+        }
+
+        let mut last_snapshot_slot = self.cold_beacon_state_provider().get_highest_slot()?;
+        let mut ops = vec![];
+        for (slot, block_root) in finalized_blocks.into_iter().rev() {
+            if last_snapshot_slot.is_none_or(|last_snapshot_slot| {
+                slot / self.slots_per_state_snapshot
+                    > last_snapshot_slot / self.slots_per_state_snapshot
+            }) {
+                if let Some(state) = BEACON_STATE_TABLE.get(&*self.db, &block_root)? {
+                    ops.push(COLD_BEACON_STATE_TABLE.insert_op(&slot, &state));
+                    last_snapshot_slot = Some(slot);
+                }
+            }
+            ops.push(BEACON_STATE_TABLE.remove_op(&block_root));
+        }
+        ops.push(FREEZER_SLOT_FIELD.insert_op(&finalized_slot));
+        self.db.write(ops)?;
+
+        info!("Migrated finalized states up to slot {finalized_slot} to the cold store");
+        Ok(())
+    }
+
+    /// Returns the post-state of `block_root`. States which were migrated to the cold store are
+    /// rebuilt by replaying the blocks since the closest snapshot before them.
+    pub async fn get_state(&self, block_root: B256) -> anyhow::Result<Option<BeaconState>> {
+        if let Some(state) = self.beacon_state_provider().get(block_root)? {
+            return Ok(Some(state));
+        }
+
+        let beacon_block_provider = self.beacon_block_provider();
+        let Some(mut block) = beacon_block_provider.get(block_root)? else {
+            return Ok(None);
+        };
+        let Some(mut state) = self
+            .cold_beacon_state_provider()
+            .get_at_or_before(block.message.slot)?
+        else {
+            return Ok(None);
+        };
+
+        let mut blocks = vec![];
+        while block.message.slot > state.slot {
+            let parent_root = block.message.parent_root;
+            blocks.push(block);
+            block = beacon_block_provider.get(parent_root)?.ok_or_else(|| {
+                anyhow!(
+                    "Missing block {parent_root} to replay from the snapshot at slot {}",
+                    state.slot
+                )
+            })?;
+        }
+        ensure!(
+            block.message.slot == state.slot
+                && block.message.parent_root == state.latest_block_header.parent_root,
+            "Block {block_root} doesn't descend from the snapshot at slot {}",
+            state.slot
+        );
+
+        // The blocks were verified when they were imported, so neither signatures nor execution
+        // payloads are checked again
+        for block in blocks.iter().rev() {
+            state
+                .state_transition(block, false, &None::<MockExecutionEngine>)
+                .await?;
+        }
+        Ok(Some(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use alloy_primitives::B256;
+
+    use crate::{
+        backend::in_memory::InMemoryBackend,
+        db::ReamDB,
+        tables::{Field, Table},
+        test_utils::{import_test_chain, import_valid_test_chain, test_block},
+    };
+
+    fn cold_slots(db: &ReamDB) -> Vec<u64> {
+        (0..16)
+            .filter(|slot| {
+                db.cold_beacon_state_provider()
+                    .get(*slot)
+                    .unwrap()
+                    .is_some()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_migrate_to_cold() {
+        let db = ReamDB::from_backend(Arc::new(InMemoryBackend::default()), 4);
+        let roots = import_test_chain(&db, B256::ZERO, 0..=10);
+
+        db.migrate_to_cold(roots[10]).unwrap();
+        assert_eq!(cold_slots(&db), vec![0, 4, 8]);
+        assert_eq!(db.freezer_slot_provider().get().unwrap(), 10);
+        for root in &roots[..10] {
+            assert!(db.beacon_state_provider().get(*root).unwrap().is_none());
+        }
+        assert!(db.beacon_state_provider().get(roots[10]).unwrap().is_some());
+
+        // The next migration continues from the previous one, snapshotting the first state of
+        // every new interval
+        let new_roots = import_test_chain(&db, roots[10], 11..=13);
+        let roots = [roots, new_roots].concat();
+        db.migrate_to_cold(roots[13]).unwrap();
+        assert_eq!(cold_slots(&db), vec![0, 4, 8, 12]);
+        assert_eq!(db.freezer_slot_provider().get().unwrap(), 13);
+        for root in &roots[10..13] {
+            assert!(db.beacon_state_provider().get(*root).unwrap().is_none());
+        }
+
+        // Migrating to an older finalized block does nothing
+        db.migrate_to_cold(roots[12]).unwrap();
+        assert_eq!(db.freezer_slot_provider().get().unwrap(), 13);
+    }
+
+    #[tokio::test]
+    async fn test_get_state() {
+        let db = ReamDB::from_backend(Arc::new(InMemoryBackend::default()), 4);
+        let roots = import_test_chain(&db, B256::ZERO, 0..=10);
+        db.migrate_to_cold(roots[10]).unwrap();
+
+        // Hot states are returned as they are, snapshots without replaying anything
+        assert_eq!(db.get_state(roots[10]).await.unwrap().unwrap().slot, 10);
+        let snapshot = db.get_state(roots[8]).await.unwrap().unwrap();
+        assert_eq!(snapshot.slot, 8);
+        assert_eq!(snapshot.latest_block_header.parent_root, roots[7]);
+
+        // Unknown blocks have no state
+        assert!(db.get_state(B256::repeat_byte(1)).await.unwrap().is_none());
+
+        // Blocks are only replayed from a snapshot they descend from, which a fork off slot 7
+        // doesn't
+        let fork_block = test_block(9, roots[7]);
+        let fork_root = fork_block.message.block_root();
+        db.beacon_block_provider()
+            .insert(fork_root, fork_block)
+            .unwrap();
+        let err = db.get_state(fork_root).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("doesn't descend from the snapshot at slot 8")
+        );
+
+        // The replay walks back to the snapshot through the stored blocks
+        let orphan_block = test_block(11, B256::repeat_byte(2));
+        let orphan_root = orphan_block.message.block_root();
+        db.beacon_block_provider()
+            .insert(orphan_root, orphan_block)
+            .unwrap();
+        let err = db.get_state(orphan_root).await.unwrap_err();
+        assert!(err.to_string().contains("Missing block"));
+    }
+
+    #[tokio::test]
+    async fn test_get_state_replays_blocks() {
+        let db = ReamDB::from_backend(Arc::new(InMemoryBackend::default()), 4);
+        let roots = import_valid_test_chain(&db, 6).await;
+        let states: Vec<_> = roots
+            .iter()
+            .map(|root| db.beacon_state_provider().get(*root).unwrap().unwrap())
+            .collect();
+        db.migrate_to_cold(roots[6]).unwrap();
+        assert_eq!(cold_slots(&db), vec![0, 4]);
+        assert_eq!(
+            db.cold_beacon_state_provider().get_highest_slot().unwrap(),
+            Some(4)
+        );
+
+        // States between two snapshots are rebuilt from the earlier one
+        for slot in [1, 3, 5] {
+            assert!(
+                db.beacon_state_provider()
+                    .get(roots[slot])
+                    .unwrap()
+                    .is_none()
+            );
+            assert_eq!(
+                db.get_state(roots[slot]).await.unwrap().as_ref(),
+                Some(&states[slot])
+            );
+        }
+    }
+}
@@ -1,4 +1,11 @@
+pub mod backend;
 pub mod db;
 pub mod dir;
 pub mod errors;
+pub mod freezer;
+pub mod migrations;
+pub mod pruning;
 pub mod tables;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod write_batch;
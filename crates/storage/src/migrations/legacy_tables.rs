@@ -0,0 +1,409 @@
+//! Upgrade of databases written before the schema version was recorded (version 0), whose redb
+//! tables were typed, to the raw byte tables of version 1.
+
+use std::{any::type_name, cmp::Ordering, fmt::Debug, marker::PhantomData};
+
+use alloy_primitives::B256;
+use ream_consensus::{
+    blob_sidecar::BlobIdentifier,
+    checkpoint::Checkpoint,
+    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+    execution_engine::rpc_types::get_blobs::BlobAndProofV1,
+    fork_choice::latest_message::LatestMessage,
+};
+use redb::{
+    Database, Durability, Key, MultimapTableDefinition, ReadableMultimapTable, ReadableTable,
+    TableDefinition, TableError, TableHandle, TypeName, Value, WriteTransaction,
+};
+use ssz::Decode;
+use tracing::info;
+
+use crate::{
+    backend::WriteOp,
+    errors::StoreError,
+    tables::{Encoding, SSZEncoding, schema_version::SCHEMA_VERSION_FIELD},
+};
+
+/// The schema version the upgraded tables are in
+const UPGRADED_SCHEMA_VERSION: u64 = 1;
+
+type RawTable<'a> = TableDefinition<'a, &'static [u8], &'static [u8]>;
+
+type RawMultimapTable<'a> = MultimapTableDefinition<'a, &'static [u8], &'static [u8]>;
+
+/// The SSZ encoded keys and values of version 0, read as the bytes they were stored as. The type
+/// name matches the one the tables were created with.
+#[derive(Debug)]
+struct LegacySSZ<T>(PhantomData<T>);
+
+impl<T> Value for LegacySSZ<T>
+where
+    T: Debug,
+{
+    type SelfType<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
+
+    type AsBytes<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
+
+    fn fixed_width() -> Option<usize> {
+        None
+    }
+
+    fn from_bytes<'a>(data: &'a [u8]) -> Self::SelfType<'a>
+    where
+        Self: 'a,
+    {
+        data
+    }
+
+    fn as_bytes<'a, 'b: 'a>(value: &'a Self::SelfType<'b>) -> Self::AsBytes<'a>
+    where
+        Self: 'a,
+        Self: 'b,
+    {
+        value
+    }
+
+    fn type_name() -> TypeName {
+        TypeName::new(&format!("SSZEncoding<{}>", type_name::<T>()))
+    }
+}
+
+impl<T> Key for LegacySSZ<T>
+where
+    T: Debug + Decode + Ord,
+{
+    fn compare(data1: &[u8], data2: &[u8]) -> Ordering {
+        T::from_ssz_bytes(data1)
+            .ok()
+            .cmp(&T::from_ssz_bytes(data2).ok())
+    }
+}
+
+const BEACON_BLOCK_TABLE: TableDefinition<LegacySSZ<B256>, LegacySSZ<SignedBeaconBlock>> =
+    TableDefinition::new("beacon_block");
+
+const BEACON_STATE_TABLE: TableDefinition<LegacySSZ<B256>, LegacySSZ<BeaconState>> =
+    TableDefinition::new("beacon_state");
+
+const BLOBS_AND_PROOFS_TABLE: TableDefinition<
+    LegacySSZ<BlobIdentifier>,
+    LegacySSZ<BlobAndProofV1>,
+> = TableDefinition::new("blobs_and_proofs");
+
+const BLOCK_TIMELINESS_TABLE: TableDefinition<LegacySSZ<B256>, LegacySSZ<bool>> =
+    TableDefinition::new("block_timeliness");
+
+const CHECKPOINT_STATES_TABLE: TableDefinition<LegacySSZ<Checkpoint>, LegacySSZ<BeaconState>> =
+    TableDefinition::new("checkpoint_states");
+
+const EQUIVOCATING_INDICES_FIELD: TableDefinition<&str, Vec<u64>> =
+    TableDefinition::new("equivocating_indices");
+
+const FINALIZED_CHECKPOINT_FIELD: TableDefinition<&str, LegacySSZ<Checkpoint>> =
+    TableDefinition::new("finalized_checkpoint");
+
+const GENESIS_TIME_FIELD: TableDefinition<&str, u64> = TableDefinition::new("genesis_time");
+
+const JUSTIFIED_CHECKPOINT_FIELD: TableDefinition<&str, LegacySSZ<Checkpoint>> =
+    TableDefinition::new("justified_checkpoint");
+
+const LATEST_MESSAGES_TABLE: TableDefinition<u64, LegacySSZ<LatestMessage>> =
+    TableDefinition::new("latest_messages");
+
+const PARENT_ROOT_INDEX_MULTIMAP_TABLE: MultimapTableDefinition<LegacySSZ<B256>, LegacySSZ<B256>> =
+    MultimapTableDefinition::new("parent_root_index_multimap");
+
+const PROPOSER_BOOST_ROOT_FIELD: TableDefinition<&str, LegacySSZ<B256>> =
+    TableDefinition::new("proposer_boost_root");
+
+const SLOT_INDEX_TABLE: TableDefinition<u64, LegacySSZ<B256>> = TableDefinition::new("slot_index");
+
+const STATE_ROOT_INDEX_TABLE: TableDefinition<LegacySSZ<B256>, LegacySSZ<B256>> =
+    TableDefinition::new("state_root_index");
+
+const TIME_FIELD: TableDefinition<&str, u64> = TableDefinition::new("time");
+
+const UNREALIZED_FINALIZED_CHECKPOINT_FIELD: TableDefinition<&str, LegacySSZ<Checkpoint>> =
+    TableDefinition::new("unrealized_finalized_checkpoint");
+
+const UNREALIZED_JUSTIFICATIONS_TABLE: TableDefinition<LegacySSZ<B256>, LegacySSZ<Checkpoint>> =
+    TableDefinition::new("unrealized_justifications");
+
+const UNREALIZED_JUSTIFIED_CHECKPOINT_FIELD: TableDefinition<&str, LegacySSZ<Checkpoint>> =
+    TableDefinition::new("unrealized_justified_checkpoint");
+
+/// Keys and values stored as the same bytes in both versions
+fn unchanged(bytes: &[u8]) -> Result<Vec<u8>, StoreError> {
+    Ok(bytes.to_vec())
+}
+
+/// Native redb integers are little-endian, version 1 stores them big-endian
+fn native_u64(bytes: &[u8]) -> Result<Vec<u8>, StoreError> {
+    let bytes: [u8; 8] = bytes
+        .try_into()
+        .map_err(|_| StoreError::Decode(format!("Invalid u64 length {}", bytes.len())))?;
+    Ok(<u64 as Encoding>::encode(&u64::from_le_bytes(bytes)))
+}
+
+fn native_u64_list(bytes: &[u8]) -> Result<Vec<u8>, StoreError> {
+    Ok(SSZEncoding::<Vec<u64>>::encode(
+        &<Vec<u64> as Value>::from_bytes(bytes),
+    ))
+}
+
+/// Rewrites the tables of a version 0 database in the layout of version 1, in a single
+/// transaction. Returns whether there was anything to upgrade.
+///
+/// On failure nothing is written, and the database can only be used again after a resync.
+pub fn upgrade_legacy_tables(db: &Database) -> Result<bool, StoreError> {
+    // Every table was created on startup, so the block table tells the layouts apart
+    match db
+        .begin_read()?
+        .open_table(RawTable::new(BEACON_BLOCK_TABLE.name()))
+    {
+        Err(TableError::TableTypeMismatch { .. }) => {}
+        Ok(_) | Err(TableError::TableDoesNotExist(_)) => return Ok(false),
+        Err(err) => return Err(err.into()),
+    }
+
+    info!("Upgrading the database tables from before the schema version was recorded");
+    upgrade(db).map_err(|err| StoreError::LegacyUpgrade(err.to_string()))?;
+    Ok(true)
+}
+
+fn upgrade(db: &Database) -> Result<(), StoreError> {
+    let mut write_txn = db.begin_write()?;
+    write_txn.set_durability(Durability::Immediate);
+
+    upgrade_table(&write_txn, BEACON_BLOCK_TABLE, unchanged, unchanged)?;
+    upgrade_table(&write_txn, BEACON_STATE_TABLE, unchanged, unchanged)?;
+    upgrade_table(&write_txn, BLOBS_AND_PROOFS_TABLE, unchanged, unchanged)?;
+    upgrade_table(&write_txn, BLOCK_TIMELINESS_TABLE, unchanged, unchanged)?;
+    upgrade_table(&write_txn, CHECKPOINT_STATES_TABLE, unchanged, unchanged)?;
+    upgrade_table(
+        &write_txn,
+        EQUIVOCATING_INDICES_FIELD,
+        unchanged,
+        native_u64_list,
+    )?;
+    upgrade_table(&write_txn, FINALIZED_CHECKPOINT_FIELD, unchanged, unchanged)?;
+    upgrade_table(&write_txn, GENESIS_TIME_FIELD, unchanged, native_u64)?;
+    upgrade_table(&write_txn, JUSTIFIED_CHECKPOINT_FIELD, unchanged, unchanged)?;
+    upgrade_table(&write_txn, LATEST_MESSAGES_TABLE, native_u64, unchanged)?;
+    upgrade_multimap_table(&write_txn, PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
+    upgrade_table(&write_txn, PROPOSER_BOOST_ROOT_FIELD, unchanged, unchanged)?;
+    upgrade_table(&write_txn, SLOT_INDEX_TABLE, native_u64, unchanged)?;
+    upgrade_table(&write_txn, STATE_ROOT_INDEX_TABLE, unchanged, unchanged)?;
+    upgrade_table(&write_txn, TIME_FIELD, unchanged, native_u64)?;
+    upgrade_table(
+        &write_txn,
+        UNREALIZED_FINALIZED_CHECKPOINT_FIELD,
+        unchanged,
+        unchanged,
+    )?;
+    upgrade_table(
+        &write_txn,
+        UNREALIZED_JUSTIFICATIONS_TABLE,
+        unchanged,
+        unchanged,
+    )?;
+    upgrade_table(
+        &write_txn,
+        UNREALIZED_JUSTIFIED_CHECKPOINT_FIELD,
+        unchanged,
+        unchanged,
+    )?;
+
+    if let WriteOp::Put { table, key, value } =
+        SCHEMA_VERSION_FIELD.insert_op(&UPGRADED_SCHEMA_VERSION)
+    {
+        write_txn
+            .open_table(RawTable::new(table))?
+            .insert(key.as_slice(), value.as_slice())?;
+    }
+    write_txn.commit()?;
+    Ok(())
+}
+
+/// Copies `legacy` into a raw staging table, converting its entries, and puts the staging table
+/// in its place. A table can't be open with two types at once, so it goes through a copy.
+fn upgrade_table<K: Key + 'static, V: Value + 'static>(
+    write_txn: &WriteTransaction,
+    legacy: TableDefinition<K, V>,
+    convert_key: fn(&[u8]) -> Result<Vec<u8>, StoreError>,
+    convert_value: fn(&[u8]) -> Result<Vec<u8>, StoreError>,
+) -> Result<(), StoreError> {
+    let staging_name = format!("{}_upgrade", legacy.name());
+    {
+        let legacy_table = write_txn.open_table(legacy)?;
+        let mut staging_table = write_txn.open_table(RawTable::new(&staging_name))?;
+        for entry in legacy_table.iter()? {
+            let (key, value) = entry?;
+            let key = convert_key(K::as_bytes(&key.value()).as_ref())?;
+            let value = convert_value(V::as_bytes(&value.value()).as_ref())?;
+            staging_table.insert(key.as_slice(), value.as_slice())?;
+        }
+    }
+    write_txn.delete_table(legacy)?;
+    write_txn.rename_table(RawTable::new(&staging_name), RawTable::new(legacy.name()))?;
+    Ok(())
+}
+
+/// Like [`upgrade_table`], for the parent root index, whose keys and values are unchanged.
+fn upgrade_multimap_table<K: Key + 'static, V: Key + 'static>(
+    write_txn: &WriteTransaction,
+    legacy: MultimapTableDefinition<K, V>,
+) -> Result<(), StoreError> {
+    let staging_name = format!("{}_upgrade", legacy.name());
+    {
+        let legacy_table = write_txn.open_multimap_table(legacy)?;
+        let mut staging_table =
+            write_txn.open_multimap_table(RawMultimapTable::new(&staging_name))?;
+        for entry in legacy_table.iter()? {
+            let (key, values) = entry?;
+            for value in values {
+                staging_table.insert(
+                    K::as_bytes(&key.value()).as_ref(),
+                    V::as_bytes(&value?.value()).as_ref(),
+                )?;
+            }
+        }
+    }
+    write_txn.delete_multimap_table(legacy)?;
+    write_txn.rename_multimap_table(
+        RawMultimapTable::new(&staging_name),
+        RawMultimapTable::new(legacy.name()),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus::{checkpoint::Checkpoint, fork_choice::latest_message::LatestMessage};
+    use ssz::Encode;
+
+    use super::*;
+    use crate::{
+        db::{DEFAULT_SLOTS_PER_STATE_SNAPSHOT, REDB_FILE, ReamDB},
+        migrations::CURRENT_SCHEMA_VERSION,
+        tables::{
+            Field, MultimapTable, Table, equivocating_indices::EQUIVOCATING_INDICES_KEY,
+            finalized_checkpoint::FINALIZED_CHECKPOINT_FIELD_KEY, genesis_time::GENESIS_TIME_KEY,
+        },
+        test_utils::test_block,
+    };
+
+    #[test]
+    fn test_upgrade_legacy_tables() {
+        let ream_dir = tempfile::tempdir().unwrap();
+        let block = test_block(5, B256::repeat_byte(1));
+        let block_root = block.message.block_root();
+        let checkpoint = Checkpoint {
+            epoch: 1,
+            root: block_root,
+        };
+        let latest_message = LatestMessage {
+            epoch: 1,
+            root: block_root,
+        };
+
+        // A database written with the typed tables of version 0
+        {
+            let db = Database::create(ream_dir.path().join(REDB_FILE)).unwrap();
+            let write_txn = db.begin_write().unwrap();
+            write_txn
+                .open_table(BEACON_BLOCK_TABLE)
+                .unwrap()
+                .insert(block_root.as_slice(), block.as_ssz_bytes().as_slice())
+                .unwrap();
+            write_txn
+                .open_table(SLOT_INDEX_TABLE)
+                .unwrap()
+                .insert(5, block_root.as_slice())
+                .unwrap();
+            write_txn
+                .open_table(LATEST_MESSAGES_TABLE)
+                .unwrap()
+                .insert(3, latest_message.as_ssz_bytes().as_slice())
+                .unwrap();
+            write_txn
+                .open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)
+                .unwrap()
+                .insert(block.message.parent_root.as_slice(), block_root.as_slice())
+                .unwrap();
+            write_txn
+                .open_table(GENESIS_TIME_FIELD)
+                .unwrap()
+                .insert(GENESIS_TIME_KEY, 1_606_824_023)
+                .unwrap();
+            write_txn
+                .open_table(EQUIVOCATING_INDICES_FIELD)
+                .unwrap()
+                .insert(EQUIVOCATING_INDICES_KEY, vec![2, 7])
+                .unwrap();
+            write_txn
+                .open_table(FINALIZED_CHECKPOINT_FIELD)
+                .unwrap()
+                .insert(
+                    FINALIZED_CHECKPOINT_FIELD_KEY,
+                    checkpoint.as_ssz_bytes().as_slice(),
+                )
+                .unwrap();
+            write_txn.commit().unwrap();
+        }
+
+        let db = ReamDB::new(
+            ream_dir.path().to_path_buf(),
+            DEFAULT_SLOTS_PER_STATE_SNAPSHOT,
+        )
+        .unwrap();
+        assert_eq!(
+            db.schema_version_provider().get().unwrap(),
+            CURRENT_SCHEMA_VERSION
+        );
+        assert_eq!(
+            db.beacon_block_provider().get(block_root).unwrap(),
+            Some(block.clone())
+        );
+        assert_eq!(db.slot_index_provider().get(5).unwrap(), Some(block_root));
+        assert_eq!(db.slot_index_provider().get_oldest_slot().unwrap(), Some(5));
+        assert_eq!(
+            db.latest_messages_provider().get(3).unwrap(),
+            Some(latest_message)
+        );
+        assert_eq!(
+            db.parent_root_index_multimap_provider()
+                .get(block.message.parent_root)
+                .unwrap(),
+            Some(vec![block_root])
+        );
+        assert_eq!(db.genesis_time_provider().get().unwrap(), 1_606_824_023);
+        let mut equivocating_indices: Vec<u64> = db
+            .equivocating_indices_provider()
+            .get()
+            .unwrap()
+            .into_iter()
+            .collect();
+        equivocating_indices.sort();
+        assert_eq!(equivocating_indices, vec![2, 7]);
+        assert_eq!(
+            db.finalized_checkpoint_provider().get().unwrap(),
+            checkpoint
+        );
+
+        // Upgraded databases open as they are
+        drop(db);
+        let db = ReamDB::new(
+            ream_dir.path().to_path_buf(),
+            DEFAULT_SLOTS_PER_STATE_SNAPSHOT,
+        )
+        .unwrap();
+        assert_eq!(db.slot_index_provider().get(5).unwrap(), Some(block_root));
+    }
+}
@@ -0,0 +1,137 @@
+pub mod legacy_tables;
+
+use tracing::info;
+
+use crate::{db::ReamDB, errors::StoreError, tables::Field};
+
+/// The schema version of databases written by this release
+pub const CURRENT_SCHEMA_VERSION: u64 = 1;
+
+/// A step upgrading the database schema from `from` to `from + 1`.
+pub struct Migration {
+    pub from: u64,
+    pub description: &'static str,
+    pub migrate: fn(&ReamDB) -> Result<(), StoreError>,
+}
+
+/// The migrations run on startup to bring older databases to [`CURRENT_SCHEMA_VERSION`]. Adding
+/// a migration means bumping the version.
+///
+/// Version 0, the typed redb tables from before the schema version was recorded, can't be read
+/// through the backend. It is upgraded to version 1 by
+/// [`legacy_tables::upgrade_legacy_tables`] when the redb file is opened, before these run.
+pub const MIGRATIONS: &[Migration] = &[];
+
+impl ReamDB {
+    /// Upgrades the database to [`CURRENT_SCHEMA_VERSION`]. Fails if it was written by a newer
+    /// release.
+    pub fn migrate_schema(&self) -> Result<(), StoreError> {
+        self.migrate_schema_to(CURRENT_SCHEMA_VERSION, MIGRATIONS)
+    }
+
+    fn migrate_schema_to(
+        &self,
+        target_version: u64,
+        migrations: &[Migration],
+    ) -> Result<(), StoreError> {
+        let mut version = match self.schema_version_provider().get() {
+            Ok(version) => version,
+            Err(StoreError::FieldNotInitilized) => {
+                if self.slot_index_provider().get_oldest_slot()?.is_none() {
+                    // A new database
+                    return self.schema_version_provider().insert(target_version);
+                }
+                // Databases from before the schema version was recorded (version 0) are stamped
+                // with version 1 when their tables are upgraded on opening, so the layout of this
+                // one is unknown
+                return Err(StoreError::UnversionedDatabase);
+            }
+            Err(err) => return Err(err),
+        };
+        if version > target_version {
+            return Err(StoreError::SchemaVersionTooNew {
+                found: version,
+                supported: target_version,
+            });
+        }
+
+        while version < target_version {
+            let migration = migrations
+                .iter()
+                .find(|migration| migration.from == version)
+                .ok_or(StoreError::MissingMigration(version))?;
+            info!(
+                "Migrating database schema from version {version} to {}: {}",
+                version + 1,
+                migration.description
+            );
+
+            // Each step is committed together with the version it upgrades to, so an interrupted
+            // migration restarts from the last completed step
+            let batch = self.write_batch();
+            (migration.migrate)(batch.db())?;
+            batch.db().schema_version_provider().insert(version + 1)?;
+            batch.commit()?;
+            version += 1;
+        }
+
+        if self.schema_version_provider().get().is_err() {
+            self.schema_version_provider().insert(version)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use super::*;
+    use crate::tables::Table;
+
+    fn double_genesis_time(db: &ReamDB) -> Result<(), StoreError> {
+        let genesis_time = db.genesis_time_provider().get()?;
+        db.genesis_time_provider().insert(genesis_time * 2)
+    }
+
+    #[test]
+    fn test_migrate_schema() {
+        let migrations = [Migration {
+            from: 1,
+            description: "Double the genesis time",
+            migrate: double_genesis_time,
+        }];
+
+        // A new database is stamped with the target version without migrating
+        let db = ReamDB::in_memory();
+        db.migrate_schema_to(2, &migrations).unwrap();
+        assert_eq!(db.schema_version_provider().get().unwrap(), 2);
+
+        let db = ReamDB::in_memory();
+        db.slot_index_provider().insert(0, B256::ZERO).unwrap();
+        db.genesis_time_provider().insert(10).unwrap();
+        db.schema_version_provider().insert(1).unwrap();
+        db.migrate_schema_to(2, &migrations).unwrap();
+        assert_eq!(db.genesis_time_provider().get().unwrap(), 20);
+        assert_eq!(db.schema_version_provider().get().unwrap(), 2);
+
+        assert!(matches!(
+            db.migrate_schema_to(1, &migrations),
+            Err(StoreError::SchemaVersionTooNew {
+                found: 2,
+                supported: 1
+            })
+        ));
+        assert!(matches!(
+            db.migrate_schema_to(3, &migrations),
+            Err(StoreError::MissingMigration(2))
+        ));
+
+        let db = ReamDB::in_memory();
+        db.slot_index_provider().insert(0, B256::ZERO).unwrap();
+        assert!(matches!(
+            db.migrate_schema_to(2, &migrations),
+            Err(StoreError::UnversionedDatabase)
+        ));
+    }
+}
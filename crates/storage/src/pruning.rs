@@ -0,0 +1,306 @@
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::B256;
+use ream_consensus::{blob_sidecar::BlobIdentifier, checkpoint::Checkpoint};
+use tracing::info;
+
+use crate::{
+    db::ReamDB,
+    errors::StoreError,
+    tables::{
+        MultimapTable, Table, beacon_block::BEACON_BLOCK_TABLE, beacon_state::BEACON_STATE_TABLE,
+        blobs_and_proofs::BLOBS_AND_PROOFS_TABLE, block_timeliness::BLOCK_TIMELINESS_TABLE,
+        checkpoint_states::CHECKPOINT_STATES_TABLE,
+        parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE, slot_index::SLOT_INDEX_TABLE,
+        state_root_index::STATE_ROOT_INDEX_TABLE,
+        unrealized_justifications::UNREALIZED_JUSTIFICATIONS_TABLE,
+    },
+};
+
+/// What a call to [`ReamDB::prune_abandoned_forks`] removed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Roots of the removed blocks, which don't descend from the finalized checkpoint
+    pub pruned_blocks: Vec<B256>,
+    pub pruned_states: usize,
+    /// Checkpoint states of pruned blocks or from before the finalized epoch
+    pub pruned_checkpoint_states: usize,
+}
+
+/// The fields of a pruned block needed to remove its index entries.
+struct PrunedBlock {
+    root: B256,
+    parent_root: B256,
+    slot: u64,
+    state_root: B256,
+    blob_count: u64,
+}
+
+impl ReamDB {
+    /// Removes the blocks which branch off the finalized chain between `previous_finalized` and
+    /// `finalized`, along with their descendants, states and index entries, in a single atomic
+    /// write. Checkpoint states from before the finalized epoch are dropped as well.
+    ///
+    /// Forks branching off before `previous_finalized` were removed by the previous call.
+    pub fn prune_abandoned_forks(
+        &self,
+        previous_finalized: Checkpoint,
+        finalized: Checkpoint,
+    ) -> Result<PruneReport, StoreError> {
+        let beacon_block_provider = self.beacon_block_provider();
+        let parent_root_index_provider = self.parent_root_index_multimap_provider();
+        let Some(previous_finalized_block) = beacon_block_provider.get(previous_finalized.root)?
+        else {
+            return Ok(PruneReport::default());
+        };
+
+        // The finalized chain between the two checkpoints, by slot
+        let mut canonical_blocks = HashMap::new();
+        let mut block_root = finalized.root;
+        while let Some(block) = beacon_block_provider.get(block_root)? {
+            if block.message.slot <= previous_finalized_block.message.slot {
+                break;
+            }
+            canonical_blocks.insert(block.message.slot, block_root);
+            block_root = block.message.parent_root;
+        }
+        let canonical_roots: HashSet<B256> = canonical_blocks.values().copied().collect();
+
+        // Children of the finalized chain which aren't on it are abandoned, so are their
+        // descendants
+        let mut pruned_blocks = vec![];
+        let mut canonical_queue = vec![previous_finalized.root];
+        let mut abandoned_queue = vec![];
+        while let Some(block_root) = canonical_queue.pop() {
+            if block_root == finalized.root {
+                continue;
+            }
+            for child_root in parent_root_index_provider
+                .get(block_root)?
+                .unwrap_or_default()
+            {
+                if canonical_roots.contains(&child_root) {
+                    canonical_queue.push(child_root);
+                } else {
+                    abandoned_queue.push(child_root);
+                }
+            }
+        }
+        while let Some(block_root) = abandoned_queue.pop() {
+            abandoned_queue.extend(
+                parent_root_index_provider
+                    .get(block_root)?
+                    .unwrap_or_default(),
+            );
+            let Some(block) = beacon_block_provider.get(block_root)? else {
+                continue;
+            };
+            pruned_blocks.push(PrunedBlock {
+                root: block_root,
+                parent_root: block.message.parent_root,
+                slot: block.message.slot,
+                state_root: block.message.state_root,
+                blob_count: block.message.body.blob_kzg_commitments.len() as u64,
+            });
+        }
+        let pruned_roots: HashSet<B256> = pruned_blocks.iter().map(|block| block.root).collect();
+
+        let finalized_slot = beacon_block_provider
+            .get(finalized.root)?
+            .map_or(0, |block| block.message.slot);
+        let mut descendant_blocks = None;
+        let mut report = PruneReport::default();
+        let mut ops = vec![];
+        for block in &pruned_blocks {
+            ops.push(BEACON_BLOCK_TABLE.remove_op(&block.root));
+            if BEACON_STATE_TABLE.contains_key(&*self.db, &block.root)? {
+                ops.push(BEACON_STATE_TABLE.remove_op(&block.root));
+                report.pruned_states += 1;
+            }
+            for index in 0..block.blob_count {
+                ops.push(BLOBS_AND_PROOFS_TABLE.remove_op(&BlobIdentifier::new(block.root, index)));
+            }
+            ops.push(BLOCK_TIMELINESS_TABLE.remove_op(&block.root));
+            ops.push(UNREALIZED_JUSTIFICATIONS_TABLE.remove_op(&block.root));
+            ops.push(STATE_ROOT_INDEX_TABLE.remove_op(&block.state_root));
+            ops.push(PARENT_ROOT_INDEX_MULTIMAP_TABLE.remove_op(&block.parent_root, &block.root));
+            ops.push(PARENT_ROOT_INDEX_MULTIMAP_TABLE.remove_all_op(&block.root));
+
+            // The slot index holds the last block imported at a slot, which may have been the
+            // pruned one. Past the finalized block, the slot may still hold one of its descendants.
+            if SLOT_INDEX_TABLE.get(&*self.db, &block.slot)? == Some(block.root) {
+                if block.slot > finalized_slot && descendant_blocks.is_none() {
+                    descendant_blocks = Some(self.descendant_blocks(finalized.root)?);
+                }
+                let remaining_root = canonical_blocks.get(&block.slot).or_else(|| {
+                    descendant_blocks
+                        .as_ref()
+                        .and_then(|descendant_blocks| descendant_blocks.get(&block.slot))
+                });
+                ops.push(match remaining_root {
+                    Some(remaining_root) => SLOT_INDEX_TABLE.insert_op(&block.slot, remaining_root),
+                    None => SLOT_INDEX_TABLE.remove_op(&block.slot),
+                });
+            }
+        }
+
+        for checkpoint in CHECKPOINT_STATES_TABLE.keys(&*self.db)? {
+            if checkpoint.epoch < finalized.epoch || pruned_roots.contains(&checkpoint.root) {
+                ops.push(CHECKPOINT_STATES_TABLE.remove_op(&checkpoint));
+                report.pruned_checkpoint_states += 1;
+            }
+        }
+        self.db.write(ops)?;
+
+        report.pruned_blocks = pruned_blocks.into_iter().map(|block| block.root).collect();
+        info!(
+            "Pruned {} blocks, {} states and {} checkpoint states at finalized epoch {}",
+            report.pruned_blocks.len(),
+            report.pruned_states,
+            report.pruned_checkpoint_states,
+            finalized.epoch
+        );
+        Ok(report)
+    }
+
+    /// Returns the descendants of `root` by slot, one of them for slots holding several.
+    fn descendant_blocks(&self, root: B256) -> Result<HashMap<u64, B256>, StoreError> {
+        let beacon_block_provider = self.beacon_block_provider();
+        let parent_root_index_provider = self.parent_root_index_multimap_provider();
+        let mut descendant_blocks = HashMap::new();
+        let mut queue = vec![root];
+        while let Some(block_root) = queue.pop() {
+            for child_root in parent_root_index_provider
+                .get(block_root)?
+                .unwrap_or_default()
+            {
+                if let Some(child) = beacon_block_provider.get(child_root)? {
+                    descendant_blocks.insert(child.message.slot, child_root);
+                    queue.push(child_root);
+                }
+            }
+        }
+        Ok(descendant_blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_consensus::{checkpoint::Checkpoint, fork_choice::latest_message::LatestMessage};
+
+    use crate::{
+        db::ReamDB,
+        tables::{MultimapTable, Table},
+        test_utils::{import_test_chain, import_test_fork, test_state},
+    };
+
+    #[test]
+    fn test_prune_abandoned_forks() {
+        let db = ReamDB::in_memory();
+        let canonical_roots = import_test_chain(&db, B256::ZERO, 0..=8);
+        let previous_finalized = Checkpoint {
+            epoch: 0,
+            root: canonical_roots[0],
+        };
+        let finalized = Checkpoint {
+            epoch: 1,
+            root: canonical_roots[8],
+        };
+        // A fork branching off slot 2, imported last at its slots
+        let fork_roots = import_test_fork(&db, canonical_roots[2], 3..=4, B256::repeat_byte(1));
+        // A child of the finalized block, and a fork branching off slot 6 which reaches past it
+        let descendant_root = import_test_chain(&db, canonical_roots[8], 9..=9)[0];
+        let late_fork_roots =
+            import_test_fork(&db, canonical_roots[6], 9..=10, B256::repeat_byte(2));
+
+        let checkpoint_state = test_state(
+            &db.beacon_block_provider()
+                .get(finalized.root)
+                .unwrap()
+                .unwrap(),
+        );
+        let fork_checkpoint = Checkpoint {
+            epoch: 1,
+            root: fork_roots[1],
+        };
+        for checkpoint in [previous_finalized, finalized, fork_checkpoint] {
+            db.checkpoint_states_provider()
+                .insert(checkpoint, checkpoint_state.clone())
+                .unwrap();
+        }
+        let fork_vote = LatestMessage {
+            epoch: 0,
+            root: fork_roots[1],
+        };
+        db.latest_messages_provider()
+            .insert(0, fork_vote.clone())
+            .unwrap();
+
+        let report = db
+            .prune_abandoned_forks(previous_finalized, finalized)
+            .unwrap();
+
+        let mut pruned_blocks = report.pruned_blocks.clone();
+        pruned_blocks.sort();
+        let mut expected_blocks = [fork_roots.clone(), late_fork_roots.clone()].concat();
+        expected_blocks.sort();
+        assert_eq!(pruned_blocks, expected_blocks);
+        assert_eq!(report.pruned_states, 4);
+        for root in &expected_blocks {
+            assert!(db.beacon_block_provider().get(*root).unwrap().is_none());
+            assert!(db.beacon_state_provider().get(*root).unwrap().is_none());
+        }
+        for root in canonical_roots.iter().chain([&descendant_root]) {
+            assert!(db.beacon_block_provider().get(*root).unwrap().is_some());
+        }
+        assert_eq!(
+            db.parent_root_index_multimap_provider()
+                .get(canonical_roots[2])
+                .unwrap(),
+            Some(vec![canonical_roots[3]])
+        );
+
+        // Slots last taken by a pruned block point back at the remaining one, or at nothing
+        let slot_index_provider = db.slot_index_provider();
+        assert_eq!(
+            slot_index_provider.get(3).unwrap(),
+            Some(canonical_roots[3])
+        );
+        assert_eq!(
+            slot_index_provider.get(4).unwrap(),
+            Some(canonical_roots[4])
+        );
+        assert_eq!(slot_index_provider.get(9).unwrap(), Some(descendant_root));
+        assert_eq!(slot_index_provider.get(10).unwrap(), None);
+
+        // Checkpoint states before the finalized epoch or of pruned blocks are gone
+        assert_eq!(report.pruned_checkpoint_states, 2);
+        let checkpoint_states_provider = db.checkpoint_states_provider();
+        assert!(
+            checkpoint_states_provider
+                .get(previous_finalized)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            checkpoint_states_provider
+                .get(fork_checkpoint)
+                .unwrap()
+                .is_none()
+        );
+        assert!(checkpoint_states_provider.get(finalized).unwrap().is_some());
+
+        // Latest messages are kept, so older votes of their validators aren't accepted again
+        assert_eq!(
+            db.latest_messages_provider().get(0).unwrap(),
+            Some(fork_vote)
+        );
+
+        // Pruning again up to the same checkpoint finds nothing left
+        let report = db
+            .prune_abandoned_forks(previous_finalized, finalized)
+            .unwrap();
+        assert!(report.pruned_blocks.is_empty());
+    }
+}
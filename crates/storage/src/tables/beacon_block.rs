@@ -2,14 +2,12 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus::electra::beacon_block::SignedBeaconBlock;
-use redb::{Database, Durability, TableDefinition};
-use tree_hash::TreeHash;
 
 use super::{
-    MultimapTable, SSZEncoding, Table, parent_root_index::ParentRootIndexMultimapTable,
-    slot_index::SlotIndexTable, state_root_index::StateRootIndexTable,
+    SSZEncoding, Table, TableDefinition, parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE,
+    slot_index::SLOT_INDEX_TABLE, state_root_index::STATE_ROOT_INDEX_TABLE,
 };
-use crate::errors::StoreError;
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the Beacon Block table
 ///
@@ -19,7 +17,7 @@ pub const BEACON_BLOCK_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<Sig
     TableDefinition::new("beacon_block");
 
 pub struct BeaconBlockTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl Table for BeaconBlockTable {
@@ -28,37 +26,58 @@ impl Table for BeaconBlockTable {
     type Value = SignedBeaconBlock;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(BEACON_BLOCK_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        BEACON_BLOCK_TABLE.get(&*self.db, &key)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        // insert entry to slot_index table
-        let block_root = value.tree_hash_root();
-        let slot_index_table = SlotIndexTable {
-            db: self.db.clone(),
-        };
-        slot_index_table.insert(value.message.slot, block_root)?;
+        // The block is written together with its slot, state root and parent root index entries,
+        // which point at the root of the block message as blocks are keyed by it
+        let block_root = value.message.block_root();
+        self.db.write(vec![
+            SLOT_INDEX_TABLE.insert_op(&value.message.slot, &block_root),
+            STATE_ROOT_INDEX_TABLE.insert_op(&value.message.state_root, &block_root),
+            PARENT_ROOT_INDEX_MULTIMAP_TABLE.insert_op(&value.message.parent_root, &block_root),
+            BEACON_BLOCK_TABLE.insert_op(&key, &value),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use crate::{
+        db::ReamDB,
+        tables::{MultimapTable, Table},
+        test_utils::test_block,
+    };
 
-        // insert entry to state root index table
-        let state_root_index_table = StateRootIndexTable {
-            db: self.db.clone(),
-        };
-        state_root_index_table.insert(value.message.state_root, block_root)?;
+    #[test]
+    fn test_insert_indexes_message_root() {
+        let db = ReamDB::in_memory();
+        let block = test_block(3, B256::ZERO);
+        let block_root = block.message.block_root();
+        db.beacon_block_provider()
+            .insert(block_root, block.clone())
+            .unwrap();
 
-        let parent_root_index_table = ParentRootIndexMultimapTable {
-            db: self.db.clone(),
-        };
-        parent_root_index_table.insert(value.message.parent_root, block_root)?;
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(BEACON_BLOCK_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        let indexed_root = db.slot_index_provider().get(3).unwrap().unwrap();
+        assert_eq!(indexed_root, block_root);
+        assert_eq!(
+            db.beacon_block_provider().get(indexed_root).unwrap(),
+            Some(block.clone())
+        );
+        assert_eq!(
+            db.state_root_index_provider()
+                .get(block.message.state_root)
+                .unwrap(),
+            Some(block_root)
+        );
+        assert_eq!(
+            db.parent_root_index_multimap_provider()
+                .get(B256::ZERO)
+                .unwrap(),
+            Some(vec![block_root])
+        );
     }
 }
@@ -2,10 +2,9 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus::electra::beacon_state::BeaconState;
-use redb::{Database, Durability, ReadableTable, TableDefinition};
 
-use super::{SSZEncoding, Table};
-use crate::errors::StoreError;
+use super::{SSZEncoding, Table, TableDefinition};
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the Beacon State table
 ///
@@ -15,7 +14,7 @@ pub const BEACON_STATE_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<Bea
     TableDefinition::new("beacon_state");
 
 pub struct BeaconStateTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl Table for BeaconStateTable {
@@ -24,30 +23,18 @@ impl Table for BeaconStateTable {
     type Value = BeaconState;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(BEACON_STATE_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        BEACON_STATE_TABLE.get(&*self.db, &key)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(BEACON_STATE_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        BEACON_STATE_TABLE.insert(&*self.db, &key, &value)
     }
 }
 
 impl BeaconStateTable {
     pub fn first(&self) -> Result<Option<BeaconState>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(BEACON_STATE_TABLE)?;
-        let result = table.first()?;
-        Ok(result.map(|res| res.1.value()))
+        Ok(BEACON_STATE_TABLE
+            .first(&*self.db)?
+            .map(|(_, beacon_state)| beacon_state))
     }
 }
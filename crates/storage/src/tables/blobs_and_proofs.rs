@@ -3,10 +3,9 @@ use std::sync::Arc;
 use ream_consensus::{
     blob_sidecar::BlobIdentifier, execution_engine::rpc_types::get_blobs::BlobAndProofV1,
 };
-use redb::{Database, Durability, TableDefinition};
 
-use super::{SSZEncoding, Table};
-use crate::errors::StoreError;
+use super::{SSZEncoding, Table, TableDefinition};
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the Blobs And Proofs table
 ///
@@ -18,7 +17,7 @@ pub const BLOBS_AND_PROOFS_TABLE: TableDefinition<
 > = TableDefinition::new("blobs_and_proofs");
 
 pub struct BlobsAndProofsTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl Table for BlobsAndProofsTable {
@@ -27,20 +26,10 @@ impl Table for BlobsAndProofsTable {
     type Value = BlobAndProofV1;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(BLOBS_AND_PROOFS_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        BLOBS_AND_PROOFS_TABLE.get(&*self.db, &key)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(BLOBS_AND_PROOFS_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        BLOBS_AND_PROOFS_TABLE.insert(&*self.db, &key, &value)
     }
 }
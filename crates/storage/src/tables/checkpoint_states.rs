@@ -1,10 +1,9 @@
 use std::sync::Arc;
 
 use ream_consensus::{checkpoint::Checkpoint, electra::beacon_state::BeaconState};
-use redb::{Database, Durability, TableDefinition};
 
-use super::{SSZEncoding, Table};
-use crate::errors::StoreError;
+use super::{SSZEncoding, Table, TableDefinition};
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the Checkpoint States table
 ///
@@ -16,7 +15,7 @@ pub const CHECKPOINT_STATES_TABLE: TableDefinition<
 > = TableDefinition::new("checkpoint_states");
 
 pub struct CheckpointStatesTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl Table for CheckpointStatesTable {
@@ -25,20 +24,10 @@ impl Table for CheckpointStatesTable {
     type Value = BeaconState;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(CHECKPOINT_STATES_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        CHECKPOINT_STATES_TABLE.get(&*self.db, &key)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(CHECKPOINT_STATES_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        CHECKPOINT_STATES_TABLE.insert(&*self.db, &key, &value)
     }
 }
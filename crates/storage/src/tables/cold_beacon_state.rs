@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use ream_consensus::electra::beacon_state::BeaconState;
+
+use super::{SSZEncoding, Table, TableDefinition};
+use crate::{backend::KeyValueBackend, errors::StoreError};
+
+/// Table definition for the Cold Beacon State table, holding snapshots of finalized states
+///
+/// Key: slot
+/// Value: BeaconState
+pub const COLD_BEACON_STATE_TABLE: TableDefinition<u64, SSZEncoding<BeaconState>> =
+    TableDefinition::new("cold_beacon_state");
+
+pub struct ColdBeaconStateTable {
+    pub db: Arc<dyn KeyValueBackend>,
+}
+
+impl Table for ColdBeaconStateTable {
+    type Key = u64;
+
+    type Value = BeaconState;
+
+    fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
+        COLD_BEACON_STATE_TABLE.get(&*self.db, &key)
+    }
+
+    fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
+        COLD_BEACON_STATE_TABLE.insert(&*self.db, &key, &value)
+    }
+}
+
+impl ColdBeaconStateTable {
+    /// Returns the most recent snapshot at or before `slot`.
+    pub fn get_at_or_before(&self, slot: u64) -> Result<Option<BeaconState>, StoreError> {
+        Ok(COLD_BEACON_STATE_TABLE
+            .last_at_or_before(&*self.db, &slot)?
+            .map(|(_, beacon_state)| beacon_state))
+    }
+
+    /// Returns the slot of the most recent snapshot, without decoding it.
+    pub fn get_highest_slot(&self) -> Result<Option<u64>, StoreError> {
+        COLD_BEACON_STATE_TABLE.last_key(&*self.db)
+    }
+}
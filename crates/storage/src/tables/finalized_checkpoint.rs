@@ -1,43 +1,30 @@
 use std::sync::Arc;
 
 use ream_consensus::checkpoint::Checkpoint;
-use redb::{Database, Durability, TableDefinition};
 
-use super::{Field, SSZEncoding};
-use crate::errors::StoreError;
+use super::{Field, FieldDefinition, SSZEncoding};
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the Finalized_Checkpoint table
 ///
 /// Value: Checkpoint
-pub const FINALIZED_CHECKPOINT_FIELD: TableDefinition<&str, SSZEncoding<Checkpoint>> =
-    TableDefinition::new("finalized_checkpoint");
+pub const FINALIZED_CHECKPOINT_FIELD: FieldDefinition<SSZEncoding<Checkpoint>> =
+    FieldDefinition::new("finalized_checkpoint", FINALIZED_CHECKPOINT_FIELD_KEY);
 
 pub const FINALIZED_CHECKPOINT_FIELD_KEY: &str = "finalized_checkpoint_key";
 
 pub struct FinalizedCheckpointField {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl Field for FinalizedCheckpointField {
     type Value = Checkpoint;
 
     fn get(&self) -> Result<Checkpoint, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(FINALIZED_CHECKPOINT_FIELD)?;
-        let result = table
-            .get(FINALIZED_CHECKPOINT_FIELD_KEY)?
-            .ok_or(StoreError::FieldNotInitilized)?;
-        Ok(result.value())
+        FINALIZED_CHECKPOINT_FIELD.get(&*self.db)
     }
 
     fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(FINALIZED_CHECKPOINT_FIELD)?;
-        table.insert(FINALIZED_CHECKPOINT_FIELD_KEY, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        FINALIZED_CHECKPOINT_FIELD.insert(&*self.db, &value)
     }
 }
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use super::{Field, FieldDefinition};
+use crate::{backend::KeyValueBackend, errors::StoreError};
+
+/// Table definition for the Freezer Slot table, the slot of the finalized block the hot states
+/// before it were last migrated to the cold store for
+///
+/// Value: u64
+pub const FREEZER_SLOT_FIELD: FieldDefinition<u64> =
+    FieldDefinition::new("freezer_slot", FREEZER_SLOT_KEY);
+
+pub const FREEZER_SLOT_KEY: &str = "freezer_slot_key";
+
+pub struct FreezerSlotField {
+    pub db: Arc<dyn KeyValueBackend>,
+}
+
+impl Field for FreezerSlotField {
+    type Value = u64;
+
+    fn get(&self) -> Result<u64, StoreError> {
+        FREEZER_SLOT_FIELD.get(&*self.db)
+    }
+
+    fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
+        FREEZER_SLOT_FIELD.insert(&*self.db, &value)
+    }
+}
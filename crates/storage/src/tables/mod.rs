@@ -3,13 +3,16 @@ pub mod beacon_state;
 pub mod blobs_and_proofs;
 pub mod block_timeliness;
 pub mod checkpoint_states;
+pub mod cold_beacon_state;
 pub mod equivocating_indices;
 pub mod finalized_checkpoint;
+pub mod freezer_slot;
 pub mod genesis_time;
 pub mod justified_checkpoint;
 pub mod latest_messages;
 pub mod parent_root_index;
 pub mod proposer_boost_root;
+pub mod schema_version;
 pub mod slot_index;
 pub mod state_root_index;
 pub mod time;
@@ -17,12 +20,14 @@ pub mod unrealized_finalized_checkpoint;
 pub mod unrealized_justifications;
 pub mod unrealized_justified_checkpoint;
 
-use std::{any::type_name, fmt::Debug};
+use std::{any::type_name, marker::PhantomData, ops::Bound};
 
-use redb::{Key, TypeName, Value};
 use ssz::{Decode, Encode};
 
-use crate::errors::StoreError;
+use crate::{
+    backend::{ALL_KEYS, KeyValueBackend, WriteOp},
+    errors::StoreError,
+};
 
 pub trait Table {
     type Key;
@@ -56,53 +61,282 @@ pub trait Field {
     fn insert(&self, value: Self::Value) -> Result<(), StoreError>;
 }
 
+/// How the keys and values of a table are turned into the bytes stored in the backend
+pub trait Encoding {
+    type Type;
+
+    fn encode(value: &Self::Type) -> Vec<u8>;
+
+    fn decode(bytes: &[u8]) -> Result<Self::Type, StoreError>;
+}
+
+/// Big-endian, so that keys are ordered numerically
+impl Encoding for u64 {
+    type Type = u64;
+
+    fn encode(value: &u64) -> Vec<u8> {
+        value.to_be_bytes().to_vec()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<u64, StoreError> {
+        let bytes: [u8; 8] = bytes
+            .try_into()
+            .map_err(|_| StoreError::Decode(format!("Invalid u64 length {}", bytes.len())))?;
+        Ok(u64::from_be_bytes(bytes))
+    }
+}
+
 /// Wrapper type to handle keys and values using SSZ encoding
 #[derive(Debug)]
 pub struct SSZEncoding<T>(pub T);
 
-impl<T> Key for SSZEncoding<T>
+impl<T> Encoding for SSZEncoding<T>
 where
-    T: Debug + Encode + Decode + Ord,
+    T: Encode + Decode,
 {
-    fn compare(data1: &[u8], data2: &[u8]) -> std::cmp::Ordering {
-        Self::from_bytes(data1).cmp(&Self::from_bytes(data2))
+    type Type = T;
+
+    fn encode(value: &T) -> Vec<u8> {
+        value.as_ssz_bytes()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<T, StoreError> {
+        T::from_ssz_bytes(bytes)
+            .map_err(|err| StoreError::Decode(format!("{}: {err:?}", type_name::<T>())))
     }
 }
 
-impl<T> Value for SSZEncoding<T>
-where
-    T: Debug + Encode + Decode,
-{
-    type SelfType<'a>
-        = T
-    where
-        Self: 'a;
+/// A table mapping keys to values, encoded with `K` and `V`
+pub struct TableDefinition<K, V> {
+    name: &'static str,
+    _encodings: PhantomData<fn() -> (K, V)>,
+}
 
-    type AsBytes<'a>
-        = Vec<u8>
-    where
-        Self: 'a;
+impl<K, V> TableDefinition<K, V> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _encodings: PhantomData,
+        }
+    }
 
-    fn fixed_width() -> Option<usize> {
-        None
+    pub const fn name(&self) -> &'static str {
+        self.name
     }
+}
 
-    fn from_bytes<'a>(data: &'a [u8]) -> Self::SelfType<'a>
-    where
-        Self: 'a,
-    {
-        Self::SelfType::from_ssz_bytes(data).expect("Failed to decode SSZ bytes, data corruption?")
+impl<K: Encoding, V: Encoding> TableDefinition<K, V> {
+    pub fn get(
+        &self,
+        backend: &dyn KeyValueBackend,
+        key: &K::Type,
+    ) -> Result<Option<V::Type>, StoreError> {
+        backend
+            .get(self.name, &K::encode(key))?
+            .map(|value| V::decode(&value))
+            .transpose()
     }
 
-    fn as_bytes<'a, 'b: 'a>(value: &'a Self::SelfType<'b>) -> Self::AsBytes<'a>
-    where
-        Self: 'a,
-        Self: 'b,
-    {
-        value.as_ssz_bytes()
+    /// Checks for `key` without decoding its value.
+    pub fn contains_key(
+        &self,
+        backend: &dyn KeyValueBackend,
+        key: &K::Type,
+    ) -> Result<bool, StoreError> {
+        Ok(backend.get(self.name, &K::encode(key))?.is_some())
+    }
+
+    pub fn first(
+        &self,
+        backend: &dyn KeyValueBackend,
+    ) -> Result<Option<(K::Type, V::Type)>, StoreError> {
+        backend
+            .first(self.name, ALL_KEYS)?
+            .map(|(key, value)| Ok((K::decode(&key)?, V::decode(&value)?)))
+            .transpose()
+    }
+
+    pub fn last(
+        &self,
+        backend: &dyn KeyValueBackend,
+    ) -> Result<Option<(K::Type, V::Type)>, StoreError> {
+        backend
+            .last(self.name, ALL_KEYS)?
+            .map(|(key, value)| Ok((K::decode(&key)?, V::decode(&value)?)))
+            .transpose()
+    }
+
+    /// Returns the highest key without reading its value.
+    pub fn last_key(&self, backend: &dyn KeyValueBackend) -> Result<Option<K::Type>, StoreError> {
+        backend
+            .last_key(self.name, ALL_KEYS)?
+            .map(|key| K::decode(&key))
+            .transpose()
+    }
+
+    /// Returns the entry with the highest key at or before `key`, in the order of the encoded
+    /// keys.
+    pub fn last_at_or_before(
+        &self,
+        backend: &dyn KeyValueBackend,
+        key: &K::Type,
+    ) -> Result<Option<(K::Type, V::Type)>, StoreError> {
+        backend
+            .last(
+                self.name,
+                (Bound::Unbounded, Bound::Included(&K::encode(key))),
+            )?
+            .map(|(key, value)| Ok((K::decode(&key)?, V::decode(&value)?)))
+            .transpose()
+    }
+
+    pub fn keys(&self, backend: &dyn KeyValueBackend) -> Result<Vec<K::Type>, StoreError> {
+        backend
+            .keys(self.name)?
+            .iter()
+            .map(|key| K::decode(key))
+            .collect()
+    }
+
+    pub fn entries(
+        &self,
+        backend: &dyn KeyValueBackend,
+    ) -> Result<Vec<(K::Type, V::Type)>, StoreError> {
+        backend
+            .entries(self.name)?
+            .iter()
+            .map(|(key, value)| Ok((K::decode(key)?, V::decode(value)?)))
+            .collect()
+    }
+
+    pub fn insert(
+        &self,
+        backend: &dyn KeyValueBackend,
+        key: &K::Type,
+        value: &V::Type,
+    ) -> Result<(), StoreError> {
+        backend.write(vec![self.insert_op(key, value)])
+    }
+
+    pub fn insert_op(&self, key: &K::Type, value: &V::Type) -> WriteOp {
+        WriteOp::Put {
+            table: self.name,
+            key: K::encode(key),
+            value: V::encode(value),
+        }
+    }
+
+    pub fn remove_op(&self, key: &K::Type) -> WriteOp {
+        WriteOp::Delete {
+            table: self.name,
+            key: K::encode(key),
+        }
+    }
+}
+
+/// A table mapping keys to sets of values, encoded with `K` and `V`
+pub struct MultimapTableDefinition<K, V> {
+    name: &'static str,
+    _encodings: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> MultimapTableDefinition<K, V> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _encodings: PhantomData,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<K: Encoding, V: Encoding> MultimapTableDefinition<K, V> {
+    pub fn get(
+        &self,
+        backend: &dyn KeyValueBackend,
+        key: &K::Type,
+    ) -> Result<Vec<V::Type>, StoreError> {
+        backend
+            .multimap_get(self.name, &K::encode(key))?
+            .iter()
+            .map(|value| V::decode(value))
+            .collect()
+    }
+
+    pub fn insert(
+        &self,
+        backend: &dyn KeyValueBackend,
+        key: &K::Type,
+        value: &V::Type,
+    ) -> Result<(), StoreError> {
+        backend.write(vec![self.insert_op(key, value)])
+    }
+
+    pub fn insert_op(&self, key: &K::Type, value: &V::Type) -> WriteOp {
+        WriteOp::MultimapInsert {
+            table: self.name,
+            key: K::encode(key),
+            value: V::encode(value),
+        }
+    }
+
+    pub fn remove_op(&self, key: &K::Type, value: &V::Type) -> WriteOp {
+        WriteOp::MultimapRemove {
+            table: self.name,
+            key: K::encode(key),
+            value: V::encode(value),
+        }
+    }
+
+    pub fn remove_all_op(&self, key: &K::Type) -> WriteOp {
+        WriteOp::MultimapRemoveAll {
+            table: self.name,
+            key: K::encode(key),
+        }
+    }
+}
+
+/// A table holding a single value, encoded with `V`, under a fixed key
+pub struct FieldDefinition<V> {
+    name: &'static str,
+    key: &'static str,
+    _encoding: PhantomData<fn() -> V>,
+}
+
+impl<V> FieldDefinition<V> {
+    pub const fn new(name: &'static str, key: &'static str) -> Self {
+        Self {
+            name,
+            key,
+            _encoding: PhantomData,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<V: Encoding> FieldDefinition<V> {
+    pub fn get(&self, backend: &dyn KeyValueBackend) -> Result<V::Type, StoreError> {
+        let value = backend
+            .get(self.name, self.key.as_bytes())?
+            .ok_or(StoreError::FieldNotInitilized)?;
+        V::decode(&value)
+    }
+
+    pub fn insert(&self, backend: &dyn KeyValueBackend, value: &V::Type) -> Result<(), StoreError> {
+        backend.write(vec![self.insert_op(value)])
     }
 
-    fn type_name() -> TypeName {
-        TypeName::new(&format!("SSZEncoding<{}>", type_name::<T>()))
+    pub fn insert_op(&self, value: &V::Type) -> WriteOp {
+        WriteOp::Put {
+            table: self.name,
+            key: self.key.as_bytes().to_vec(),
+            value: V::encode(value),
+        }
     }
 }
@@ -1,10 +1,9 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use redb::{Database, Durability, MultimapTableDefinition};
 
-use super::{MultimapTable, SSZEncoding};
-use crate::errors::StoreError;
+use super::{MultimapTable, MultimapTableDefinition, SSZEncoding};
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the Parent Root Index Multimap table
 ///
@@ -16,7 +15,7 @@ pub const PARENT_ROOT_INDEX_MULTIMAP_TABLE: MultimapTableDefinition<
 > = MultimapTableDefinition::new("parent_root_index_multimap");
 
 pub struct ParentRootIndexMultimapTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl MultimapTable for ParentRootIndexMultimapTable {
@@ -27,24 +26,10 @@ impl MultimapTable for ParentRootIndexMultimapTable {
     type InsertValue = B256;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::GetValue>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
-        let result = table.get(key)?;
-        let mut values = vec![];
-        for value in result {
-            values.push(value?.value());
-        }
-        Ok(Some(values))
+        Ok(Some(PARENT_ROOT_INDEX_MULTIMAP_TABLE.get(&*self.db, &key)?))
     }
 
     fn insert(&self, key: Self::Key, value: Self::InsertValue) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        PARENT_ROOT_INDEX_MULTIMAP_TABLE.insert(&*self.db, &key, &value)
     }
 }
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use super::{Field, FieldDefinition};
+use crate::{backend::KeyValueBackend, errors::StoreError};
+
+/// Table definition for the Metadata table, holding the schema version of the database
+///
+/// Value: u64
+pub const SCHEMA_VERSION_FIELD: FieldDefinition<u64> =
+    FieldDefinition::new("metadata", SCHEMA_VERSION_KEY);
+
+pub const SCHEMA_VERSION_KEY: &str = "schema_version_key";
+
+pub struct SchemaVersionField {
+    pub db: Arc<dyn KeyValueBackend>,
+}
+
+impl Field for SchemaVersionField {
+    type Value = u64;
+
+    fn get(&self) -> Result<u64, StoreError> {
+        SCHEMA_VERSION_FIELD.get(&*self.db)
+    }
+
+    fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
+        SCHEMA_VERSION_FIELD.insert(&*self.db, &value)
+    }
+}
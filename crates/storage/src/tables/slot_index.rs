@@ -1,10 +1,9 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use redb::{Database, Durability, ReadableTable, TableDefinition};
 
-use super::{SSZEncoding, Table};
-use crate::errors::StoreError;
+use super::{SSZEncoding, Table, TableDefinition};
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the Slot Index table
 ///
@@ -14,7 +13,7 @@ pub const SLOT_INDEX_TABLE: TableDefinition<u64, SSZEncoding<B256>> =
     TableDefinition::new("slot_index");
 
 pub struct SlotIndexTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl Table for SlotIndexTable {
@@ -23,28 +22,20 @@ impl Table for SlotIndexTable {
     type Value = B256;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(SLOT_INDEX_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        SLOT_INDEX_TABLE.get(&*self.db, &key)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(SLOT_INDEX_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        SLOT_INDEX_TABLE.insert(&*self.db, &key, &value)
     }
 }
 
 impl SlotIndexTable {
     pub fn get_highest_slot(&self) -> Result<Option<u64>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(SLOT_INDEX_TABLE)?;
-        Ok(table.last()?.map(|result| result.0.value()))
+        SLOT_INDEX_TABLE.last_key(&*self.db)
+    }
+
+    pub fn get_oldest_slot(&self) -> Result<Option<u64>, StoreError> {
+        Ok(SLOT_INDEX_TABLE.first(&*self.db)?.map(|(slot, _)| slot))
     }
 }
@@ -1,10 +1,9 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use redb::{Database, Durability, TableDefinition};
 
-use super::{SSZEncoding, Table};
-use crate::errors::StoreError;
+use super::{SSZEncoding, Table, TableDefinition};
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the State Root Index table
 ///
@@ -14,7 +13,7 @@ pub const STATE_ROOT_INDEX_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding
     TableDefinition::new("state_root_index");
 
 pub struct StateRootIndexTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl Table for StateRootIndexTable {
@@ -23,20 +22,10 @@ impl Table for StateRootIndexTable {
     type Value = B256;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(STATE_ROOT_INDEX_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        STATE_ROOT_INDEX_TABLE.get(&*self.db, &key)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(STATE_ROOT_INDEX_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        STATE_ROOT_INDEX_TABLE.insert(&*self.db, &key, &value)
     }
 }
@@ -2,10 +2,9 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus::checkpoint::Checkpoint;
-use redb::{Database, Durability, TableDefinition};
 
-use super::{SSZEncoding, Table};
-use crate::errors::StoreError;
+use super::{SSZEncoding, Table, TableDefinition};
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the Unrealized Justifications table
 ///
@@ -17,7 +16,7 @@ pub const UNREALIZED_JUSTIFICATIONS_TABLE: TableDefinition<
 > = TableDefinition::new("unrealized_justifications");
 
 pub struct UnrealizedJustificationsTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl Table for UnrealizedJustificationsTable {
@@ -26,20 +25,10 @@ impl Table for UnrealizedJustificationsTable {
     type Value = Checkpoint;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(UNREALIZED_JUSTIFICATIONS_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        UNREALIZED_JUSTIFICATIONS_TABLE.get(&*self.db, &key)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(UNREALIZED_JUSTIFICATIONS_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        UNREALIZED_JUSTIFICATIONS_TABLE.insert(&*self.db, &key, &value)
     }
 }
@@ -1,43 +1,33 @@
 use std::sync::Arc;
 
 use ream_consensus::checkpoint::Checkpoint;
-use redb::{Database, Durability, TableDefinition};
 
-use super::{Field, SSZEncoding};
-use crate::errors::StoreError;
+use super::{Field, FieldDefinition, SSZEncoding};
+use crate::{backend::KeyValueBackend, errors::StoreError};
 
 /// Table definition for the Unrealized_Justified_Checkpoint table
 ///
 /// Value: Checkpoint
-pub const UNREALIZED_JUSTIFED_CHECKPOINT_FIELD: TableDefinition<&str, SSZEncoding<Checkpoint>> =
-    TableDefinition::new("unrealized_justified_checkpoint");
+pub const UNREALIZED_JUSTIFED_CHECKPOINT_FIELD: FieldDefinition<SSZEncoding<Checkpoint>> =
+    FieldDefinition::new(
+        "unrealized_justified_checkpoint",
+        UNREALIZED_JUSTIFED_CHECKPOINT_KEY,
+    );
 
 pub const UNREALIZED_JUSTIFED_CHECKPOINT_KEY: &str = "unrealized_justified_checkpoint_key";
 
 pub struct UnrealizedJustifiedCheckpointField {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn KeyValueBackend>,
 }
 
 impl Field for UnrealizedJustifiedCheckpointField {
     type Value = Checkpoint;
 
     fn get(&self) -> Result<Checkpoint, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(UNREALIZED_JUSTIFED_CHECKPOINT_FIELD)?;
-        let result = table
-            .get(UNREALIZED_JUSTIFED_CHECKPOINT_KEY)?
-            .ok_or(StoreError::FieldNotInitilized)?;
-        Ok(result.value())
+        UNREALIZED_JUSTIFED_CHECKPOINT_FIELD.get(&*self.db)
     }
 
     fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(UNREALIZED_JUSTIFED_CHECKPOINT_FIELD)?;
-        table.insert(UNREALIZED_JUSTIFED_CHECKPOINT_KEY, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        UNREALIZED_JUSTIFED_CHECKPOINT_FIELD.insert(&*self.db, &value)
     }
 }
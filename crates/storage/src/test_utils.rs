@@ -0,0 +1,307 @@
+//! Blocks and states for the tests of the storage and of the crates built on it.
+
+use std::sync::Arc;
+
+use alloy_primitives::{Address, B256, U256, aliases::B32, hex, keccak256};
+use ream_bls::{BLSSignature, PrivateKey, PubKey, traits::Signable};
+use ream_consensus::{
+    beacon_block_header::BeaconBlockHeader,
+    checkpoint::Checkpoint,
+    constants::{
+        DOMAIN_BEACON_PROPOSER, DOMAIN_RANDAO, FAR_FUTURE_EPOCH, MAX_EFFECTIVE_BALANCE_ELECTRA,
+    },
+    electra::{
+        beacon_block::{BeaconBlock, SignedBeaconBlock},
+        beacon_block_body::BeaconBlockBody,
+        beacon_state::BeaconState,
+        execution_payload::ExecutionPayload,
+        execution_payload_header::ExecutionPayloadHeader,
+    },
+    eth_1_data::Eth1Data,
+    execution_engine::mock_engine::MockExecutionEngine,
+    execution_requests::ExecutionRequests,
+    fork::Fork,
+    misc::compute_signing_root,
+    sync_aggregate::SyncAggregate,
+    sync_committee::SyncCommittee,
+    validator::Validator,
+};
+use ssz_types::{BitVector, FixedVector, VariableList};
+use tree_hash::TreeHash;
+
+use crate::{db::ReamDB, tables::Table};
+
+/// Imports a chain of blocks at `slots` on top of `parent_root` with their states, returning
+/// the roots they are keyed by.
+pub fn import_test_chain(
+    db: &ReamDB,
+    parent_root: B256,
+    slots: impl IntoIterator<Item = u64>,
+) -> Vec<B256> {
+    import_test_fork(db, parent_root, slots, B256::ZERO)
+}
+
+/// Like [`import_test_chain`], with blocks carrying `graffiti` so that they differ from the
+/// blocks of other chains at the same slots on top of the same parent.
+pub fn import_test_fork(
+    db: &ReamDB,
+    mut parent_root: B256,
+    slots: impl IntoIterator<Item = u64>,
+    graffiti: B256,
+) -> Vec<B256> {
+    let mut roots = vec![];
+    for slot in slots {
+        let block = test_fork_block(slot, parent_root, graffiti);
+        let block_root = block.message.block_root();
+        db.beacon_state_provider()
+            .insert(block_root, test_state(&block))
+            .unwrap();
+        db.beacon_block_provider()
+            .insert(block_root, block)
+            .unwrap();
+        roots.push(block_root);
+        parent_root = block_root;
+    }
+    roots
+}
+
+/// The compressed generator of G1, the public key of the secret key 1
+const G1_GENERATOR: [u8; 48] = hex!(
+    "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+);
+
+/// The secret key of the single validator of [`import_valid_test_chain`].
+pub fn test_private_key() -> PrivateKey {
+    // Secret keys are encoded little-endian
+    PrivateKey {
+        inner: B256::right_padding_from(&[1]),
+    }
+}
+
+/// Imports a genesis block and blocks at slots `1..=slots` on top of it with their states,
+/// returning their roots. Unlike the blocks of [`import_test_chain`], these pass the state
+/// transition of their single validator and are signed by it, so their states can be rebuilt by
+/// replaying them.
+pub async fn import_valid_test_chain(db: &ReamDB, slots: u64) -> Vec<B256> {
+    let private_key = test_private_key();
+    let pubkey = PubKey {
+        inner: FixedVector::from(G1_GENERATOR.to_vec()),
+    };
+
+    let mut block = test_block(0, B256::ZERO);
+    let mut state = test_state(&block);
+    state.validators = VariableList::from(vec![Validator {
+        pubkey: pubkey.clone(),
+        withdrawal_credentials: B256::ZERO,
+        effective_balance: MAX_EFFECTIVE_BALANCE_ELECTRA,
+        slashed: false,
+        activation_eligibility_epoch: 0,
+        activation_epoch: 0,
+        exit_epoch: FAR_FUTURE_EPOCH,
+        withdrawable_epoch: FAR_FUTURE_EPOCH,
+    }]);
+    state.balances = VariableList::from(vec![MAX_EFFECTIVE_BALANCE_ELECTRA]);
+    let sync_committee = Arc::new(SyncCommittee {
+        pubkeys: FixedVector::from_elem(pubkey.clone()),
+        aggregate_pubkey: pubkey,
+    });
+    state.current_sync_committee = sync_committee.clone();
+    state.next_sync_committee = sync_committee;
+    block.message.state_root = state.tree_hash_root();
+
+    let mut roots = vec![];
+    for slot in 0..=slots {
+        if slot > 0 {
+            let mut pre_state = state.clone();
+            pre_state.process_slots(slot).unwrap();
+            let epoch = pre_state.get_current_epoch();
+
+            block = test_block(slot, pre_state.latest_block_header.tree_hash_root());
+            let body = &mut block.message.body;
+            body.randao_reveal = private_key
+                .sign(
+                    compute_signing_root(epoch, pre_state.get_domain(DOMAIN_RANDAO, Some(epoch)))
+                        .as_slice(),
+                )
+                .unwrap();
+            body.sync_aggregate.sync_committee_signature = BLSSignature::infinity();
+            body.execution_payload.prev_randao = pre_state.get_randao_mix(epoch);
+            body.execution_payload.timestamp = pre_state.compute_timestamp_at_slot(slot);
+
+            state
+                .state_transition(&block, false, &None::<MockExecutionEngine>)
+                .await
+                .unwrap();
+            block.message.state_root = state.tree_hash_root();
+            block.signature = private_key
+                .sign(
+                    compute_signing_root(
+                        block.message.clone(),
+                        state.get_domain(DOMAIN_BEACON_PROPOSER, None),
+                    )
+                    .as_slice(),
+                )
+                .unwrap();
+        }
+
+        let block_root = block.message.block_root();
+        db.beacon_state_provider()
+            .insert(block_root, state.clone())
+            .unwrap();
+        db.beacon_block_provider()
+            .insert(block_root, block.clone())
+            .unwrap();
+        roots.push(block_root);
+    }
+    roots
+}
+
+/// An empty block at `slot` on top of `parent_root`. The state root only has to be unique, as
+/// it is indexed.
+pub fn test_block(slot: u64, parent_root: B256) -> SignedBeaconBlock {
+    test_fork_block(slot, parent_root, B256::ZERO)
+}
+
+fn test_fork_block(slot: u64, parent_root: B256, graffiti: B256) -> SignedBeaconBlock {
+    SignedBeaconBlock {
+        message: BeaconBlock {
+            slot,
+            proposer_index: 0,
+            parent_root,
+            state_root: keccak256(
+                [
+                    parent_root.as_slice(),
+                    &slot.to_le_bytes(),
+                    graffiti.as_slice(),
+                ]
+                .concat(),
+            ),
+            body: BeaconBlockBody {
+                randao_reveal: Default::default(),
+                eth1_data: test_eth1_data(),
+                graffiti,
+                proposer_slashings: VariableList::default(),
+                attester_slashings: VariableList::default(),
+                attestations: VariableList::default(),
+                deposits: VariableList::default(),
+                voluntary_exits: VariableList::default(),
+                sync_aggregate: SyncAggregate {
+                    sync_committee_bits: BitVector::new(),
+                    sync_committee_signature: Default::default(),
+                },
+                execution_payload: ExecutionPayload {
+                    parent_hash: B256::ZERO,
+                    fee_recipient: Address::ZERO,
+                    state_root: B256::ZERO,
+                    receipts_root: B256::ZERO,
+                    logs_bloom: FixedVector::default(),
+                    prev_randao: B256::ZERO,
+                    block_number: 0,
+                    gas_limit: 0,
+                    gas_used: 0,
+                    timestamp: 0,
+                    extra_data: VariableList::default(),
+                    base_fee_per_gas: U256::ZERO,
+                    block_hash: B256::ZERO,
+                    transactions: VariableList::default(),
+                    withdrawals: VariableList::default(),
+                    blob_gas_used: 0,
+                    excess_blob_gas: 0,
+                },
+                bls_to_execution_changes: VariableList::default(),
+                blob_kzg_commitments: VariableList::default(),
+                execution_requests: ExecutionRequests {
+                    deposits: VariableList::default(),
+                    withdrawals: VariableList::default(),
+                    consolidations: VariableList::default(),
+                },
+            },
+        },
+        signature: Default::default(),
+    }
+}
+
+/// The post-state of `block`, with an empty validator set. Only the slot and the latest block
+/// header are meaningful.
+pub fn test_state(block: &SignedBeaconBlock) -> BeaconState {
+    let sync_committee = Arc::new(SyncCommittee {
+        pubkeys: FixedVector::default(),
+        aggregate_pubkey: Default::default(),
+    });
+
+    BeaconState {
+        genesis_time: 0,
+        genesis_validators_root: B256::ZERO,
+        slot: block.message.slot,
+        fork: Fork {
+            previous_version: B32::ZERO,
+            current_version: B32::ZERO,
+            epoch: 0,
+        },
+        latest_block_header: BeaconBlockHeader {
+            slot: block.message.slot,
+            proposer_index: block.message.proposer_index,
+            parent_root: block.message.parent_root,
+            state_root: B256::ZERO,
+            body_root: block.message.body.tree_hash_root(),
+        },
+        block_roots: FixedVector::default(),
+        state_roots: FixedVector::default(),
+        historical_roots: VariableList::default(),
+        eth1_data: test_eth1_data(),
+        eth1_data_votes: VariableList::default(),
+        eth1_deposit_index: 0,
+        validators: VariableList::default(),
+        balances: VariableList::default(),
+        randao_mixes: FixedVector::default(),
+        slashings: FixedVector::default(),
+        previous_epoch_participation: VariableList::default(),
+        current_epoch_participation: VariableList::default(),
+        justification_bits: BitVector::new(),
+        previous_justified_checkpoint: Checkpoint::default(),
+        current_justified_checkpoint: Checkpoint::default(),
+        finalized_checkpoint: Checkpoint::default(),
+        inactivity_scores: VariableList::default(),
+        current_sync_committee: sync_committee.clone(),
+        next_sync_committee: sync_committee,
+        latest_execution_payload_header: ExecutionPayloadHeader {
+            parent_hash: B256::ZERO,
+            fee_recipient: Address::ZERO,
+            state_root: B256::ZERO,
+            receipts_root: B256::ZERO,
+            logs_bloom: FixedVector::default(),
+            prev_randao: B256::ZERO,
+            block_number: 0,
+            gas_limit: 0,
+            gas_used: 0,
+            timestamp: 0,
+            extra_data: VariableList::default(),
+            base_fee_per_gas: U256::ZERO,
+            block_hash: B256::ZERO,
+            transactions_root: B256::ZERO,
+            withdrawals_root: B256::ZERO,
+            blob_gas_used: 0,
+            excess_blob_gas: 0,
+        },
+        next_withdrawal_index: 0,
+        next_withdrawal_validator_index: 0,
+        historical_summaries: VariableList::default(),
+        deposit_requests_start_index: 0,
+        deposit_balance_to_consume: 0,
+        exit_balance_to_consume: 0,
+        earliest_exit_epoch: 0,
+        consolidation_balance_to_consume: 0,
+        earliest_consolidation_epoch: 0,
+        pending_deposits: VariableList::default(),
+        pending_partial_withdrawals: VariableList::default(),
+        pending_consolidations: VariableList::default(),
+    }
+}
+
+fn test_eth1_data() -> Eth1Data {
+    Eth1Data {
+        deposit_root: B256::ZERO,
+        deposit_count: 0,
+        block_hash: B256::ZERO,
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use crate::{backend::staged::StagedBackend, db::ReamDB, errors::StoreError};
+
+/// Writes to several tables which are committed together.
+///
+/// The writes are made through the providers of [`WriteBatch::db`], which read them back before
+/// they are committed. Dropping the batch without committing discards them.
+pub struct WriteBatch {
+    backend: Arc<StagedBackend>,
+    db: ReamDB,
+}
+
+impl WriteBatch {
+    /// The database with the staged writes applied.
+    pub fn db(&self) -> &ReamDB {
+        &self.db
+    }
+
+    /// Writes all staged writes atomically.
+    pub fn commit(self) -> Result<(), StoreError> {
+        self.backend.commit()
+    }
+}
+
+impl ReamDB {
+    pub fn write_batch(&self) -> WriteBatch {
+        let backend = Arc::new(StagedBackend::new(self.db.clone()));
+        WriteBatch {
+            db: ReamDB::from_backend(backend.clone(), self.slots_per_state_snapshot),
+            backend,
+        }
+    }
+}
@@ -18,7 +18,6 @@ macro_rules! test_fork_choice {
                 use ream_storage::{
                     db::ReamDB,
                     tables::{Table, Field},
-                    dir::setup_data_dir
                 };
                 use rstest::rstest;
                 use serde::Deserialize;
@@ -125,8 +124,7 @@ macro_rules! test_fork_choice {
                             utils::read_ssz_snappy(&case_dir.join("anchor_block.ssz_snappy"))
                                 .expect("Failed to read anchor_block.ssz_snappy");
 
-                        let ream_dir = setup_data_dir("ream", None, true).expect("Failed to create data dir");
-                        let reamdb = ReamDB::new(ream_dir).expect("count not find reabdb");
+                        let reamdb = ReamDB::in_memory();
                         let mut store = get_forkchoice_store(anchor_state, anchor_block, reamdb)
                             .expect("get_forkchoice_store failed");
 